@@ -3,8 +3,13 @@ use std::fs::create_dir_all;
 
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 
-use merkle_drop::msg::{ExecuteMsg, GetRootResponse, GetSubdenomResponse, InstantiateMsg, QueryMsg};
-use merkle_drop::state::Config;
+use merkle_drop::events::MerkleDropEvent;
+use merkle_drop::msg::{
+    ClaimHookExecuteMsg, ExecuteMsg, GetRootResponse, GetSubdenomResponse, GrantStatusResponse,
+    InstantiateMsg, ListHooksResponse, MigrateMsg, QueryMsg, RemoteClaimAck, RemoteClaimPacket,
+    RemoteClaimResult, SudoMsg,
+};
+use merkle_drop::state::{ClaimStats, Config};
 
 fn main() {
     let mut out_dir = current_dir().unwrap();
@@ -15,7 +20,17 @@ fn main() {
     export_schema(&schema_for!(InstantiateMsg), &out_dir);
     export_schema(&schema_for!(ExecuteMsg), &out_dir);
     export_schema(&schema_for!(QueryMsg), &out_dir);
+    export_schema(&schema_for!(SudoMsg), &out_dir);
+    export_schema(&schema_for!(MigrateMsg), &out_dir);
     export_schema(&schema_for!(Config), &out_dir);
+    export_schema(&schema_for!(ClaimStats), &out_dir);
     export_schema(&schema_for!(GetRootResponse), &out_dir);
     export_schema(&schema_for!(GetSubdenomResponse), &out_dir);
+    export_schema(&schema_for!(GrantStatusResponse), &out_dir);
+    export_schema(&schema_for!(MerkleDropEvent), &out_dir);
+    export_schema(&schema_for!(ListHooksResponse), &out_dir);
+    export_schema(&schema_for!(ClaimHookExecuteMsg), &out_dir);
+    export_schema(&schema_for!(RemoteClaimPacket), &out_dir);
+    export_schema(&schema_for!(RemoteClaimAck), &out_dir);
+    export_schema(&schema_for!(RemoteClaimResult), &out_dir);
 }
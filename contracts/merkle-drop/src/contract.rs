@@ -1,17 +1,41 @@
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response, StdResult,
+    to_binary, Binary, Deps, DepsMut, Env, IbcBasicResponse, IbcChannelCloseMsg,
+    IbcChannelConnectMsg, IbcChannelOpenMsg, IbcPacketAckMsg, IbcPacketReceiveMsg,
+    IbcPacketTimeoutMsg, IbcReceiveResponse, MessageInfo, Reply, Response, StdResult,
 };
 use cw2::set_contract_version;
 
+use crate::decay::{query_decay_multiplier, validate_decay};
 use crate::error::ContractError;
-use crate::execute::claim::claim;
+use crate::events::MerkleDropEvent;
+use crate::execute::claim::{claim, ensure_proof_version};
+use crate::execute::claim_fee::validate_claim_fee;
+use crate::execute::denylist::{add_to_denylist, query_denylist, remove_from_denylist};
+use crate::execute::fund::{escrow_asset, fund, receive, withdraw_remaining};
+use crate::execute::hooks::{add_hook, query_hooks, remove_hook};
+use crate::execute::rate_limit::{
+    query_rate_limit_usage, update_rate_limits, validate_rate_limits,
+};
+use crate::execute::referral::{query_referral_earnings, validate_referral};
+use crate::execute::set_denom_metadata::{query_denom_metadata, set_denom_metadata};
 use crate::execute::set_subdenom::set_subdenom;
-use crate::msg::{ExecuteMsg, GetRootResponse, GetSubdenomResponse, InstantiateMsg, QueryMsg};
+use crate::grants::query_grant_status;
+use crate::ibc::ibc_lifecycle_complete;
+use crate::msg::{
+    ExecuteMsg, GetRootResponse, GetSubdenomResponse, GovernanceSudoMsg, IbcHooksSudoMsg,
+    InstantiateMsg, MigrateMsg, QueryMsg, SudoMsg,
+};
+use crate::remote_claim::{ack_error, receive_claim, validate_channel, validate_remote_claims};
 use crate::reply::{
-    handle_mint_reply, handle_send_reply, AUTHZ_EXEC_MINT_MSG_ID, AUTHZ_EXEC_SEND_MSG_ID,
+    handle_hook_reply, handle_ibc_transfer_reply, handle_lock_reply, handle_mint_reply,
+    handle_post_claim_reply, handle_send_reply, handle_set_denom_metadata_reply,
+    AUTHZ_EXEC_LOCK_MSG_ID, AUTHZ_EXEC_MINT_MSG_ID, AUTHZ_EXEC_SEND_MSG_ID,
+    AUTHZ_EXEC_SET_METADATA_MSG_ID, CLAIM_HOOK_MSG_ID, IBC_TRANSFER_MSG_ID, POST_CLAIM_JOIN_MSG_ID,
+    POST_CLAIM_SWAP_MSG_ID,
 };
-use crate::state::{Config, CONFIG, SUBDENOM};
+use crate::state::{ClaimStats, Config, Distribution, CLAIM_STATS, CONFIG, SUBDENOM};
+use crate::sudo::{clawback, set_paused, update_root};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:merkle-drop";
@@ -24,16 +48,69 @@ pub fn instantiate(
     info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
+    let distribution = msg.distribution.unwrap_or_default();
+    if let Distribution::Cw20 { address } = &distribution {
+        deps.api.addr_validate(address.as_str())?;
+    }
+
+    let owner = match msg.owner {
+        Some(owner) => deps.api.addr_validate(&owner)?,
+        None => info.sender.clone(),
+    };
+
+    if let Some(decay) = &msg.decay {
+        validate_decay(decay)?;
+    }
+
+    if let Some(rate_limits) = &msg.rate_limits {
+        validate_rate_limits(rate_limits)?;
+    }
+
+    if let Some(claim_fee) = &msg.claim_fee {
+        validate_claim_fee(deps.api, claim_fee)?;
+    }
+
+    if let Some(referral) = &msg.referral {
+        validate_referral(referral)?;
+    }
+
+    let remote_claim_sources = msg.remote_claim_sources.unwrap_or_default();
+    if !remote_claim_sources.is_empty() {
+        validate_remote_claims(&distribution, &msg.claim_fee)?;
+    }
+
+    let denom = escrow_asset(&distribution).ok();
     let config = Config {
         merkle_root: msg.merkle_root,
-        owner: info.sender.clone(),
+        hash_function: msg.hash_function.unwrap_or_default(),
+        owner,
+        distribution,
+        claim_end: msg.claim_end,
+        total_amount: msg.total_amount,
+        governance_only: msg.governance_only.unwrap_or(false),
+        paused: false,
+        lock_options: msg.lock_options.unwrap_or_default(),
+        join_pool_id: msg.join_pool_id,
+        remote_claim_sources,
+        decay: msg.decay,
+        rate_limits: msg.rate_limits,
+        claim_fee: msg.claim_fee,
+        referral: msg.referral,
     };
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     CONFIG.save(deps.storage, &config)?;
+    CLAIM_STATS.save(deps.storage, &ClaimStats::default())?;
 
     Ok(Response::new()
         .add_attribute("method", "instantiate")
-        .add_attribute("owner", info.sender))
+        .add_event(
+            MerkleDropEvent::ConfigUpdate {
+                action: String::from("instantiate"),
+                sender: info.sender.into_string(),
+                denom,
+            }
+            .into(),
+        ))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -45,32 +122,188 @@ pub fn execute(
 ) -> Result<Response, ContractError> {
     match msg {
         ExecuteMsg::SetSubDenom { subdenom } => set_subdenom(deps, env, info, subdenom),
+        ExecuteMsg::SetDenomMetadata { metadata } => set_denom_metadata(deps, env, info, metadata),
         ExecuteMsg::Claim {
             proof,
+            proof_version,
             amount,
             claimer_addr,
-        } => claim(deps, env, proof, amount, claimer_addr),
+            options,
+        } => {
+            ensure_proof_version(&proof, proof_version.unwrap_or_default())?;
+            claim(
+                deps,
+                env,
+                info,
+                proof,
+                amount,
+                claimer_addr,
+                options.unwrap_or_default(),
+            )
+        }
+        ExecuteMsg::Fund {} => fund(deps, info),
+        ExecuteMsg::WithdrawRemaining { recipient } => {
+            withdraw_remaining(deps, env, info, recipient)
+        }
+        ExecuteMsg::Receive(wrapper) => receive(deps, info, wrapper),
+        ExecuteMsg::AddHook {
+            address,
+            failure_policy,
+        } => add_hook(deps, info, address, failure_policy),
+        ExecuteMsg::RemoveHook { address } => remove_hook(deps, info, address),
+        ExecuteMsg::AddToDenylist { addresses } => add_to_denylist(deps, info, addresses),
+        ExecuteMsg::RemoveFromDenylist { addresses } => remove_from_denylist(deps, info, addresses),
+        ExecuteMsg::UpdateRateLimits { rate_limits } => update_rate_limits(deps, info, rate_limits),
+    }
+}
+
+/// Handling governance intervention and ibc-hooks callbacks, see SudoMsg.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
+    match msg {
+        SudoMsg::Governance(msg) => match msg {
+            GovernanceSudoMsg::Pause {} => set_paused(deps, true),
+            GovernanceSudoMsg::Unpause {} => set_paused(deps, false),
+            GovernanceSudoMsg::UpdateRoot { merkle_root } => update_root(deps, merkle_root),
+            GovernanceSudoMsg::Clawback { recipient } => clawback(deps, env, recipient),
+        },
+        SudoMsg::IbcHooks(IbcHooksSudoMsg::IbcLifecycleComplete(msg)) => {
+            ibc_lifecycle_complete(deps, msg)
+        }
     }
 }
 
+/// Upgrades drops instantiated by earlier versions, whose config lacks the
+/// fields added since and which kept no claim stats.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    // rewrite the config with the defaults of the missing fields.
+    let config = CONFIG.load(deps.storage)?;
+    CONFIG.save(deps.storage, &config)?;
+
+    // claims made before the migration are not counted.
+    if CLAIM_STATS.may_load(deps.storage)?.is_none() {
+        CLAIM_STATS.save(deps.storage, &ClaimStats::default())?;
+    }
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new().add_attribute("method", "migrate"))
+}
+
+/// Only the configured remote claim sources may open a channel.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_channel_open(
+    deps: DepsMut,
+    _env: Env,
+    msg: IbcChannelOpenMsg,
+) -> Result<(), ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    validate_channel(&config, msg.channel(), msg.counterparty_version())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_channel_connect(
+    deps: DepsMut,
+    _env: Env,
+    msg: IbcChannelConnectMsg,
+) -> Result<IbcBasicResponse, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    validate_channel(&config, msg.channel(), msg.counterparty_version())?;
+
+    Ok(IbcBasicResponse::new()
+        .add_attribute("method", "ibc_channel_connect")
+        .add_attribute("channel", &msg.channel().endpoint.channel_id))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_channel_close(
+    _deps: DepsMut,
+    _env: Env,
+    msg: IbcChannelCloseMsg,
+) -> Result<IbcBasicResponse, ContractError> {
+    Ok(IbcBasicResponse::new()
+        .add_attribute("method", "ibc_channel_close")
+        .add_attribute("channel", &msg.channel().endpoint.channel_id))
+}
+
+/// Claims relayed by a companion contract are acknowledged with the
+/// ContractError they failed with instead of failing the packet.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_packet_receive(
+    deps: DepsMut,
+    env: Env,
+    msg: IbcPacketReceiveMsg,
+) -> Result<IbcReceiveResponse, ContractError> {
+    match receive_claim(deps, &env, &msg.packet) {
+        Ok(response) => Ok(response),
+        Err(err) => Ok(IbcReceiveResponse::new()
+            .set_ack(ack_error(&err)?)
+            .add_attribute("method", "remote_claim")
+            .add_attribute("error", err.to_string())),
+    }
+}
+
+/// The contract sends no packets on its channels.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_packet_ack(
+    _deps: DepsMut,
+    _env: Env,
+    _msg: IbcPacketAckMsg,
+) -> Result<IbcBasicResponse, ContractError> {
+    Ok(IbcBasicResponse::new())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_packet_timeout(
+    _deps: DepsMut,
+    _env: Env,
+    _msg: IbcPacketTimeoutMsg,
+) -> Result<IbcBasicResponse, ContractError> {
+    Ok(IbcBasicResponse::new())
+}
+
 /// Handling submessage reply.
 /// For more info on submessage and reply, see https://github.com/CosmWasm/cosmwasm/blob/main/SEMANTICS.md#submessages
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
     deps.api.debug("reply reached");
     if msg.id == AUTHZ_EXEC_MINT_MSG_ID {
-        return handle_mint_reply(deps, msg, env.contract.address.to_string());
+        return handle_mint_reply(deps, env, msg);
     } else if msg.id == AUTHZ_EXEC_SEND_MSG_ID {
-        return handle_send_reply(deps, msg);
+        return handle_send_reply(deps, env, msg);
+    } else if msg.id == CLAIM_HOOK_MSG_ID {
+        return handle_hook_reply(deps, msg);
+    } else if msg.id == AUTHZ_EXEC_LOCK_MSG_ID {
+        return handle_lock_reply(deps, env, msg);
+    } else if msg.id == POST_CLAIM_SWAP_MSG_ID || msg.id == POST_CLAIM_JOIN_MSG_ID {
+        return handle_post_claim_reply(deps, msg);
+    } else if msg.id == IBC_TRANSFER_MSG_ID {
+        return handle_ibc_transfer_reply(deps, msg);
+    } else if msg.id == AUTHZ_EXEC_SET_METADATA_MSG_ID {
+        return handle_set_denom_metadata_reply(deps, env, msg);
     }
     Err(ContractError::UnknownReplyId { reply_id: msg.id })
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::GetRoot {} => to_binary(&query_root(deps)?),
         QueryMsg::GetSubdenom {} => to_binary(&query_subdenom(deps)?),
+        QueryMsg::DenomMetadata {} => to_binary(&query_denom_metadata(deps)?),
+        QueryMsg::GetConfig {} => to_binary(&CONFIG.load(deps.storage)?),
+        QueryMsg::GetStats {} => to_binary(&CLAIM_STATS.load(deps.storage)?),
+        QueryMsg::GrantStatus {} => to_binary(&query_grant_status(deps, env)?),
+        QueryMsg::ListHooks {} => to_binary(&query_hooks(deps)?),
+        QueryMsg::DecayMultiplier {} => to_binary(&query_decay_multiplier(deps, env)?),
+        QueryMsg::Denylist { start_after, limit } => {
+            to_binary(&query_denylist(deps, start_after, limit)?)
+        }
+        QueryMsg::RateLimitUsage {} => to_binary(&query_rate_limit_usage(deps, env)?),
+        QueryMsg::ReferralEarnings { start_after, limit } => {
+            to_binary(&query_referral_earnings(deps, start_after, limit)?)
+        }
     }
 }
 
@@ -94,7 +327,10 @@ fn query_subdenom(deps: Deps) -> StdResult<GetSubdenomResponse> {
 mod tests {
     use super::*;
     use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-    use cosmwasm_std::{coins, from_binary};
+    use cosmwasm_std::{coins, from_binary, from_slice, Storage, Uint128};
+
+    use crate::msg::{test_instantiate_msg, IbcLifecycleComplete};
+    use crate::state::{PendingIbcTransfer, PENDING_IBC_TRANSFERS};
 
     // TEST_ROOT test merkel root that was generated from "testdata/uosmo_only.csv" using merkle-drop-cli
     const TEST_ROOT: &str = "bd9c439f3903b3dbc92bad230df593d434aada80f26e8124d77d2f92fbaa6238";
@@ -105,6 +341,7 @@ mod tests {
 
         let msg = InstantiateMsg {
             merkle_root: String::from(TEST_ROOT),
+            ..test_instantiate_msg()
         };
         let info = mock_info("creator", &coins(1000, "earth"));
 
@@ -115,4 +352,113 @@ mod tests {
         let value: GetRootResponse = from_binary(&res).unwrap();
         assert_eq!(TEST_ROOT, value.root);
     }
+
+    #[test]
+    fn fund_escrow_wrong_denom_fails() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            merkle_root: String::from(TEST_ROOT),
+            distribution: Some(Distribution::Escrow {
+                denom: String::from("uosmo"),
+            }),
+            ..test_instantiate_msg()
+        };
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let err = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Fund {}).unwrap_err();
+        assert!(matches!(err, ContractError::Payment(_)), "{:?}", err);
+
+        let info = mock_info("creator", &coins(1000, "uosmo"));
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Fund {}).unwrap();
+    }
+
+    #[test]
+    fn sudo_messages_not_spoofed() {
+        // governance actions and ibc-hooks callbacks parse as what they are.
+        let msg: SudoMsg = from_slice(br#"{"pause":{}}"#).unwrap();
+        assert_eq!(SudoMsg::Governance(GovernanceSudoMsg::Pause {}), msg);
+        let msg: SudoMsg = from_slice(
+            br#"{"ibc_lifecycle_complete":{"ibc_timeout":{"channel":"channel-0","sequence":1}}}"#,
+        )
+        .unwrap();
+        assert!(matches!(msg, SudoMsg::IbcHooks(_)));
+
+        // neither is accepted inside or alongside the other.
+        let spoofed: [&[u8]; 3] = [
+            br#"{"ibc_lifecycle_complete":{"clawback":{"recipient":"osmo1thief"}}}"#,
+            br#"{"pause":{"ibc_timeout":{"channel":"channel-0","sequence":1}}}"#,
+            br#"{"pause":{},"ibc_lifecycle_complete":{"ibc_timeout":{"channel":"channel-0","sequence":1}}}"#,
+        ];
+        for msg in spoofed {
+            from_slice::<SudoMsg>(msg).unwrap_err();
+        }
+    }
+
+    #[test]
+    fn sudo_callbacks_and_governance_kept_apart() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            merkle_root: String::from(TEST_ROOT),
+            distribution: Some(Distribution::Escrow {
+                denom: String::from("uosmo"),
+            }),
+            ..test_instantiate_msg()
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let pending = PendingIbcTransfer {
+            claimer_addr: String::from("claimer"),
+            amount: Uint128::new(100),
+            denom: String::from("uosmo"),
+        };
+        PENDING_IBC_TRANSFERS
+            .save(deps.as_mut().storage, ("channel-0", 1), &pending)
+            .unwrap();
+
+        // pausing leaves pending transfers to their callbacks.
+        let sudo_msg = SudoMsg::Governance(GovernanceSudoMsg::Pause {});
+        sudo(deps.as_mut(), mock_env(), sudo_msg).unwrap();
+        assert!(PENDING_IBC_TRANSFERS.has(&deps.storage, ("channel-0", 1)));
+
+        // a callback for a transfer the contract did not send changes nothing.
+        let sudo_msg = SudoMsg::IbcHooks(IbcHooksSudoMsg::IbcLifecycleComplete(
+            IbcLifecycleComplete::IbcTimeout {
+                channel: String::from("channel-0"),
+                sequence: 2,
+            },
+        ));
+        let res = sudo(deps.as_mut(), mock_env(), sudo_msg).unwrap();
+        assert!(res.messages.is_empty());
+        assert!(CONFIG.load(&deps.storage).unwrap().paused);
+        assert!(PENDING_IBC_TRANSFERS.has(&deps.storage, ("channel-0", 1)));
+    }
+
+    #[test]
+    fn migrate_legacy_config() {
+        let mut deps = mock_dependencies();
+
+        // config as saved by the first version of the contract.
+        deps.storage.set(
+            b"config",
+            format!(
+                "{{\"merkle_root\":\"{}\",\"owner\":\"creator\"}}",
+                TEST_ROOT
+            )
+            .as_bytes(),
+        );
+
+        migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+
+        let config = CONFIG.load(&deps.storage).unwrap();
+        assert_eq!(Distribution::TokenFactory, config.distribution);
+        assert!(!config.governance_only && !config.paused);
+        assert!(config.lock_options.is_empty() && config.remote_claim_sources.is_empty());
+        assert_eq!(None, config.referral);
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetStats {}).unwrap();
+        assert_eq!(ClaimStats::default(), from_binary(&res).unwrap());
+    }
 }
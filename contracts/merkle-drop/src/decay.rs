@@ -0,0 +1,126 @@
+use cosmwasm_std::{Decimal, Deps, Env, StdResult, Timestamp, Uint128};
+
+use crate::msg::DecayMultiplierResponse;
+use crate::state::{DecaySchedule, CONFIG};
+use crate::ContractError;
+
+pub(crate) fn validate_decay(decay: &DecaySchedule) -> Result<(), ContractError> {
+    if decay.start >= decay.end || decay.min_multiplier > Decimal::one() {
+        return Err(ContractError::InvalidDecaySchedule {});
+    }
+    Ok(())
+}
+
+// decay_multiplier is the share of an allocation paid out at time: the full
+// allocation until the decay starts, then linearly less down to the minimum
+// multiplier at the end of the decay.
+pub(crate) fn decay_multiplier(decay: &Option<DecaySchedule>, time: Timestamp) -> Decimal {
+    let decay = match decay {
+        Some(decay) => decay,
+        None => return Decimal::one(),
+    };
+
+    if time <= decay.start {
+        return Decimal::one();
+    }
+    if time >= decay.end {
+        return decay.min_multiplier;
+    }
+
+    let elapsed = time.nanos() - decay.start.nanos();
+    let duration = decay.end.nanos() - decay.start.nanos();
+
+    Decimal::one()
+        - (Decimal::one() - decay.min_multiplier) * Decimal::from_ratio(elapsed, duration)
+}
+
+// forfeited_amount is the part of amount that is not paid out at time.
+pub(crate) fn forfeited_amount(
+    decay: &Option<DecaySchedule>,
+    time: Timestamp,
+    amount: Uint128,
+) -> Uint128 {
+    amount - amount * decay_multiplier(decay, time)
+}
+
+pub(crate) fn query_decay_multiplier(deps: Deps, env: Env) -> StdResult<DecayMultiplierResponse> {
+    let config = CONFIG.load(deps.storage)?;
+
+    Ok(DecayMultiplierResponse {
+        multiplier: decay_multiplier(&config.decay, env.block.time),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: u64 = 60 * 60 * 24;
+
+    // full allocation for two weeks, then down to 50% four weeks later.
+    fn schedule() -> Option<DecaySchedule> {
+        Some(DecaySchedule {
+            start: Timestamp::from_seconds(14 * DAY),
+            end: Timestamp::from_seconds(42 * DAY),
+            min_multiplier: Decimal::percent(50),
+        })
+    }
+
+    #[test]
+    fn decay_multiplier_over_time() {
+        let cases = [
+            (0, Decimal::one()),
+            (14 * DAY, Decimal::one()),
+            (21 * DAY, Decimal::permille(875)),
+            (28 * DAY, Decimal::percent(75)),
+            (42 * DAY, Decimal::percent(50)),
+            (100 * DAY, Decimal::percent(50)),
+        ];
+
+        for (seconds, expected) in cases {
+            assert_eq!(
+                expected,
+                decay_multiplier(&schedule(), Timestamp::from_seconds(seconds)),
+                "{}",
+                seconds
+            );
+        }
+
+        assert_eq!(
+            Decimal::one(),
+            decay_multiplier(&None, Timestamp::from_seconds(100 * DAY))
+        );
+    }
+
+    #[test]
+    fn forfeited_amount_rounds_payout_down() {
+        let time = Timestamp::from_seconds(28 * DAY);
+
+        assert_eq!(
+            Uint128::new(251),
+            forfeited_amount(&schedule(), time, Uint128::new(1001))
+        );
+        assert_eq!(
+            Uint128::zero(),
+            forfeited_amount(&None, time, Uint128::new(1001))
+        );
+    }
+
+    #[test]
+    fn invalid_decay_schedules() {
+        validate_decay(&schedule().unwrap()).unwrap();
+
+        let reversed = DecaySchedule {
+            start: Timestamp::from_seconds(42 * DAY),
+            end: Timestamp::from_seconds(14 * DAY),
+            min_multiplier: Decimal::percent(50),
+        };
+        assert!(validate_decay(&reversed).is_err());
+
+        let bonus = DecaySchedule {
+            min_multiplier: Decimal::percent(150),
+            ..schedule().unwrap()
+        };
+        assert!(validate_decay(&bonus).is_err());
+    }
+}
@@ -1,11 +1,20 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{Coin, OverflowError, StdError, Timestamp, Uint128};
+use cw_utils::PaymentError;
 use thiserror::Error;
 
+use crate::msg::ProofVersion;
+
 #[derive(Error, Debug)]
 pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
 
+    #[error("{0}")]
+    Payment(#[from] PaymentError),
+
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
     #[error("Unauthorized")]
     Unauthorized {},
 
@@ -18,15 +27,163 @@ pub enum ContractError {
     #[error("Failed to verify proof")]
     FailedVerifyProof {},
 
+    #[error("Proof is not in the {version:?} format of its proof_version")]
+    ProofVersionMismatch { version: ProofVersion },
+
     #[error("{claim:?} already claimed")]
     AlreadyClaimed { claim: String },
 
     #[error("{reply_id:?} unknown reply id")]
     UnknownReplyId { reply_id: u64 },
 
-    #[error("Failed to mint")]
-    FailedToMint {},
+    #[error("Failed to mint: {reason}")]
+    MintFailed { reason: String },
+
+    #[error("Failed to send: {reason}")]
+    SendFailed { reason: String },
+
+    #[error("Failed to set denom metadata: {reason}")]
+    SetDenomMetadataFailed { reason: String },
+
+    #[error("Invalid denom metadata: {reason}")]
+    InvalidDenomMetadata { reason: String },
+
+    #[error("Send authorization spend limit does not cover {amount}{denom}")]
+    SpendLimitExceeded { amount: Uint128, denom: String },
+
+    #[error("AuthZ grant for {msg_type_url:?} is not issued for the contract address")]
+    NoAuthZGrant { msg_type_url: String },
+
+    #[error("AuthZ grant for {msg_type_url:?} expired at {expiration}")]
+    GrantExpired {
+        msg_type_url: String,
+        expiration: Timestamp,
+    },
+
+    #[error("AuthZ grant for {msg_type_url:?} expires at {expiration}, before the claim end {claim_end}")]
+    GrantExpiresBeforeClaimEnd {
+        msg_type_url: String,
+        expiration: Timestamp,
+        claim_end: Timestamp,
+    },
+
+    #[error("{action:?} is not supported by the configured distribution")]
+    UnsupportedDistribution { action: String },
+
+    #[error("Claim window ended at {claim_end}")]
+    ClaimWindowClosed { claim_end: Timestamp },
+
+    #[error("Claims are paused")]
+    Paused {},
+
+    #[error("The contract is administered by governance only")]
+    GovernanceOnly {},
+
+    #[error("Hook {address:?} is already registered")]
+    HookAlreadyRegistered { address: String },
+
+    #[error("Hook {address:?} is not registered")]
+    HookNotRegistered { address: String },
+
+    #[error("Address {address} is blocked from claiming")]
+    AddressBlocked { address: String },
+
+    #[error("Decay must start before it ends and keep a multiplier of at most 1")]
+    InvalidDecaySchedule {},
+
+    #[error("At most {max_claims} claims per {period}")]
+    ClaimRateLimitExceeded { period: String, max_claims: u64 },
+
+    #[error("At most {max_amount} can be claimed per {period}")]
+    AmountRateLimitExceeded { period: String, max_amount: Uint128 },
+
+    #[error("Rate limit window must be at least one second")]
+    InvalidRateLimits {},
+
+    #[error("Claim fee must be a non-zero coin or a rate of at most 1")]
+    InvalidClaimFee {},
+
+    #[error("Claim fee of {required} must be attached to the claim")]
+    InsufficientClaimFee { required: Coin },
+
+    #[error("Referral rate must be at most 1")]
+    InvalidReferralConfig {},
+
+    #[error("Referrals are not enabled")]
+    ReferralsDisabled {},
+
+    #[error("Referrer {referrer} must be another address that already claimed")]
+    InvalidReferrer { referrer: String },
+
+    #[error("Only the claimer can set a referrer")]
+    ReferralRequiresClaimer {},
+
+    #[error("Only the claimer can opt into a lock")]
+    LockRequiresClaimer {},
+
+    #[error("Lock duration {duration} is not supported")]
+    UnsupportedLockDuration { duration: u64 },
+
+    #[error("Failed to lock: {reason}")]
+    LockFailed { reason: String },
+
+    #[error("Only the claimer can set a post-claim action")]
+    PostClaimActionRequiresClaimer {},
+
+    #[error("A post-claim action cannot be combined with a lock")]
+    PostClaimActionWithLock {},
+
+    #[error("Swap requires at least one route")]
+    EmptySwapRoutes {},
+
+    #[error("No pool to join is configured")]
+    NoJoinPoolConfigured {},
+
+    #[error("Only the claimer can set an IBC destination")]
+    IbcDestinationRequiresClaimer {},
+
+    #[error("An IBC destination cannot be combined with a lock or post-claim action")]
+    IbcDestinationConflict {},
+
+    #[error("Only unordered channels with version {version} are supported")]
+    InvalidRemoteClaimChannel { version: String },
+
+    #[error("Port {port_id} on {connection_id} is not a remote claim source")]
+    UnknownRemoteClaimSource {
+        connection_id: String,
+        port_id: String,
+    },
+
+    #[error("Fixed claim fees cannot be charged on remote claims, which come with no funds")]
+    RemoteClaimFixedFee {},
+
+    #[error("IBC transfer returned no packet sequence")]
+    MissingPacketSequence {},
+
+    #[error("Post-claim action returned no {msg_type_url} response")]
+    MissingPostClaimResponse { msg_type_url: String },
+
+    #[error("Claim window is still open")]
+    ClaimWindowOpen {},
+
+    #[error("Insufficient escrow: {balance} available, {required} required")]
+    InsufficientEscrow { balance: Uint128, required: Uint128 },
+
+    #[error("Nothing left to withdraw")]
+    NothingToWithdraw {},
+
+    #[error("Cw20 token {token:?} is not the distributed token")]
+    UnexpectedCw20 { token: String },
+
+    #[error("Unsupported authorization {type_url:?}")]
+    UnsupportedAuthorization { type_url: String },
+
+    #[error("Send authorization has no spend limit in {denom:?}")]
+    NoSpendLimitForDenom { denom: String },
 
-    #[error("AuthZ grant for tokenfactory mint is not issued for the contract address")]
-    NoAuthZMintGrant {},
+    #[error("Send authorization spend limit {spend_limit} is below the required {required}")]
+    InsufficientSpendLimit {
+        spend_limit: Uint128,
+        required: Uint128,
+    },
 }
@@ -0,0 +1,324 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Event, Uint128};
+
+// Custom event types. wasmd prefixes them with "wasm-", so indexers see
+// e.g. "wasm-merkle_drop_claim".
+pub const CLAIM_EVENT: &str = "merkle_drop_claim";
+pub const CONFIG_UPDATE_EVENT: &str = "merkle_drop_config_update";
+pub const FUND_EVENT: &str = "merkle_drop_fund";
+pub const WITHDRAW_EVENT: &str = "merkle_drop_withdraw";
+pub const LOCK_EVENT: &str = "merkle_drop_lock";
+pub const POST_CLAIM_EVENT: &str = "merkle_drop_post_claim";
+pub const IBC_TRANSFER_EVENT: &str = "merkle_drop_ibc_transfer";
+pub const IBC_REFUND_EVENT: &str = "merkle_drop_ibc_refund";
+pub const CLAIM_FEE_EVENT: &str = "merkle_drop_claim_fee";
+pub const REFERRAL_EVENT: &str = "merkle_drop_referral";
+
+/// ClaimStage is the `stage` attribute of a claim event.
+#[cw_serde]
+pub enum ClaimStage {
+    /// The proof was verified and the tokenfactory mint was dispatched.
+    Requested,
+    /// The tokenfactory mint succeeded and the send to the claimer was dispatched.
+    Minted,
+    /// The claim was paid out and recorded.
+    Completed,
+}
+
+impl ClaimStage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ClaimStage::Requested => "requested",
+            ClaimStage::Minted => "minted",
+            ClaimStage::Completed => "completed",
+        }
+    }
+}
+
+/// MerkleDropEvent lists the custom events emitted by the contract. Each
+/// variant is emitted as an event of the type returned by `event_type`
+/// with one attribute per field, named after the field. Amounts are
+/// formatted as integers and optional fields are omitted when unset.
+#[cw_serde]
+pub enum MerkleDropEvent {
+    /// A claim of amount of denom for address progressed to stage.
+    /// The sender is the account that submitted the claim.
+    Claim {
+        stage: ClaimStage,
+        sender: String,
+        address: String,
+        amount: Uint128,
+        denom: String,
+    },
+    /// The contract was configured, action is e.g. "instantiate", "set_subdenom",
+    /// "set_denom_metadata", or one of "pause", "unpause" and "update_root" through sudo, in which
+    /// case the sender is "governance". The denom is the distributed asset
+    /// once it is known.
+    ConfigUpdate {
+        action: String,
+        sender: String,
+        denom: Option<String>,
+    },
+    /// The owner escrowed amount of denom for claims.
+    Fund {
+        sender: String,
+        amount: Uint128,
+        denom: String,
+    },
+    /// The unclaimed escrow was withdrawn to recipient, by the owner or by
+    /// "governance" through a clawback.
+    Withdraw {
+        sender: String,
+        recipient: String,
+        amount: Uint128,
+        denom: String,
+    },
+    /// The amount of denom paid out to address, bonus included, was locked
+    /// in its name for duration seconds.
+    Lock {
+        address: String,
+        amount: Uint128,
+        denom: String,
+        duration: u64,
+        bonus: Uint128,
+    },
+    /// The amount of denom claimed by address went through action, "swap"
+    /// or "join_pool", and amount_out of denom_out was delivered to it.
+    PostClaim {
+        action: String,
+        address: String,
+        amount: Uint128,
+        denom: String,
+        amount_out: Uint128,
+        denom_out: String,
+    },
+    /// The amount of denom claimed by address was transferred to receiver
+    /// over channel, in the packet of the given sequence.
+    IbcTransfer {
+        address: String,
+        amount: Uint128,
+        denom: String,
+        channel: String,
+        receiver: String,
+        sequence: u64,
+    },
+    /// The IBC transfer of the given packet failed or timed out, reason is
+    /// "error" or "timeout", and the amount of denom was sent to address
+    /// on this chain.
+    IbcRefund {
+        address: String,
+        amount: Uint128,
+        denom: String,
+        channel: String,
+        sequence: u64,
+        reason: String,
+    },
+    /// A fee of amount of denom was charged on the claim of address and
+    /// sent to treasury.
+    ClaimFee {
+        address: String,
+        treasury: String,
+        amount: Uint128,
+        denom: String,
+    },
+    /// The claim of address was referred by referrer, who was rewarded
+    /// with amount of denom.
+    Referral {
+        referrer: String,
+        address: String,
+        amount: Uint128,
+        denom: String,
+    },
+}
+
+impl MerkleDropEvent {
+    pub fn event_type(&self) -> &'static str {
+        match self {
+            MerkleDropEvent::Claim { .. } => CLAIM_EVENT,
+            MerkleDropEvent::ConfigUpdate { .. } => CONFIG_UPDATE_EVENT,
+            MerkleDropEvent::Fund { .. } => FUND_EVENT,
+            MerkleDropEvent::Withdraw { .. } => WITHDRAW_EVENT,
+            MerkleDropEvent::Lock { .. } => LOCK_EVENT,
+            MerkleDropEvent::PostClaim { .. } => POST_CLAIM_EVENT,
+            MerkleDropEvent::IbcTransfer { .. } => IBC_TRANSFER_EVENT,
+            MerkleDropEvent::IbcRefund { .. } => IBC_REFUND_EVENT,
+            MerkleDropEvent::ClaimFee { .. } => CLAIM_FEE_EVENT,
+            MerkleDropEvent::Referral { .. } => REFERRAL_EVENT,
+        }
+    }
+}
+
+impl From<MerkleDropEvent> for Event {
+    fn from(event: MerkleDropEvent) -> Self {
+        let result = Event::new(event.event_type());
+
+        match event {
+            MerkleDropEvent::Claim {
+                stage,
+                sender,
+                address,
+                amount,
+                denom,
+            } => result
+                .add_attribute("stage", stage.as_str())
+                .add_attribute("sender", sender)
+                .add_attribute("address", address)
+                .add_attribute("amount", amount)
+                .add_attribute("denom", denom),
+            MerkleDropEvent::ConfigUpdate {
+                action,
+                sender,
+                denom,
+            } => {
+                let result = result
+                    .add_attribute("action", action)
+                    .add_attribute("sender", sender);
+                match denom {
+                    Some(denom) => result.add_attribute("denom", denom),
+                    None => result,
+                }
+            }
+            MerkleDropEvent::Fund {
+                sender,
+                amount,
+                denom,
+            } => result
+                .add_attribute("sender", sender)
+                .add_attribute("amount", amount)
+                .add_attribute("denom", denom),
+            MerkleDropEvent::Withdraw {
+                sender,
+                recipient,
+                amount,
+                denom,
+            } => result
+                .add_attribute("sender", sender)
+                .add_attribute("recipient", recipient)
+                .add_attribute("amount", amount)
+                .add_attribute("denom", denom),
+            MerkleDropEvent::Lock {
+                address,
+                amount,
+                denom,
+                duration,
+                bonus,
+            } => result
+                .add_attribute("address", address)
+                .add_attribute("amount", amount)
+                .add_attribute("denom", denom)
+                .add_attribute("duration", duration.to_string())
+                .add_attribute("bonus", bonus),
+            MerkleDropEvent::PostClaim {
+                action,
+                address,
+                amount,
+                denom,
+                amount_out,
+                denom_out,
+            } => result
+                .add_attribute("action", action)
+                .add_attribute("address", address)
+                .add_attribute("amount", amount)
+                .add_attribute("denom", denom)
+                .add_attribute("amount_out", amount_out)
+                .add_attribute("denom_out", denom_out),
+            MerkleDropEvent::IbcTransfer {
+                address,
+                amount,
+                denom,
+                channel,
+                receiver,
+                sequence,
+            } => result
+                .add_attribute("address", address)
+                .add_attribute("amount", amount)
+                .add_attribute("denom", denom)
+                .add_attribute("channel", channel)
+                .add_attribute("receiver", receiver)
+                .add_attribute("sequence", sequence.to_string()),
+            MerkleDropEvent::IbcRefund {
+                address,
+                amount,
+                denom,
+                channel,
+                sequence,
+                reason,
+            } => result
+                .add_attribute("address", address)
+                .add_attribute("amount", amount)
+                .add_attribute("denom", denom)
+                .add_attribute("channel", channel)
+                .add_attribute("sequence", sequence.to_string())
+                .add_attribute("reason", reason),
+            MerkleDropEvent::ClaimFee {
+                address,
+                treasury,
+                amount,
+                denom,
+            } => result
+                .add_attribute("address", address)
+                .add_attribute("treasury", treasury)
+                .add_attribute("amount", amount)
+                .add_attribute("denom", denom),
+            MerkleDropEvent::Referral {
+                referrer,
+                address,
+                amount,
+                denom,
+            } => result
+                .add_attribute("referrer", referrer)
+                .add_attribute("address", address)
+                .add_attribute("amount", amount)
+                .add_attribute("denom", denom),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::Attribute;
+
+    #[test]
+    fn claim_event_attributes() {
+        let event: Event = MerkleDropEvent::Claim {
+            stage: ClaimStage::Completed,
+            sender: String::from("sender"),
+            address: String::from("claimer"),
+            amount: Uint128::new(1421901),
+            denom: String::from("uosmo"),
+        }
+        .into();
+
+        assert_eq!(CLAIM_EVENT, event.ty);
+        assert_eq!(
+            vec![
+                Attribute::new("stage", "completed"),
+                Attribute::new("sender", "sender"),
+                Attribute::new("address", "claimer"),
+                Attribute::new("amount", "1421901"),
+                Attribute::new("denom", "uosmo"),
+            ],
+            event.attributes
+        );
+    }
+
+    #[test]
+    fn config_update_event_omits_unset_denom() {
+        let event: Event = MerkleDropEvent::ConfigUpdate {
+            action: String::from("instantiate"),
+            sender: String::from("owner"),
+            denom: None,
+        }
+        .into();
+
+        assert_eq!(CONFIG_UPDATE_EVENT, event.ty);
+        assert_eq!(
+            vec![
+                Attribute::new("action", "instantiate"),
+                Attribute::new("sender", "owner"),
+            ],
+            event.attributes
+        );
+    }
+}
@@ -1,26 +1,84 @@
-use cosmwasm_std::{DepsMut, Env, Response, SubMsg, Uint128};
-use merkle::{hash::Hash, proof::Proof};
+use cosmwasm_std::{
+    DepsMut, Env, Event, MessageInfo, Response, StdResult, Storage, SubMsg, Uint128,
+};
+use merkle::hash::{
+    Hash, Keccak256Hasher, Sha256Hasher, Sha3Hasher, SortedKeccak256Hasher, SortedSha256Hasher,
+};
+use merkle::proof::Proof;
+use merkle::sorted_pair;
 use osmosis_std::shim::Any;
 use osmosis_std::types::cosmos::authz::v1beta1::MsgExec;
 use osmosis_std::types::cosmos::base::v1beta1;
 use osmosis_std::types::osmosis::tokenfactory::v1beta1::{MsgMint, TokenfactoryQuerier};
 
+use crate::decay::forfeited_amount;
 use crate::error::ContractError;
+use crate::events::{ClaimStage, MerkleDropEvent};
+use crate::execute::claim_fee::{claim_funds_msgs, fee_payment, percentage_fee};
+use crate::execute::denylist::ensure_not_blocked;
+use crate::execute::fund::{escrow_asset, escrow_balance, escrow_transfer};
+use crate::execute::hooks::claim_hooks;
+use crate::execute::lock::{lock_tokens, resolve_lock, ClaimLock};
+use crate::execute::post_claim::{post_claim_msg, validate_post_claim_action};
+use crate::execute::rate_limit::enforce_rate_limits;
+use crate::execute::referral::{record_referral, resolve_referral};
+use crate::grants::ensure_grants_active;
+use crate::ibc::{ibc_transfer_msg, validate_ibc_destination};
+use crate::msg::{ClaimOptions, ClaimProof, ProofVersion};
 use crate::reply::AUTHZ_EXEC_MINT_MSG_ID;
-use crate::state::{MintReplyState, CLAIMED_ADDRESSES, CONFIG, REPLY_STATE, SUBDENOM};
+use crate::state::{
+    Config, Distribution, HashFunction, IbcDestination, IbcTransferState, MintReplyState,
+    PostClaimAction, ReferralReward, CLAIMED_ADDRESSES, CLAIMED_BY, CLAIM_STATS, CONFIG,
+    REPLY_STATE, SUBDENOM,
+};
+
+// ClaimPayout is a verified claim, paid out by the configured distribution.
+struct ClaimPayout {
+    claimer_addr: String,
+    amount: Uint128,
+    // part of amount lost to the decay schedule.
+    forfeited: Uint128,
+    // part of the paid out amount sent to the treasury.
+    fee: Uint128,
+    lock: Option<ClaimLock>,
+    post_claim_action: Option<PostClaimAction>,
+    ibc_destination: Option<IbcDestination>,
+    referral: Option<ReferralReward>,
+}
 
 pub fn claim(
     deps: DepsMut,
     env: Env,
-    proof_str: String,
+    info: MessageInfo,
+    proof: ClaimProof,
     amount: Uint128,
     claimer_addr: String,
+    options: ClaimOptions,
 ) -> Result<Response, ContractError> {
-    let config = CONFIG.load(deps.storage).unwrap();
+    let ClaimOptions {
+        lock_duration,
+        post_claim_action,
+        ibc_destination,
+        referrer,
+    } = options;
+
+    let config = CONFIG.load(deps.storage)?;
+
+    if config.paused {
+        return Err(ContractError::Paused {});
+    }
+
+    if let Some(claim_end) = config.claim_end {
+        if env.block.time >= claim_end {
+            return Err(ContractError::ClaimWindowClosed { claim_end });
+        }
+    }
 
     // TODO: validate claimer_addr is an actual account
 
-    let claim = format!("{}{}", claimer_addr, amount);
+    ensure_not_blocked(deps.storage, &claimer_addr)?;
+
+    let claim = claim_key(&claimer_addr, amount);
 
     let claim_check = CLAIMED_ADDRESSES.may_load(deps.storage, &claim)?;
     if claim_check.is_some() {
@@ -30,16 +88,167 @@ pub fn claim(
     deps.api
         .debug(&format!("merkle_root {0}", &config.merkle_root));
 
-    deps.api.debug(&format!("proof_str {0}", &proof_str));
+    deps.api.debug(&format!("proof {0:?}", &proof));
 
     deps.api.debug(&format!("claim {0}", &claim));
 
-    verify_proof(&config.merkle_root, &proof_str, claim)?;
+    verify_proof(
+        &config.merkle_root,
+        &config.hash_function,
+        &proof,
+        claim_leaf(&config.hash_function, &claimer_addr, amount),
+    )?;
 
     deps.api.debug("validation passed");
 
+    // late claims forfeit part of the allocation.
+    let forfeited = forfeited_amount(&config.decay, env.block.time, amount);
+    let fee = percentage_fee(&config, amount - forfeited);
+
+    validate_post_claim_action(
+        &info,
+        &config,
+        &claimer_addr,
+        &post_claim_action,
+        lock_duration,
+    )?;
+
+    validate_ibc_destination(
+        &info,
+        &config,
+        &claimer_addr,
+        &ibc_destination,
+        lock_duration,
+        &post_claim_action,
+    )?;
+
+    let lock = resolve_lock(
+        deps.as_ref(),
+        &env,
+        &info,
+        &config,
+        &claimer_addr,
+        amount - forfeited,
+        lock_duration,
+    )?;
+
+    let referral = resolve_referral(
+        deps.as_ref(),
+        &info,
+        &config,
+        &claimer_addr,
+        referrer,
+        amount - forfeited,
+    )?;
+
+    let bonus = lock.as_ref().map_or_else(Uint128::zero, |lock| lock.bonus);
+    let reward = referral
+        .as_ref()
+        .map_or_else(Uint128::zero, |referral| referral.amount);
+    enforce_rate_limits(
+        deps.storage,
+        &env,
+        &config,
+        (amount - forfeited)
+            .checked_add(bonus)?
+            .checked_add(reward)?,
+    )?;
+
+    if let Some(referral) = &referral {
+        record_referral(deps.storage, referral)?;
+    }
+
+    let (funds_msgs, fixed_fee_event) = claim_funds_msgs(&config, &info, &claimer_addr)?;
+
+    let payout = ClaimPayout {
+        claimer_addr,
+        amount,
+        forfeited,
+        fee,
+        lock,
+        post_claim_action,
+        ibc_destination,
+        referral,
+    };
+    let response = match config.distribution {
+        Distribution::TokenFactory => mint(deps, env, info, config, payout),
+        _ => send_escrow(deps, env, info, config, payout),
+    }?;
+
+    Ok(response
+        .add_messages(funds_msgs)
+        .add_events(fixed_fee_event.map(Event::from)))
+}
+
+// claim_key is the entry recorded in CLAIMED_ADDRESSES once a claim is paid out.
+pub(crate) fn claim_key(claimer_addr: &str, amount: Uint128) -> String {
+    format!("{}{}", claimer_addr, amount)
+}
+
+// claim_leaf is the data the leaf of a claim is hashed from: the claim key,
+// or the ABI encoded address and amount for StandardMerkleTree roots.
+pub(crate) fn claim_leaf(
+    hash_function: &HashFunction,
+    claimer_addr: &str,
+    amount: Uint128,
+) -> Vec<u8> {
+    match hash_function {
+        HashFunction::SortedKeccak256 => sorted_pair::claim_leaf(claimer_addr, amount.u128()),
+        _ => claim_key(claimer_addr, amount).into_bytes(),
+    }
+}
+
+// record_claim marks the claim as paid out and updates the claim stats.
+// Shared by every distribution once its payout is dispatched.
+pub(crate) fn record_claim(
+    storage: &mut dyn Storage,
+    claimer_addr: &str,
+    amount: Uint128,
+    forfeited: Uint128,
+) -> StdResult<()> {
+    CLAIMED_ADDRESSES.save(storage, &claim_key(claimer_addr, amount), &true)?;
+    CLAIMED_BY.update(storage, claimer_addr, |claimed| {
+        claimed.unwrap_or_default().checked_add(amount)
+    })?;
+
+    CLAIM_STATS.update(storage, |mut stats| -> StdResult<_> {
+        stats.claimed_count += 1;
+        stats.claimed_amount = stats.claimed_amount.checked_add(amount)?;
+        stats.forfeited_amount = stats.forfeited_amount.checked_add(forfeited)?;
+        Ok(stats)
+    })?;
+
+    Ok(())
+}
+
+// mint mints the claimed amount of the owner's tokenfactory denom through authz,
+// less the forfeited part. The fee is minted as well and sent to the treasury
+// instead of the claimer, and a referral reward is minted for the referrer.
+// The claim is only recorded once the follow-up send
+// succeeds, see reply.rs.
+fn mint(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    config: Config,
+    payout: ClaimPayout,
+) -> Result<Response, ContractError> {
+    let ClaimPayout {
+        claimer_addr,
+        amount,
+        forfeited,
+        fee,
+        lock,
+        post_claim_action,
+        ibc_destination,
+        referral,
+    } = payout;
+
     let subdenom = SUBDENOM.load(deps.storage)?;
 
+    // fail with a clear error instead of a failed authz exec once a grant lapsed.
+    ensure_grants_active(deps.storage, &deps.querier, &env, &config)?;
+
     let full_denom = format!("factory/{}/{}", config.owner, subdenom);
     deps.api
         .debug(&format!("claim full_denom: claim end: {}", full_denom));
@@ -52,11 +261,19 @@ pub fn claim(
         .admin;
     deps.api.debug(&format!("denom admin = {admin:?}"));
 
+    let bonus = lock.as_ref().map_or_else(Uint128::zero, |lock| lock.bonus);
+    let reward = referral
+        .as_ref()
+        .map_or_else(Uint128::zero, |referral| referral.amount);
+
     let mint_msg_res = MsgMint {
         sender: config.owner.to_string(),
         amount: Some(v1beta1::Coin {
             denom: full_denom.clone(),
-            amount: amount.to_string(),
+            amount: (amount - forfeited)
+                .checked_add(bonus)?
+                .checked_add(reward)?
+                .to_string(),
         }),
     };
 
@@ -76,46 +293,287 @@ pub fn claim(
         deps.storage,
         AUTHZ_EXEC_MINT_MSG_ID,
         &MintReplyState {
-            claimer_addr,
+            sender: info.sender.to_string(),
+            claimer_addr: claimer_addr.clone(),
             amount,
-            denom: full_denom,
+            denom: full_denom.clone(),
+            bonus,
+            forfeited,
+            fee,
+            lock_duration: lock.map(|lock| lock.duration),
+            post_claim_action,
+            ibc_destination,
+            referral,
         },
     )?;
 
     deps.api.debug("claim end");
 
     Ok(Response::new()
-        .add_attribute("action", "claim")
-        .add_submessage(SubMsg::reply_on_success(exec_msg, AUTHZ_EXEC_MINT_MSG_ID)))
+        .add_attribute("method", "claim")
+        .add_event(
+            MerkleDropEvent::Claim {
+                stage: ClaimStage::Requested,
+                sender: info.sender.into_string(),
+                address: claimer_addr,
+                amount,
+                denom: full_denom,
+            }
+            .into(),
+        )
+        .add_submessage(SubMsg::reply_always(exec_msg, AUTHZ_EXEC_MINT_MSG_ID)))
 }
 
-pub fn verify_proof(
+// send_escrow pays the claim out of the native or CW20 funds escrowed in the contract.
+// A lock bonus, the fee and a referral reward are paid out of the escrow as well. With a post-claim
+// action, the escrowed funds are routed through the pool instead of being sent directly.
+fn send_escrow(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    config: Config,
+    payout: ClaimPayout,
+) -> Result<Response, ContractError> {
+    let ClaimPayout {
+        claimer_addr,
+        amount,
+        forfeited,
+        fee,
+        lock,
+        post_claim_action,
+        ibc_destination,
+        referral,
+    } = payout;
+
+    let bonus = lock.as_ref().map_or_else(Uint128::zero, |lock| lock.bonus);
+    let reward = referral
+        .as_ref()
+        .map_or_else(Uint128::zero, |referral| referral.amount);
+    let paid = amount - forfeited - fee;
+    let payout = paid.checked_add(bonus)?;
+    let required = payout.checked_add(fee)?.checked_add(reward)?;
+
+    // funds attached to the claim are not part of the escrow.
+    let attached: Uint128 = match &config.distribution {
+        Distribution::Escrow { denom } => info
+            .funds
+            .iter()
+            .filter(|funds| funds.denom == *denom)
+            .map(|funds| funds.amount)
+            .sum(),
+        _ => Uint128::zero(),
+    };
+    let balance =
+        escrow_balance(deps.as_ref(), &env, &config.distribution)?.saturating_sub(attached);
+    if balance < required {
+        return Err(ContractError::InsufficientEscrow { balance, required });
+    }
+
+    let denom = escrow_asset(&config.distribution)?;
+
+    let mut response = Response::new();
+    if let Some((treasury, event)) = fee_payment(&config, &claimer_addr, fee, &denom) {
+        response = response
+            .add_message(escrow_transfer(&config.distribution, &treasury, fee)?)
+            .add_event(event.into());
+    }
+
+    if let Some(referral) = referral {
+        response = response
+            .add_message(escrow_transfer(
+                &config.distribution,
+                &referral.referrer,
+                referral.amount,
+            )?)
+            .add_event(
+                MerkleDropEvent::Referral {
+                    referrer: referral.referrer,
+                    address: claimer_addr.clone(),
+                    amount: referral.amount,
+                    denom: denom.clone(),
+                }
+                .into(),
+            );
+    }
+
+    // an IBC claim is recorded once the transfer is dispatched, see
+    // handle_ibc_transfer_reply.
+    if let Some(destination) = ibc_destination {
+        return Ok(response
+            .add_attribute("method", "claim")
+            .add_submessage(ibc_transfer_msg(
+                deps.storage,
+                &env,
+                IbcTransferState {
+                    sender: info.sender.into_string(),
+                    claimer_addr,
+                    amount,
+                    forfeited,
+                    fee,
+                    denom,
+                    channel: destination.channel,
+                    receiver: destination.receiver,
+                },
+                destination.timeout,
+            )?));
+    }
+
+    record_claim(deps.storage, &claimer_addr, amount, forfeited)?;
+    let hooks = claim_hooks(deps.storage, &claimer_addr, paid, &denom)?;
+
+    response = match post_claim_action {
+        Some(action) => response.add_submessage(post_claim_msg(
+            deps.storage,
+            &env,
+            &config,
+            action,
+            &claimer_addr,
+            payout,
+            &denom,
+        )?),
+        None => response.add_message(escrow_transfer(
+            &config.distribution,
+            &claimer_addr,
+            payout,
+        )?),
+    };
+
+    // the payout is locked once it reached the claimer.
+    if let Some(lock) = lock {
+        response = response
+            .add_submessage(lock_tokens(
+                &env,
+                &claimer_addr,
+                lock.duration,
+                payout,
+                &denom,
+            ))
+            .add_event(
+                MerkleDropEvent::Lock {
+                    address: claimer_addr.clone(),
+                    amount: payout,
+                    denom: denom.clone(),
+                    duration: lock.duration,
+                    bonus: lock.bonus,
+                }
+                .into(),
+            );
+    }
+
+    Ok(response
+        .add_submessages(hooks)
+        .add_attribute("method", "claim")
+        .add_event(
+            MerkleDropEvent::Claim {
+                stage: ClaimStage::Completed,
+                sender: info.sender.into_string(),
+                address: claimer_addr,
+                amount,
+                denom,
+            }
+            .into(),
+        ))
+}
+
+// ensure_proof_version checks that the proof is in the format the claim
+// declares, rather than inferring the format from the shape of the proof.
+pub(crate) fn ensure_proof_version(
+    proof: &ClaimProof,
+    version: ProofVersion,
+) -> Result<(), ContractError> {
+    if proof.version() != version {
+        return Err(ContractError::ProofVersionMismatch { version });
+    }
+
+    Ok(())
+}
+
+pub fn verify_proof<T: AsRef<[u8]>>(
     merkle_root: &String,
-    proof_str: &str,
-    to_verify: String,
+    hash_function: &HashFunction,
+    proof: &ClaimProof,
+    to_verify: T,
 ) -> Result<(), ContractError> {
-    let proof: Proof = serde_json_wasm::from_str(proof_str).unwrap();
-    let root = match base64::decode(merkle_root) {
-        Ok(f) => f,
-        Err(e) => {
-            return Err(ContractError::FailedToDecodeRoot {
-                root: e.to_string(),
-            })
+    let proof = parse_proof(hash_function, proof)?;
+    let root_hash = decode_root(merkle_root, hash_function)?;
+
+    let verified = match hash_function {
+        HashFunction::Sha3_256 => proof.verify::<Sha3Hasher, _>(&to_verify, &root_hash),
+        HashFunction::Sha256 => proof.verify::<Sha256Hasher, _>(&to_verify, &root_hash),
+        HashFunction::Keccak256 => proof.verify::<Keccak256Hasher, _>(&to_verify, &root_hash),
+        HashFunction::SortedKeccak256 => {
+            proof.verify::<SortedKeccak256Hasher, _>(&to_verify, &root_hash)
+        }
+        HashFunction::Cw20MerkleAirdrop => {
+            proof.verify::<SortedSha256Hasher, _>(&to_verify, &root_hash)
         }
     };
 
-    let root_hash = Hash::from(root);
-
-    if !proof.verify(&to_verify, &root_hash) {
+    if !verified {
         return Err(ContractError::FailedVerifyProof {});
     }
 
     Ok(())
 }
 
+// decode_root decodes a merkle root, hex encoded for cw20-merkle-airdrop and
+// StandardMerkleTree roots and base64 encoded otherwise.
+pub(crate) fn decode_root(
+    merkle_root: &str,
+    hash_function: &HashFunction,
+) -> Result<Hash, ContractError> {
+    let decoded = match hash_function {
+        HashFunction::Cw20MerkleAirdrop => hex::decode(merkle_root).map_err(|e| e.to_string()),
+        HashFunction::SortedKeccak256 => {
+            hex::decode(merkle_root.trim_start_matches("0x")).map_err(|e| e.to_string())
+        }
+        _ => base64::decode(merkle_root).map_err(|e| e.to_string()),
+    };
+
+    decoded
+        .and_then(|root| Hash::try_from(root.as_slice()).map_err(|e| e.to_string()))
+        .map_err(|root| ContractError::FailedToDecodeRoot { root })
+}
+
+fn parse_proof(hash_function: &HashFunction, proof: &ClaimProof) -> Result<Proof, ContractError> {
+    let sorted_pairs = matches!(
+        hash_function,
+        HashFunction::SortedKeccak256 | HashFunction::Cw20MerkleAirdrop
+    );
+
+    match proof {
+        ClaimProof::Legacy(proof_str) => {
+            serde_json_wasm::from_str(proof_str).map_err(|_| ContractError::FailedVerifyProof {})
+        }
+        ClaimProof::Compact { hashes, path } => {
+            Proof::from_compact(hashes, *path).ok_or(ContractError::FailedVerifyProof {})
+        }
+        // sorted pairs are hashed the same whichever side the sibling is on.
+        ClaimProof::Hashes { hashes } if sorted_pairs => {
+            Proof::from_compact(hashes, 0).ok_or(ContractError::FailedVerifyProof {})
+        }
+        ClaimProof::Hex { hex } if sorted_pairs => {
+            let hashes = hex
+                .iter()
+                .map(|hash| hex::decode(hash.trim_start_matches("0x")))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| ContractError::FailedVerifyProof {})?;
+            Proof::from_compact(hashes, 0).ok_or(ContractError::FailedVerifyProof {})
+        }
+        ClaimProof::Hashes { .. } | ClaimProof::Hex { .. } => {
+            Err(ContractError::FailedVerifyProof {})
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use cosmwasm_std::{to_vec, Binary};
+    use merkle::cw20_airdrop::Cw20AirdropTree;
+    use merkle::hash::MerkleHasher;
+    use merkle::sorted_pair::SortedPairTree;
+    use merkle::{Tree, TreeVersion};
 
     // TEST_ROOT test merkel root that was generated from "testdata/uosmo_only.csv" using merkle-drop-cli
     const TEST_ROOT: &str = "Nz54SQtyBVHwsmEqNI//mxFgiq8MRD7sS92IGkhgMvo=";
@@ -133,7 +591,8 @@ mod tests {
     fn verify_proof_success() {
         verify_proof(
             &String::from(TEST_ROOT),
-            &String::from(VALID_PROOF_STR),
+            &HashFunction::Sha3_256,
+            &ClaimProof::Legacy(String::from(VALID_PROOF_STR)),
             String::from(TO_VERIFY_VALID),
         )
         .unwrap();
@@ -143,17 +602,422 @@ mod tests {
     fn verify_proof_amount_addr_success() {
         verify_proof(
             &String::from(TEST_ROOT2_ADDR_AMOUNT),
-            &String::from(VALID_PROOF_STR2_ADDR_AMOUNT),
+            &HashFunction::Sha3_256,
+            &ClaimProof::Legacy(String::from(VALID_PROOF_STR2_ADDR_AMOUNT)),
             String::from(TO_VERIFY_VALID2_ADDR_AMOUNT),
         )
         .unwrap();
     }
 
+    #[test]
+    fn verify_compact_proof_success() {
+        let proof: Proof = serde_json_wasm::from_str(VALID_PROOF_STR).unwrap();
+
+        verify_proof(
+            &String::from(TEST_ROOT),
+            &HashFunction::Sha3_256,
+            &compact_proof(&proof),
+            String::from(TO_VERIFY_VALID),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn verify_compact_proof_invalid_error() {
+        let proof: Proof = serde_json_wasm::from_str(VALID_PROOF_STR).unwrap();
+        let (hashes, path) = compact_parts(&proof);
+
+        let invalid = [
+            // wrong sibling order.
+            ClaimProof::Compact {
+                hashes: hashes.clone(),
+                path: path ^ 1,
+            },
+            // path past the last hash.
+            ClaimProof::Compact {
+                hashes: hashes.clone(),
+                path: path | 1 << hashes.len(),
+            },
+            // truncated hash.
+            ClaimProof::Compact {
+                hashes: vec![Binary::from(&hashes[0][..31])],
+                path: 0,
+            },
+        ];
+
+        for proof in invalid {
+            let err = verify_proof(
+                &String::from(TEST_ROOT),
+                &HashFunction::Sha3_256,
+                &proof,
+                String::from(TO_VERIFY_VALID),
+            )
+            .unwrap_err();
+            assert!(matches!(err, ContractError::FailedVerifyProof {}));
+        }
+    }
+
+    #[test]
+    fn compact_proof_size() {
+        const DEPTH: usize = 20;
+
+        // proof of a leaf of a tree with a million claims.
+        let mut proof = Proof::default();
+        let mut root = Sha3Hasher::leaf(TO_VERIFY_VALID.as_bytes());
+        for i in 0..DEPTH {
+            let sibling = Sha3Hasher::leaf(&i.to_be_bytes());
+            let is_left_sibling = i % 3 == 0;
+            root = if is_left_sibling {
+                Sha3Hasher::branch(&sibling, &root)
+            } else {
+                Sha3Hasher::branch(&root, &sibling)
+            };
+            proof.push(is_left_sibling, sibling);
+        }
+        let root = base64::encode(root);
+
+        // serialized length of the proof as sent in a claim.
+        let size = |proof: &ClaimProof| {
+            verify_proof(
+                &root,
+                &HashFunction::Sha3_256,
+                proof,
+                String::from(TO_VERIFY_VALID),
+            )
+            .unwrap();
+            to_vec(proof).unwrap().len()
+        };
+
+        let legacy_size = size(&ClaimProof::Legacy(
+            serde_json_wasm::to_string(&proof).unwrap(),
+        ));
+        let compact_size = size(&compact_proof(&proof));
+
+        // each legacy hash is 44 base64 characters written out as numbers.
+        assert!(
+            legacy_size > 3 * compact_size,
+            "legacy proof is {} bytes, compact proof {} bytes",
+            legacy_size,
+            compact_size
+        );
+    }
+
+    #[test]
+    fn claim_proof_formats() {
+        // claims sent before the proof formats were introduced.
+        let legacy = serde_json_wasm::to_string(VALID_PROOF_STR).unwrap();
+        assert_eq!(
+            ClaimProof::Legacy(String::from(VALID_PROOF_STR)),
+            serde_json_wasm::from_str::<ClaimProof>(&legacy).unwrap()
+        );
+
+        let hash = "YOjr1EMfDwr0ETIgRGal0Ol5i8Rgo9U3LFRZsBaNY3I=";
+        let cases = [
+            (
+                format!("{{\"hashes\":[\"{}\"],\"path\":5}}", hash),
+                ClaimProof::Compact {
+                    hashes: vec![Binary::from_base64(hash).unwrap()],
+                    path: 5,
+                },
+            ),
+            (
+                format!("{{\"hashes\":[\"{}\"]}}", hash),
+                ClaimProof::Hashes {
+                    hashes: vec![Binary::from_base64(hash).unwrap()],
+                },
+            ),
+            (
+                String::from("{\"hex\":[\"0xbc41\"]}"),
+                ClaimProof::Hex {
+                    hex: vec![String::from("0xbc41")],
+                },
+            ),
+        ];
+
+        for (json, proof) in cases {
+            assert_eq!(
+                proof,
+                serde_json_wasm::from_str::<ClaimProof>(&json).unwrap()
+            );
+            assert_eq!(json, serde_json_wasm::to_string(&proof).unwrap());
+        }
+    }
+
+    #[test]
+    fn proof_version_must_match() {
+        let proof: Proof = serde_json_wasm::from_str(VALID_PROOF_STR).unwrap();
+        let (hashes, _) = compact_parts(&proof);
+        let proofs = [
+            ClaimProof::Legacy(String::from(VALID_PROOF_STR)),
+            compact_proof(&proof),
+            ClaimProof::Hashes { hashes },
+            ClaimProof::Hex { hex: vec![] },
+        ];
+        let versions = [
+            ProofVersion::Legacy,
+            ProofVersion::Compact,
+            ProofVersion::Hashes,
+            ProofVersion::Hex,
+        ];
+
+        for (i, proof) in proofs.iter().enumerate() {
+            for (j, version) in versions.iter().enumerate() {
+                let res = ensure_proof_version(proof, *version);
+                if i == j {
+                    res.unwrap();
+                } else {
+                    assert!(
+                        matches!(
+                            res.unwrap_err(),
+                            ContractError::ProofVersionMismatch { version: v } if v == *version
+                        ),
+                        "{:?} as {:?}",
+                        proof,
+                        version
+                    );
+                }
+            }
+        }
+
+        // claims without a proof_version are legacy.
+        ensure_proof_version(&proofs[0], ProofVersion::default()).unwrap();
+    }
+
+    #[test]
+    fn verify_proof_hash_functions() {
+        let leaves = [TO_VERIFY_VALID, TO_VERIFY_VALID2_ADDR_AMOUNT];
+        let sha256_tree: Tree<Sha256Hasher> = Tree::new(&leaves).unwrap();
+        let keccak_tree: Tree<Keccak256Hasher> = Tree::new(&leaves).unwrap();
+
+        let cases = [
+            (
+                HashFunction::Sha256,
+                base64::encode(sha256_tree.get_root().unwrap()),
+                sha256_tree.find_proof(&TO_VERIFY_VALID).unwrap(),
+            ),
+            (
+                HashFunction::Keccak256,
+                base64::encode(keccak_tree.get_root().unwrap()),
+                keccak_tree.find_proof(&TO_VERIFY_VALID).unwrap(),
+            ),
+        ];
+
+        for (hash_function, root, proof) in cases {
+            let proof = compact_proof(&proof);
+            verify_proof(&root, &hash_function, &proof, String::from(TO_VERIFY_VALID)).unwrap();
+
+            // the root was not built with the default hash function.
+            verify_proof(
+                &root,
+                &HashFunction::Sha3_256,
+                &proof,
+                String::from(TO_VERIFY_VALID),
+            )
+            .unwrap_err();
+        }
+    }
+
+    #[test]
+    fn verify_v2_tree_proof() {
+        // the odd leaf is promoted to the root's level, without a sibling of
+        // its own.
+        let leaves = [
+            TO_VERIFY_VALID,
+            TO_VERIFY_VALID2_ADDR_AMOUNT,
+            "osmo1other1000",
+        ];
+        let tree: Tree = Tree::with_version(&leaves, TreeVersion::V2).unwrap();
+        let root = base64::encode(tree.get_root().unwrap());
+
+        for leaf in leaves {
+            verify_proof(
+                &root,
+                &HashFunction::Sha3_256,
+                &compact_proof(&tree.find_proof(&leaf).unwrap()),
+                String::from(leaf),
+            )
+            .unwrap();
+        }
+    }
+
+    #[test]
+    fn verify_single_leaf_proof() {
+        // the only leaf is the root, so its proof has no siblings.
+        let tree: Tree = Tree::new(&[TO_VERIFY_VALID]).unwrap();
+        let root = base64::encode(tree.get_root().unwrap());
+        assert_eq!(
+            Sha3Hasher::leaf(TO_VERIFY_VALID.as_bytes()),
+            tree.get_root().unwrap()
+        );
+
+        let proofs = [
+            compact_proof(&tree.find_proof(&TO_VERIFY_VALID).unwrap()),
+            ClaimProof::Legacy(String::from("[]")),
+        ];
+        for proof in proofs {
+            verify_proof(
+                &root,
+                &HashFunction::Sha3_256,
+                &proof,
+                String::from(TO_VERIFY_VALID),
+            )
+            .unwrap();
+        }
+
+        let err = verify_proof(
+            &root,
+            &HashFunction::Sha3_256,
+            &ClaimProof::Legacy(String::from("[]")),
+            String::from(TO_VERIFY_VALID2_ADDR_AMOUNT),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::FailedVerifyProof {}));
+    }
+
+    #[test]
+    fn verify_sorted_pair_proof() {
+        let hash_function = HashFunction::SortedKeccak256;
+        let claims = [
+            ("osmo1claimera", Uint128::new(100)),
+            ("osmo1claimerb", Uint128::new(2250)),
+            ("osmo1claimerc", Uint128::new(3300)),
+        ];
+        let leaves: Vec<Vec<u8>> = claims
+            .iter()
+            .map(|(address, amount)| claim_leaf(&hash_function, address, *amount))
+            .collect();
+        let tree = SortedPairTree::new(&leaves).unwrap();
+        let root = tree.get_root_hex().unwrap();
+        let hashes: Vec<Binary> = tree
+            .find_proof(&leaves[0])
+            .unwrap()
+            .hashes()
+            .iter()
+            .map(|hash| Binary::from(hash.as_ref()))
+            .collect();
+
+        verify_proof(
+            &root,
+            &hash_function,
+            &ClaimProof::Hashes {
+                hashes: hashes.clone(),
+            },
+            &leaves[0],
+        )
+        .unwrap();
+
+        // leaves are ABI encoded, not the claim key.
+        let err = verify_proof(
+            &root,
+            &hash_function,
+            &ClaimProof::Hashes {
+                hashes: hashes.clone(),
+            },
+            claim_key(claims[0].0, claims[0].1),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::FailedVerifyProof {}));
+
+        let err = verify_proof(
+            &root,
+            &hash_function,
+            &ClaimProof::Hashes {
+                hashes: hashes.clone(),
+            },
+            &leaves[1],
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::FailedVerifyProof {}));
+
+        // plain hash lists only make sense for sorted pairs.
+        let err = verify_proof(
+            &base64::encode(tree.get_root().unwrap()),
+            &HashFunction::Keccak256,
+            &ClaimProof::Hashes { hashes },
+            &leaves[0],
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::FailedVerifyProof {}));
+    }
+
+    #[test]
+    fn verify_cw20_airdrop_proof() {
+        let leaves = [
+            TO_VERIFY_VALID,
+            TO_VERIFY_VALID2_ADDR_AMOUNT,
+            "osmo1other1000",
+        ];
+        let tree = Cw20AirdropTree::new(&leaves).unwrap();
+        let root = tree.get_root_hex().unwrap();
+        let proof = tree.find_proof(&TO_VERIFY_VALID).unwrap();
+
+        // proofs as written by cw20-merkle-airdrop tooling.
+        let hex_proof: Vec<String> =
+            serde_json_wasm::from_str(&serde_json_wasm::to_string(&proof).unwrap()).unwrap();
+        let hashes = proof
+            .hashes()
+            .iter()
+            .map(|hash| Binary::from(hash.as_ref()))
+            .collect();
+
+        for proof in [
+            ClaimProof::Hex { hex: hex_proof },
+            ClaimProof::Hashes { hashes },
+        ] {
+            verify_proof(
+                &root,
+                &HashFunction::Cw20MerkleAirdrop,
+                &proof,
+                String::from(TO_VERIFY_VALID),
+            )
+            .unwrap();
+
+            let err = verify_proof(
+                &root,
+                &HashFunction::Cw20MerkleAirdrop,
+                &proof,
+                String::from(TO_VERIFY_VALID2_ADDR_AMOUNT),
+            )
+            .unwrap_err();
+            assert!(matches!(err, ContractError::FailedVerifyProof {}));
+        }
+    }
+
+    #[test]
+    fn decode_root_encodings() {
+        let root = [7u8; 32];
+
+        decode_root(&hex::encode(root), &HashFunction::Cw20MerkleAirdrop).unwrap();
+        decode_root(&base64::encode(root), &HashFunction::Sha3_256).unwrap();
+        for hex_root in [hex::encode(root), format!("0x{}", hex::encode(root))] {
+            decode_root(&hex_root, &HashFunction::SortedKeccak256).unwrap();
+        }
+
+        let invalid = [
+            (base64::encode(root), HashFunction::Cw20MerkleAirdrop),
+            (hex::encode(root), HashFunction::Sha3_256),
+            (base64::encode(root), HashFunction::SortedKeccak256),
+            // not a 32-byte hash.
+            (hex::encode(&root[..31]), HashFunction::Cw20MerkleAirdrop),
+            (base64::encode(&root[..31]), HashFunction::Sha256),
+        ];
+
+        for (root, hash_function) in invalid {
+            let err = decode_root(&root, &hash_function).unwrap_err();
+            assert!(
+                matches!(err, ContractError::FailedToDecodeRoot { .. }),
+                "{}",
+                root
+            );
+        }
+    }
+
     #[test]
     fn verify_proof_invalid_root_error() {
         verify_proof(
             &String::from("this is garbage"),
-            &String::from(VALID_PROOF_STR),
+            &HashFunction::Sha3_256,
+            &ClaimProof::Legacy(String::from(VALID_PROOF_STR)),
             String::from(TO_VERIFY_VALID),
         )
         .unwrap_err();
@@ -163,9 +1027,25 @@ mod tests {
     fn verify_proof_invalid_proof_error() {
         verify_proof(
             &String::from(TEST_ROOT),
-            &String::from(INVALID_PROOF_STR),
+            &HashFunction::Sha3_256,
+            &ClaimProof::Legacy(String::from(INVALID_PROOF_STR)),
             String::from(TO_VERIFY_VALID),
         )
         .unwrap_err();
     }
+
+    fn compact_proof(proof: &Proof) -> ClaimProof {
+        let (hashes, path) = compact_parts(proof);
+        ClaimProof::Compact { hashes, path }
+    }
+
+    fn compact_parts(proof: &Proof) -> (Vec<Binary>, u64) {
+        let compact = proof.to_compact().unwrap();
+        let hashes = compact
+            .hashes
+            .iter()
+            .map(|hash| Binary::from(hash.as_ref()))
+            .collect();
+        (hashes, compact.path)
+    }
 }
@@ -0,0 +1,191 @@
+use cosmwasm_std::{Api, BankMsg, Coin, Decimal, MessageInfo, Uint128};
+
+use crate::events::MerkleDropEvent;
+use crate::state::{ClaimFee, Config, FeeAmount};
+use crate::ContractError;
+
+pub(crate) fn validate_claim_fee(api: &dyn Api, fee: &ClaimFee) -> Result<(), ContractError> {
+    api.addr_validate(fee.treasury.as_str())?;
+
+    match &fee.amount {
+        FeeAmount::Fixed { coin } if coin.amount.is_zero() => {
+            Err(ContractError::InvalidClaimFee {})
+        }
+        FeeAmount::Percentage { rate } if *rate > Decimal::one() => {
+            Err(ContractError::InvalidClaimFee {})
+        }
+        _ => Ok(()),
+    }
+}
+
+// claim_funds_msgs routes the fixed fee paid with a claim to the treasury and
+// refunds the rest of the funds to the sender. Claims need no other funds.
+pub(crate) fn claim_funds_msgs(
+    config: &Config,
+    info: &MessageInfo,
+    claimer_addr: &str,
+) -> Result<(Vec<BankMsg>, Option<MerkleDropEvent>), ContractError> {
+    let mut refund = info.funds.clone();
+    let mut msgs = vec![];
+    let mut event = None;
+
+    if let Some(ClaimFee {
+        amount: FeeAmount::Fixed { coin },
+        treasury,
+    }) = &config.claim_fee
+    {
+        let paid = refund
+            .iter_mut()
+            .find(|funds| funds.denom == coin.denom)
+            .filter(|funds| funds.amount >= coin.amount)
+            .ok_or_else(|| ContractError::InsufficientClaimFee {
+                required: coin.clone(),
+            })?;
+        paid.amount -= coin.amount;
+
+        msgs.push(BankMsg::Send {
+            to_address: treasury.to_string(),
+            amount: vec![coin.clone()],
+        });
+        event = Some(MerkleDropEvent::ClaimFee {
+            address: String::from(claimer_addr),
+            treasury: treasury.to_string(),
+            amount: coin.amount,
+            denom: coin.denom.clone(),
+        });
+    }
+
+    refund.retain(|funds| !funds.amount.is_zero());
+    if !refund.is_empty() {
+        msgs.push(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: refund,
+        });
+    }
+
+    Ok((msgs, event))
+}
+
+// percentage_fee is the part of the paid out amount withheld from the claimer
+// for the treasury.
+pub(crate) fn percentage_fee(config: &Config, paid: Uint128) -> Uint128 {
+    match &config.claim_fee {
+        Some(ClaimFee {
+            amount: FeeAmount::Percentage { rate },
+            ..
+        }) => paid * *rate,
+        _ => Uint128::zero(),
+    }
+}
+
+// fee_payment names the treasury the percentage fee of a claim is paid to,
+// along with its event. None if there is no fee to pay.
+pub(crate) fn fee_payment(
+    config: &Config,
+    claimer_addr: &str,
+    fee: Uint128,
+    denom: &str,
+) -> Option<(String, MerkleDropEvent)> {
+    match &config.claim_fee {
+        Some(ClaimFee { treasury, .. }) if !fee.is_zero() => Some((
+            treasury.to_string(),
+            MerkleDropEvent::ClaimFee {
+                address: String::from(claimer_addr),
+                treasury: treasury.to_string(),
+                amount: fee,
+                denom: String::from(denom),
+            },
+        )),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::mock_info;
+    use cosmwasm_std::{coins, Addr};
+
+    use crate::state::test_config;
+
+    fn config(amount: FeeAmount) -> Config {
+        Config {
+            claim_fee: Some(ClaimFee {
+                amount,
+                treasury: Addr::unchecked("treasury"),
+            }),
+            ..test_config()
+        }
+    }
+
+    #[test]
+    fn fixed_fee_routed_and_excess_refunded() {
+        let config = config(FeeAmount::Fixed {
+            coin: Coin::new(100, "uosmo"),
+        });
+        let info = mock_info("sender", &[Coin::new(150, "uosmo"), Coin::new(5, "uion")]);
+
+        let (msgs, event) = claim_funds_msgs(&config, &info, "claimer").unwrap();
+
+        assert_eq!(
+            vec![
+                BankMsg::Send {
+                    to_address: String::from("treasury"),
+                    amount: coins(100, "uosmo"),
+                },
+                BankMsg::Send {
+                    to_address: String::from("sender"),
+                    amount: vec![Coin::new(50, "uosmo"), Coin::new(5, "uion")],
+                },
+            ],
+            msgs
+        );
+        assert!(event.is_some());
+
+        // exact payment leaves nothing to refund.
+        let info = mock_info("sender", &coins(100, "uosmo"));
+        let (msgs, _) = claim_funds_msgs(&config, &info, "claimer").unwrap();
+        assert_eq!(1, msgs.len());
+    }
+
+    #[test]
+    fn insufficient_fixed_fee() {
+        let config = config(FeeAmount::Fixed {
+            coin: Coin::new(100, "uosmo"),
+        });
+
+        for funds in [vec![], coins(99, "uosmo"), coins(100, "uion")] {
+            let err =
+                claim_funds_msgs(&config, &mock_info("sender", &funds), "claimer").unwrap_err();
+            assert!(matches!(err, ContractError::InsufficientClaimFee { .. }));
+        }
+    }
+
+    #[test]
+    fn percentage_fee_rounds_down() {
+        let config = config(FeeAmount::Percentage {
+            rate: Decimal::percent(1),
+        });
+
+        assert_eq!(
+            Uint128::new(14),
+            percentage_fee(&config, Uint128::new(1499))
+        );
+
+        // without a fixed fee, attached funds are refunded.
+        let (msgs, event) = claim_funds_msgs(
+            &config,
+            &mock_info("sender", &coins(10, "uosmo")),
+            "claimer",
+        )
+        .unwrap();
+        assert_eq!(
+            vec![BankMsg::Send {
+                to_address: String::from("sender"),
+                amount: coins(10, "uosmo"),
+            }],
+            msgs
+        );
+        assert!(event.is_none());
+    }
+}
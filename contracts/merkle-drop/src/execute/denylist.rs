@@ -0,0 +1,97 @@
+use cosmwasm_std::{Addr, Deps, DepsMut, MessageInfo, Order, Response, StdResult, Storage};
+use cw_storage_plus::Bound;
+
+use crate::events::MerkleDropEvent;
+use crate::execute::ensure_owner;
+use crate::msg::DenylistResponse;
+use crate::state::{CONFIG, DENYLIST};
+use crate::ContractError;
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+pub fn add_to_denylist(
+    deps: DepsMut,
+    info: MessageInfo,
+    addresses: Vec<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    ensure_owner(&config, &info.sender)?;
+
+    for address in &addresses {
+        let address = deps.api.addr_validate(address)?;
+        DENYLIST.save(deps.storage, &address, &())?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("method", "add_to_denylist")
+        .add_attribute("addresses", addresses.join(","))
+        .add_event(
+            MerkleDropEvent::ConfigUpdate {
+                action: String::from("add_to_denylist"),
+                sender: info.sender.into_string(),
+                denom: None,
+            }
+            .into(),
+        ))
+}
+
+pub fn remove_from_denylist(
+    deps: DepsMut,
+    info: MessageInfo,
+    addresses: Vec<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    ensure_owner(&config, &info.sender)?;
+
+    for address in &addresses {
+        let address = deps.api.addr_validate(address)?;
+        DENYLIST.remove(deps.storage, &address);
+    }
+
+    Ok(Response::new()
+        .add_attribute("method", "remove_from_denylist")
+        .add_attribute("addresses", addresses.join(","))
+        .add_event(
+            MerkleDropEvent::ConfigUpdate {
+                action: String::from("remove_from_denylist"),
+                sender: info.sender.into_string(),
+                denom: None,
+            }
+            .into(),
+        ))
+}
+
+// ensure_not_blocked rejects claims for denylisted addresses. Addresses of
+// other chains cannot be denylisted and always pass.
+pub(crate) fn ensure_not_blocked(
+    storage: &dyn Storage,
+    address: &str,
+) -> Result<(), ContractError> {
+    if DENYLIST.has(storage, &Addr::unchecked(address)) {
+        return Err(ContractError::AddressBlocked {
+            address: String::from(address),
+        });
+    }
+    Ok(())
+}
+
+pub(crate) fn query_denylist(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<DenylistResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after.map(Addr::unchecked);
+    let start = start_after.as_ref().map(Bound::exclusive);
+
+    let addresses = DENYLIST
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|address| address.map(Addr::into_string))
+        .collect::<StdResult<_>>()?;
+
+    Ok(DenylistResponse { addresses })
+}
@@ -0,0 +1,180 @@
+use cosmwasm_std::{
+    from_binary, to_binary, BankMsg, Coin, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response,
+    Uint128, WasmMsg,
+};
+use cw20::{BalanceResponse, Cw20ExecuteMsg, Cw20QueryMsg, Cw20ReceiveMsg};
+use cw_utils::must_pay;
+
+use crate::events::MerkleDropEvent;
+use crate::execute::ensure_owner;
+use crate::msg::ReceiveMsg;
+use crate::state::{Distribution, CONFIG};
+use crate::ContractError;
+
+pub fn fund(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    ensure_owner(&config, &info.sender)?;
+
+    let denom = match config.distribution {
+        Distribution::Escrow { denom } => denom,
+        _ => {
+            return Err(ContractError::UnsupportedDistribution {
+                action: String::from("fund"),
+            })
+        }
+    };
+    let amount = must_pay(&info, &denom)?;
+
+    Ok(Response::new().add_attribute("method", "fund").add_event(
+        MerkleDropEvent::Fund {
+            sender: info.sender.into_string(),
+            amount,
+            denom,
+        }
+        .into(),
+    ))
+}
+
+pub fn receive(
+    deps: DepsMut,
+    info: MessageInfo,
+    wrapper: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // only the distributed token may call the hook.
+    match &config.distribution {
+        Distribution::Cw20 { address } if *address == info.sender => {}
+        _ => {
+            return Err(ContractError::UnexpectedCw20 {
+                token: info.sender.into_string(),
+            })
+        }
+    }
+
+    let sender = deps.api.addr_validate(&wrapper.sender)?;
+    ensure_owner(&config, &sender)?;
+
+    match from_binary(&wrapper.msg)? {
+        ReceiveMsg::Fund {} => Ok(Response::new().add_attribute("method", "fund").add_event(
+            MerkleDropEvent::Fund {
+                sender: sender.into_string(),
+                amount: wrapper.amount,
+                denom: info.sender.into_string(),
+            }
+            .into(),
+        )),
+    }
+}
+
+pub fn withdraw_remaining(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    ensure_owner(&config, &info.sender)?;
+
+    // the escrow stays locked for as long as it can still be claimed.
+    match config.claim_end {
+        Some(claim_end) if env.block.time >= claim_end => {}
+        _ => return Err(ContractError::ClaimWindowOpen {}),
+    }
+
+    let recipient = match recipient {
+        Some(recipient) => deps.api.addr_validate(&recipient)?,
+        None => config.owner,
+    };
+
+    let balance = escrow_balance(deps.as_ref(), &env, &config.distribution)?;
+    if balance.is_zero() {
+        return Err(ContractError::NothingToWithdraw {});
+    }
+
+    Ok(Response::new()
+        .add_message(escrow_transfer(
+            &config.distribution,
+            recipient.as_str(),
+            balance,
+        )?)
+        .add_attribute("method", "withdraw_remaining")
+        .add_event(
+            MerkleDropEvent::Withdraw {
+                sender: info.sender.into_string(),
+                recipient: recipient.into_string(),
+                amount: balance,
+                denom: escrow_asset(&config.distribution)?,
+            }
+            .into(),
+        ))
+}
+
+// escrow_asset names the escrowed asset, the bank denom or the CW20 contract address.
+pub(crate) fn escrow_asset(distribution: &Distribution) -> Result<String, ContractError> {
+    match distribution {
+        Distribution::Escrow { denom } => Ok(denom.clone()),
+        Distribution::Cw20 { address } => Ok(address.to_string()),
+        Distribution::TokenFactory => Err(ContractError::UnsupportedDistribution {
+            action: String::from("escrow_asset"),
+        }),
+    }
+}
+
+// escrow_balance returns how much of the distributed asset the contract holds.
+pub(crate) fn escrow_balance(
+    deps: Deps,
+    env: &Env,
+    distribution: &Distribution,
+) -> Result<Uint128, ContractError> {
+    match distribution {
+        Distribution::Escrow { denom } => Ok(deps
+            .querier
+            .query_balance(&env.contract.address, denom)?
+            .amount),
+        Distribution::Cw20 { address } => {
+            let response: BalanceResponse = deps.querier.query_wasm_smart(
+                address,
+                &Cw20QueryMsg::Balance {
+                    address: env.contract.address.to_string(),
+                },
+            )?;
+            Ok(response.balance)
+        }
+        Distribution::TokenFactory => Err(ContractError::UnsupportedDistribution {
+            action: String::from("escrow_balance"),
+        }),
+    }
+}
+
+// escrow_transfer creates the message paying amount of the escrowed asset to recipient.
+pub(crate) fn escrow_transfer(
+    distribution: &Distribution,
+    recipient: &str,
+    amount: Uint128,
+) -> Result<CosmosMsg, ContractError> {
+    match distribution {
+        Distribution::Escrow { denom } => Ok(BankMsg::Send {
+            to_address: String::from(recipient),
+            amount: vec![Coin {
+                denom: denom.clone(),
+                amount,
+            }],
+        }
+        .into()),
+        Distribution::Cw20 { address } => Ok(WasmMsg::Execute {
+            contract_addr: address.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: String::from(recipient),
+                amount,
+            })?,
+            funds: vec![],
+        }
+        .into()),
+        Distribution::TokenFactory => Err(ContractError::UnsupportedDistribution {
+            action: String::from("escrow_transfer"),
+        }),
+    }
+}
@@ -0,0 +1,136 @@
+use cosmwasm_std::{
+    Deps, DepsMut, MessageInfo, Order, Response, StdResult, Storage, SubMsg, Uint128,
+};
+
+use crate::events::MerkleDropEvent;
+use crate::execute::ensure_owner;
+use crate::msg::{ClaimHookMsg, HookResponse, ListHooksResponse};
+use crate::reply::CLAIM_HOOK_MSG_ID;
+use crate::state::{HookFailurePolicy, CONFIG, HOOKS};
+use crate::ContractError;
+
+pub fn add_hook(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+    failure_policy: Option<HookFailurePolicy>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    ensure_owner(&config, &info.sender)?;
+
+    let hook = deps.api.addr_validate(&address)?;
+    if HOOKS.has(deps.storage, &hook) {
+        return Err(ContractError::HookAlreadyRegistered { address });
+    }
+
+    let failure_policy = failure_policy.unwrap_or(HookFailurePolicy::Ignore);
+    HOOKS.save(deps.storage, &hook, &failure_policy)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "add_hook")
+        .add_attribute("hook", hook)
+        .add_event(
+            MerkleDropEvent::ConfigUpdate {
+                action: String::from("add_hook"),
+                sender: info.sender.into_string(),
+                denom: None,
+            }
+            .into(),
+        ))
+}
+
+pub fn remove_hook(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    ensure_owner(&config, &info.sender)?;
+
+    let hook = deps.api.addr_validate(&address)?;
+    if !HOOKS.has(deps.storage, &hook) {
+        return Err(ContractError::HookNotRegistered { address });
+    }
+
+    HOOKS.remove(deps.storage, &hook);
+
+    Ok(Response::new()
+        .add_attribute("method", "remove_hook")
+        .add_attribute("hook", hook)
+        .add_event(
+            MerkleDropEvent::ConfigUpdate {
+                action: String::from("remove_hook"),
+                sender: info.sender.into_string(),
+                denom: None,
+            }
+            .into(),
+        ))
+}
+
+// claim_hooks creates the submessages notifying every registered hook of a
+// paid out claim. Failures of hooks with the ignore policy are caught in
+// reply, the others revert the claim.
+pub(crate) fn claim_hooks(
+    storage: &dyn Storage,
+    address: &str,
+    amount: Uint128,
+    denom: &str,
+) -> StdResult<Vec<SubMsg>> {
+    hook_msgs(storage, address, amount, denom, true)
+}
+
+// remote_claim_hooks creates the claim hook submessages of a claim relayed
+// over IBC. A failing submessage would fail the packet instead of having it
+// acknowledged, so the failures of every hook are caught in reply and no
+// hook reverts a remote claim.
+pub(crate) fn remote_claim_hooks(
+    storage: &dyn Storage,
+    address: &str,
+    amount: Uint128,
+    denom: &str,
+) -> StdResult<Vec<SubMsg>> {
+    hook_msgs(storage, address, amount, denom, false)
+}
+
+fn hook_msgs(
+    storage: &dyn Storage,
+    address: &str,
+    amount: Uint128,
+    denom: &str,
+    revertible: bool,
+) -> StdResult<Vec<SubMsg>> {
+    HOOKS
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (hook, failure_policy) = item?;
+            let msg = ClaimHookMsg {
+                address: String::from(address),
+                amount,
+                denom: String::from(denom),
+            }
+            .into_cosmos_msg(hook)?;
+
+            Ok(match failure_policy {
+                HookFailurePolicy::Revert if revertible => SubMsg::new(msg),
+                _ => SubMsg::reply_on_error(msg, CLAIM_HOOK_MSG_ID),
+            })
+        })
+        .collect()
+}
+
+pub(crate) fn query_hooks(deps: Deps) -> StdResult<ListHooksResponse> {
+    let hooks = HOOKS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (address, failure_policy) = item?;
+            Ok(HookResponse {
+                address: address.into_string(),
+                failure_policy,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+
+    Ok(ListHooksResponse { hooks })
+}
@@ -0,0 +1,104 @@
+use cosmwasm_std::{Deps, Env, MessageInfo, SubMsg, Uint128};
+use osmosis_std::shim::{Any, Duration};
+use osmosis_std::types::cosmos::authz::v1beta1::MsgExec;
+use osmosis_std::types::cosmos::base::v1beta1::Coin;
+use osmosis_std::types::osmosis::lockup::MsgLockTokens;
+
+use crate::execute::ensure_claimer;
+use crate::grants::query_grant;
+use crate::reply::AUTHZ_EXEC_LOCK_MSG_ID;
+use crate::state::{Config, Distribution};
+use crate::ContractError;
+
+// ClaimLock is the lock a claimer opted into and the bonus it earns.
+pub(crate) struct ClaimLock {
+    pub duration: u64,
+    pub bonus: Uint128,
+}
+
+// resolve_lock validates the lock_duration a claimer opted into. The claimer
+// must have granted MsgLockTokens to the contract.
+pub(crate) fn resolve_lock(
+    deps: Deps,
+    env: &Env,
+    info: &MessageInfo,
+    config: &Config,
+    claimer_addr: &str,
+    amount: Uint128,
+    lock_duration: Option<u64>,
+) -> Result<Option<ClaimLock>, ContractError> {
+    let duration = match lock_duration {
+        Some(duration) => duration,
+        None => return Ok(None),
+    };
+
+    if let Distribution::Cw20 { .. } = config.distribution {
+        return Err(ContractError::UnsupportedDistribution {
+            action: String::from("lock"),
+        });
+    }
+
+    ensure_claimer(
+        &info.sender,
+        claimer_addr,
+        ContractError::LockRequiresClaimer {},
+    )?;
+
+    let option = config
+        .lock_options
+        .iter()
+        .find(|option| option.duration == duration)
+        .ok_or(ContractError::UnsupportedLockDuration { duration })?;
+
+    if query_grant(
+        &deps.querier,
+        claimer_addr,
+        env.contract.address.as_str(),
+        MsgLockTokens::TYPE_URL,
+    )
+    .is_none()
+    {
+        return Err(ContractError::NoAuthZGrant {
+            msg_type_url: String::from(MsgLockTokens::TYPE_URL),
+        });
+    }
+
+    Ok(Some(ClaimLock {
+        duration,
+        bonus: amount * option.bonus,
+    }))
+}
+
+// lock_tokens creates the authz exec locking amount of denom in the
+// claimer's name, once it was paid out to the claimer.
+pub(crate) fn lock_tokens(
+    env: &Env,
+    owner: &str,
+    duration: u64,
+    amount: Uint128,
+    denom: &str,
+) -> SubMsg {
+    let lock_msg = MsgLockTokens {
+        owner: String::from(owner),
+        duration: Some(Duration {
+            seconds: duration as i64,
+            nanos: 0,
+        }),
+        coins: vec![Coin {
+            denom: String::from(denom),
+            amount: amount.to_string(),
+        }],
+    };
+
+    let lock_msg_binary: cosmwasm_std::Binary = lock_msg.into();
+
+    let exec_msg = MsgExec {
+        grantee: env.contract.address.to_string(),
+        msgs: vec![Any {
+            type_url: String::from(MsgLockTokens::TYPE_URL),
+            value: lock_msg_binary.to_vec(),
+        }],
+    };
+
+    SubMsg::reply_always(exec_msg, AUTHZ_EXEC_LOCK_MSG_ID)
+}
@@ -1,2 +1,62 @@
+use cosmwasm_std::Addr;
+
+use crate::{state::Config, ContractError};
+
 pub(crate) mod claim;
+pub(crate) mod claim_fee;
+pub(crate) mod denylist;
+pub(crate) mod fund;
+pub(crate) mod hooks;
+pub(crate) mod lock;
+pub(crate) mod post_claim;
+pub(crate) mod rate_limit;
+pub(crate) mod referral;
+pub(crate) mod set_denom_metadata;
 pub(crate) mod set_subdenom;
+
+pub(crate) fn ensure_owner(config: &Config, sender: &Addr) -> Result<(), ContractError> {
+    if config.governance_only {
+        return Err(ContractError::GovernanceOnly {});
+    }
+    if config.owner != *sender {
+        return Err(ContractError::UnauthorizedSender {
+            sender: sender.to_string(),
+            owner: config.owner.to_string(),
+        });
+    }
+    Ok(())
+}
+
+// ensure_claimer fails with err unless the claim is sent by the claimer.
+// Proofs are public and anyone may submit a claim on behalf of the claimer,
+// but only the claimer may choose options that change how it is paid out.
+pub(crate) fn ensure_claimer(
+    sender: &Addr,
+    claimer_addr: &str,
+    err: ContractError,
+) -> Result<(), ContractError> {
+    if *sender != claimer_addr {
+        return Err(err);
+    }
+    Ok(())
+}
+
+// ensure_setup_owner lets the owner of a governance only drop complete the
+// one time setup of its denom. Once configured, changes go through governance
+// like every other owner action.
+pub(crate) fn ensure_setup_owner(
+    config: &Config,
+    sender: &Addr,
+    configured: bool,
+) -> Result<(), ContractError> {
+    if !config.governance_only || configured {
+        return ensure_owner(config, sender);
+    }
+    if config.owner != *sender {
+        return Err(ContractError::UnauthorizedSender {
+            sender: sender.to_string(),
+            owner: config.owner.to_string(),
+        });
+    }
+    Ok(())
+}
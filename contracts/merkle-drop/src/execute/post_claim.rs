@@ -0,0 +1,242 @@
+use cosmwasm_std::{Env, MessageInfo, Storage, SubMsg, Uint128};
+use osmosis_std::types::cosmos::base::v1beta1::Coin;
+use osmosis_std::types::osmosis::gamm::v1beta1::{
+    MsgJoinSwapExternAmountIn, MsgSwapExactAmountIn, SwapAmountInRoute,
+};
+
+use crate::execute::ensure_claimer;
+use crate::reply::{POST_CLAIM_JOIN_MSG_ID, POST_CLAIM_SWAP_MSG_ID};
+use crate::state::{Config, Distribution, PostClaimAction, PostClaimState, POST_CLAIM_STATE};
+use crate::ContractError;
+
+// validate_post_claim_action checks the post-claim action a claimer set.
+pub(crate) fn validate_post_claim_action(
+    info: &MessageInfo,
+    config: &Config,
+    claimer_addr: &str,
+    action: &Option<PostClaimAction>,
+    lock_duration: Option<u64>,
+) -> Result<(), ContractError> {
+    let action = match action {
+        Some(action) => action,
+        None => return Ok(()),
+    };
+
+    if let Distribution::Cw20 { .. } = config.distribution {
+        return Err(ContractError::UnsupportedDistribution {
+            action: String::from("post_claim_action"),
+        });
+    }
+
+    if lock_duration.is_some() {
+        return Err(ContractError::PostClaimActionWithLock {});
+    }
+
+    ensure_claimer(
+        &info.sender,
+        claimer_addr,
+        ContractError::PostClaimActionRequiresClaimer {},
+    )?;
+
+    match action {
+        PostClaimAction::Swap { routes, .. } if routes.is_empty() => {
+            Err(ContractError::EmptySwapRoutes {})
+        }
+        PostClaimAction::JoinPool { .. } if config.join_pool_id.is_none() => {
+            Err(ContractError::NoJoinPoolConfigured {})
+        }
+        _ => Ok(()),
+    }
+}
+
+// post_claim_msg creates the swap or pool join of amount of denom, held by
+// the contract, on behalf of claimer_addr. Its reply forwards the resulting
+// tokens to the claimer, and a failure reverts the claim.
+pub(crate) fn post_claim_msg(
+    storage: &mut dyn Storage,
+    env: &Env,
+    config: &Config,
+    action: PostClaimAction,
+    claimer_addr: &str,
+    amount: Uint128,
+    denom: &str,
+) -> Result<SubMsg, ContractError> {
+    let token_in = Some(Coin {
+        denom: String::from(denom),
+        amount: amount.to_string(),
+    });
+
+    let (msg, denom_out) = match action {
+        PostClaimAction::Swap {
+            routes,
+            token_out_min_amount,
+        } => {
+            let denom_out = routes
+                .last()
+                .map(|route| route.token_out_denom.clone())
+                .ok_or(ContractError::EmptySwapRoutes {})?;
+
+            let swap_msg = MsgSwapExactAmountIn {
+                sender: env.contract.address.to_string(),
+                routes: routes
+                    .into_iter()
+                    .map(|route| SwapAmountInRoute {
+                        pool_id: route.pool_id,
+                        token_out_denom: route.token_out_denom,
+                    })
+                    .collect(),
+                token_in,
+                token_out_min_amount: token_out_min_amount.to_string(),
+            };
+
+            (
+                SubMsg::reply_on_success(swap_msg, POST_CLAIM_SWAP_MSG_ID),
+                denom_out,
+            )
+        }
+        PostClaimAction::JoinPool {
+            share_out_min_amount,
+        } => {
+            let pool_id = config
+                .join_pool_id
+                .ok_or(ContractError::NoJoinPoolConfigured {})?;
+
+            let join_msg = MsgJoinSwapExternAmountIn {
+                sender: env.contract.address.to_string(),
+                pool_id,
+                token_in,
+                share_out_min_amount: share_out_min_amount.to_string(),
+            };
+
+            (
+                SubMsg::reply_on_success(join_msg, POST_CLAIM_JOIN_MSG_ID),
+                format!("gamm/pool/{}", pool_id),
+            )
+        }
+    };
+
+    POST_CLAIM_STATE.save(
+        storage,
+        &PostClaimState {
+            claimer_addr: String::from(claimer_addr),
+            amount,
+            denom: String::from(denom),
+            denom_out,
+        },
+    )?;
+
+    Ok(msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::CosmosMsg;
+
+    use crate::state::{test_config, SwapRoute};
+
+    const CLAIMER: &str = "claimer";
+
+    fn escrow_config() -> Config {
+        Config {
+            distribution: Distribution::Escrow {
+                denom: String::from("uion"),
+            },
+            join_pool_id: Some(1),
+            ..test_config()
+        }
+    }
+
+    fn swap() -> PostClaimAction {
+        PostClaimAction::Swap {
+            routes: vec![SwapRoute {
+                pool_id: 1,
+                token_out_denom: String::from("uosmo"),
+            }],
+            token_out_min_amount: Uint128::new(90),
+        }
+    }
+
+    #[test]
+    fn validate_post_claim_action_errors() {
+        let config = escrow_config();
+        let claimer = mock_info(CLAIMER, &[]);
+
+        let err = validate_post_claim_action(
+            &mock_info("other", &[]),
+            &config,
+            CLAIMER,
+            &Some(swap()),
+            None,
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::PostClaimActionRequiresClaimer {}
+        ));
+
+        let err = validate_post_claim_action(&claimer, &config, CLAIMER, &Some(swap()), Some(60))
+            .unwrap_err();
+        assert!(matches!(err, ContractError::PostClaimActionWithLock {}));
+
+        let empty_swap = PostClaimAction::Swap {
+            routes: vec![],
+            token_out_min_amount: Uint128::zero(),
+        };
+        let err = validate_post_claim_action(&claimer, &config, CLAIMER, &Some(empty_swap), None)
+            .unwrap_err();
+        assert!(matches!(err, ContractError::EmptySwapRoutes {}));
+
+        let join = PostClaimAction::JoinPool {
+            share_out_min_amount: Uint128::zero(),
+        };
+        let err = validate_post_claim_action(
+            &claimer,
+            &Config {
+                join_pool_id: None,
+                ..escrow_config()
+            },
+            CLAIMER,
+            &Some(join),
+            None,
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::NoJoinPoolConfigured {}));
+
+        validate_post_claim_action(&claimer, &config, CLAIMER, &Some(swap()), None).unwrap();
+        validate_post_claim_action(&mock_info("other", &[]), &config, CLAIMER, &None, None)
+            .unwrap();
+    }
+
+    #[test]
+    fn post_claim_msg_saves_state() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        let msg = post_claim_msg(
+            deps.as_mut().storage,
+            &env,
+            &escrow_config(),
+            PostClaimAction::JoinPool {
+                share_out_min_amount: Uint128::new(5),
+            },
+            CLAIMER,
+            Uint128::new(100),
+            "uion",
+        )
+        .unwrap();
+
+        assert_eq!(POST_CLAIM_JOIN_MSG_ID, msg.id);
+        assert!(matches!(msg.msg, CosmosMsg::Stargate { .. }));
+        assert_eq!(
+            PostClaimState {
+                claimer_addr: String::from(CLAIMER),
+                amount: Uint128::new(100),
+                denom: String::from("uion"),
+                denom_out: String::from("gamm/pool/1"),
+            },
+            POST_CLAIM_STATE.load(deps.as_ref().storage).unwrap()
+        );
+    }
+}
@@ -0,0 +1,307 @@
+use cosmwasm_std::{Deps, DepsMut, Env, MessageInfo, Response, StdResult, Storage, Uint128};
+
+use crate::events::MerkleDropEvent;
+use crate::execute::ensure_owner;
+use crate::msg::RateLimitUsageResponse;
+use crate::state::{Config, RateLimitUsage, RateLimits, RateUsage, CONFIG, RATE_LIMIT_USAGE};
+use crate::ContractError;
+
+pub(crate) fn validate_rate_limits(rate_limits: &RateLimits) -> Result<(), ContractError> {
+    if let Some(limit) = &rate_limits.per_window {
+        if limit.seconds == 0 {
+            return Err(ContractError::InvalidRateLimits {});
+        }
+    }
+    Ok(())
+}
+
+pub fn update_rate_limits(
+    deps: DepsMut,
+    info: MessageInfo,
+    rate_limits: Option<RateLimits>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    ensure_owner(&config, &info.sender)?;
+
+    if let Some(rate_limits) = &rate_limits {
+        validate_rate_limits(rate_limits)?;
+    }
+
+    // usage is counted in windows of the configured length, so it only
+    // carries over to new limits with the same window length.
+    if window_seconds(&config.rate_limits) != window_seconds(&rate_limits) {
+        RATE_LIMIT_USAGE.remove(deps.storage);
+    }
+
+    config.rate_limits = rate_limits;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "update_rate_limits")
+        .add_event(
+            MerkleDropEvent::ConfigUpdate {
+                action: String::from("update_rate_limits"),
+                sender: info.sender.into_string(),
+                denom: None,
+            }
+            .into(),
+        ))
+}
+
+fn window_seconds(rate_limits: &Option<RateLimits>) -> Option<u64> {
+    rate_limits
+        .as_ref()
+        .and_then(|rate_limits| rate_limits.per_window.as_ref())
+        .map(|limit| limit.seconds)
+}
+
+// enforce_rate_limits counts a claim paying out amount against the configured
+// rate limits, and rejects it if it exceeds them. Usage is only saved for
+// accepted claims.
+pub(crate) fn enforce_rate_limits(
+    storage: &mut dyn Storage,
+    env: &Env,
+    config: &Config,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    let rate_limits = match &config.rate_limits {
+        Some(rate_limits) => rate_limits,
+        None => return Ok(()),
+    };
+
+    let mut usage = current_usage(storage, env, rate_limits)?;
+
+    usage.block.claims += 1;
+    usage.block.amount = usage.block.amount.checked_add(amount)?;
+    if let Some(limit) = &rate_limits.per_block {
+        check_limit("block", limit.max_claims, limit.max_amount, &usage.block)?;
+    }
+
+    if let Some(limit) = &rate_limits.per_window {
+        usage.current_window.claims += 1;
+        usage.current_window.amount = usage.current_window.amount.checked_add(amount)?;
+        check_limit(
+            "window",
+            limit.max_claims,
+            limit.max_amount,
+            &window_usage(&usage, limit.seconds, env),
+        )?;
+    }
+
+    RATE_LIMIT_USAGE.save(storage, &usage)?;
+
+    Ok(())
+}
+
+// current_usage loads the saved usage, reset for a new block and moved on
+// to the current window.
+fn current_usage(
+    storage: &dyn Storage,
+    env: &Env,
+    rate_limits: &RateLimits,
+) -> StdResult<RateLimitUsage> {
+    let mut usage = RATE_LIMIT_USAGE.may_load(storage)?.unwrap_or_default();
+
+    if usage.height != env.block.height {
+        usage.height = env.block.height;
+        usage.block = RateUsage::default();
+    }
+
+    if let Some(limit) = &rate_limits.per_window {
+        let window = env.block.time.seconds() / limit.seconds;
+        if usage.window != window {
+            usage.previous_window = if usage.window + 1 == window {
+                usage.current_window.clone()
+            } else {
+                RateUsage::default()
+            };
+            usage.current_window = RateUsage::default();
+            usage.window = window;
+        }
+    }
+
+    Ok(usage)
+}
+
+// window_usage estimates the usage of the rolling window ending now from the
+// current fixed window and the part of the previous one the rolling window
+// still overlaps, assuming its claims were evenly spread.
+fn window_usage(usage: &RateLimitUsage, seconds: u64, env: &Env) -> RateUsage {
+    let remaining = seconds - env.block.time.seconds() % seconds;
+
+    RateUsage {
+        claims: usage.current_window.claims + usage.previous_window.claims * remaining / seconds,
+        amount: usage.current_window.amount
+            + usage
+                .previous_window
+                .amount
+                .multiply_ratio(remaining, seconds),
+    }
+}
+
+fn check_limit(
+    period: &str,
+    max_claims: Option<u64>,
+    max_amount: Option<Uint128>,
+    usage: &RateUsage,
+) -> Result<(), ContractError> {
+    if let Some(max_claims) = max_claims {
+        if usage.claims > max_claims {
+            return Err(ContractError::ClaimRateLimitExceeded {
+                period: String::from(period),
+                max_claims,
+            });
+        }
+    }
+
+    if let Some(max_amount) = max_amount {
+        if usage.amount > max_amount {
+            return Err(ContractError::AmountRateLimitExceeded {
+                period: String::from(period),
+                max_amount,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+pub(crate) fn query_rate_limit_usage(deps: Deps, env: Env) -> StdResult<RateLimitUsageResponse> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let rate_limits = match config.rate_limits {
+        Some(rate_limits) => rate_limits,
+        None => {
+            return Ok(RateLimitUsageResponse {
+                rate_limits: None,
+                block: RateUsage::default(),
+                window: RateUsage::default(),
+            })
+        }
+    };
+
+    let usage = current_usage(deps.storage, &env, &rate_limits)?;
+    let window = match &rate_limits.per_window {
+        Some(limit) => window_usage(&usage, limit.seconds, &env),
+        None => RateUsage::default(),
+    };
+
+    Ok(RateLimitUsageResponse {
+        rate_limits: Some(rate_limits),
+        block: usage.block,
+        window,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env};
+    use cosmwasm_std::Timestamp;
+
+    use crate::state::{test_config, RateLimit, WindowRateLimit};
+
+    const HOUR: u64 = 60 * 60;
+
+    fn config(rate_limits: RateLimits) -> Config {
+        Config {
+            rate_limits: Some(rate_limits),
+            ..test_config()
+        }
+    }
+
+    fn env_at(height: u64, seconds: u64) -> Env {
+        let mut env = mock_env();
+        env.block.height = height;
+        env.block.time = Timestamp::from_seconds(seconds);
+        env
+    }
+
+    #[test]
+    fn per_block_limits() {
+        let mut deps = mock_dependencies();
+        let config = config(RateLimits {
+            per_block: Some(RateLimit {
+                max_claims: Some(2),
+                max_amount: Some(Uint128::new(250)),
+            }),
+            per_window: None,
+        });
+
+        let env = env_at(1, HOUR);
+        enforce_rate_limits(&mut deps.storage, &env, &config, Uint128::new(100)).unwrap();
+
+        let err =
+            enforce_rate_limits(&mut deps.storage, &env, &config, Uint128::new(200)).unwrap_err();
+        assert!(matches!(err, ContractError::AmountRateLimitExceeded { .. }));
+
+        // the rejected claim is not counted.
+        enforce_rate_limits(&mut deps.storage, &env, &config, Uint128::new(100)).unwrap();
+
+        let err =
+            enforce_rate_limits(&mut deps.storage, &env, &config, Uint128::new(1)).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::ClaimRateLimitExceeded { max_claims: 2, .. }
+        ));
+
+        // usage starts over in the next block.
+        let env = env_at(2, HOUR + 5);
+        enforce_rate_limits(&mut deps.storage, &env, &config, Uint128::new(200)).unwrap();
+    }
+
+    #[test]
+    fn per_window_limits_roll_over() {
+        let mut deps = mock_dependencies();
+        let config = config(RateLimits {
+            per_block: None,
+            per_window: Some(WindowRateLimit {
+                seconds: HOUR,
+                max_claims: Some(4),
+                max_amount: None,
+            }),
+        });
+
+        for height in 1..=4 {
+            let env = env_at(height, 10 * HOUR + height);
+            enforce_rate_limits(&mut deps.storage, &env, &config, Uint128::new(1)).unwrap();
+        }
+        let err = enforce_rate_limits(
+            &mut deps.storage,
+            &env_at(5, 10 * HOUR + 5),
+            &config,
+            Uint128::new(1),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::ClaimRateLimitExceeded { .. }));
+
+        // halfway through the next window, half of the previous window's
+        // claims still count.
+        let env = env_at(100, 11 * HOUR + HOUR / 2);
+        for _ in 0..2 {
+            enforce_rate_limits(&mut deps.storage, &env, &config, Uint128::new(1)).unwrap();
+        }
+        enforce_rate_limits(&mut deps.storage, &env, &config, Uint128::new(1)).unwrap_err();
+
+        // the previous window no longer counts two windows on.
+        let env = env_at(200, 13 * HOUR);
+        for _ in 0..4 {
+            enforce_rate_limits(&mut deps.storage, &env, &config, Uint128::new(1)).unwrap();
+        }
+    }
+
+    #[test]
+    fn zero_length_window_rejected() {
+        let err = validate_rate_limits(&RateLimits {
+            per_block: None,
+            per_window: Some(WindowRateLimit {
+                seconds: 0,
+                max_claims: Some(1),
+                max_amount: None,
+            }),
+        })
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidRateLimits {}));
+    }
+}
@@ -0,0 +1,213 @@
+use cosmwasm_std::{Addr, Decimal, Deps, MessageInfo, Order, StdResult, Storage, Uint128};
+use cw_storage_plus::Bound;
+
+use crate::execute::denylist::ensure_not_blocked;
+use crate::execute::ensure_claimer;
+use crate::msg::{ReferralEarningsResponse, ReferrerEarnings};
+use crate::state::{Config, ReferralConfig, ReferralReward, CLAIMED_BY, REFERRAL_EARNINGS};
+use crate::ContractError;
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+pub(crate) fn validate_referral(referral: &ReferralConfig) -> Result<(), ContractError> {
+    if referral.rate > Decimal::one() {
+        return Err(ContractError::InvalidReferralConfig {});
+    }
+    Ok(())
+}
+
+// resolve_referral checks the referrer of a claim paying out paid to the
+// claimer and computes its reward, capped by what the referrer can still earn.
+// None once the referrer reached the cap.
+pub(crate) fn resolve_referral(
+    deps: Deps,
+    info: &MessageInfo,
+    config: &Config,
+    claimer_addr: &str,
+    referrer: Option<String>,
+    paid: Uint128,
+) -> Result<Option<ReferralReward>, ContractError> {
+    let referrer = match referrer {
+        Some(referrer) => referrer,
+        None => return Ok(None),
+    };
+
+    ensure_claimer(
+        &info.sender,
+        claimer_addr,
+        ContractError::ReferralRequiresClaimer {},
+    )?;
+
+    let referral = config
+        .referral
+        .as_ref()
+        .ok_or(ContractError::ReferralsDisabled {})?;
+
+    let referrer_addr = deps.api.addr_validate(&referrer)?;
+    if referrer == claimer_addr || !CLAIMED_BY.has(deps.storage, &referrer) {
+        return Err(ContractError::InvalidReferrer { referrer });
+    }
+    ensure_not_blocked(deps.storage, &referrer)?;
+
+    let earned = REFERRAL_EARNINGS
+        .may_load(deps.storage, &referrer_addr)?
+        .unwrap_or_default()
+        .amount;
+    let amount = (paid * referral.rate).min(referral.cap.saturating_sub(earned));
+
+    if amount.is_zero() {
+        return Ok(None);
+    }
+
+    Ok(Some(ReferralReward { referrer, amount }))
+}
+
+// record_referral adds the reward to the referrer's earnings.
+pub(crate) fn record_referral(storage: &mut dyn Storage, reward: &ReferralReward) -> StdResult<()> {
+    REFERRAL_EARNINGS.update(
+        storage,
+        &Addr::unchecked(&reward.referrer),
+        |earnings| -> StdResult<_> {
+            let mut earnings = earnings.unwrap_or_default();
+            earnings.referrals += 1;
+            earnings.amount = earnings.amount.checked_add(reward.amount)?;
+            Ok(earnings)
+        },
+    )?;
+    Ok(())
+}
+
+pub(crate) fn query_referral_earnings(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ReferralEarningsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after.map(Addr::unchecked);
+    let start = start_after.as_ref().map(Bound::exclusive);
+
+    let referrers = REFERRAL_EARNINGS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            item.map(|(referrer, earnings)| ReferrerEarnings {
+                referrer: referrer.into_string(),
+                referrals: earnings.referrals,
+                amount: earnings.amount,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+
+    Ok(ReferralEarningsResponse { referrers })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_dependencies, mock_info};
+
+    use crate::state::{test_config, ReferralEarnings};
+
+    fn config() -> Config {
+        Config {
+            referral: Some(ReferralConfig {
+                rate: Decimal::percent(10),
+                cap: Uint128::new(150),
+            }),
+            ..test_config()
+        }
+    }
+
+    #[test]
+    fn referral_reward_capped() {
+        let mut deps = mock_dependencies();
+        CLAIMED_BY
+            .save(deps.as_mut().storage, "referrer", &Uint128::new(1000))
+            .unwrap();
+
+        let reward = resolve_referral(
+            deps.as_ref(),
+            &mock_info("claimer", &[]),
+            &config(),
+            "claimer",
+            Some(String::from("referrer")),
+            Uint128::new(1000),
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(Uint128::new(100), reward.amount);
+        record_referral(deps.as_mut().storage, &reward).unwrap();
+
+        // only 50 left under the cap.
+        let reward = resolve_referral(
+            deps.as_ref(),
+            &mock_info("claimer2", &[]),
+            &config(),
+            "claimer2",
+            Some(String::from("referrer")),
+            Uint128::new(1000),
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(Uint128::new(50), reward.amount);
+        record_referral(deps.as_mut().storage, &reward).unwrap();
+
+        assert_eq!(
+            ReferralEarnings {
+                referrals: 2,
+                amount: Uint128::new(150),
+            },
+            REFERRAL_EARNINGS
+                .load(&deps.storage, &Addr::unchecked("referrer"))
+                .unwrap()
+        );
+
+        // nothing once the cap is reached.
+        let reward = resolve_referral(
+            deps.as_ref(),
+            &mock_info("claimer3", &[]),
+            &config(),
+            "claimer3",
+            Some(String::from("referrer")),
+            Uint128::new(1000),
+        )
+        .unwrap();
+        assert_eq!(None, reward);
+    }
+
+    #[test]
+    fn invalid_referrers() {
+        let mut deps = mock_dependencies();
+        CLAIMED_BY
+            .save(deps.as_mut().storage, "claimer", &Uint128::new(1000))
+            .unwrap();
+
+        for referrer in ["claimer", "unclaimed"] {
+            let err = resolve_referral(
+                deps.as_ref(),
+                &mock_info("claimer", &[]),
+                &config(),
+                "claimer",
+                Some(String::from(referrer)),
+                Uint128::new(1000),
+            )
+            .unwrap_err();
+            assert!(matches!(err, ContractError::InvalidReferrer { .. }));
+        }
+
+        let err = resolve_referral(
+            deps.as_ref(),
+            &mock_info("other", &[]),
+            &Config {
+                referral: None,
+                ..config()
+            },
+            "other",
+            Some(String::from("claimer")),
+            Uint128::new(1000),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::ReferralsDisabled {}));
+    }
+}
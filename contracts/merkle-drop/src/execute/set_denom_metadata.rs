@@ -0,0 +1,234 @@
+use cosmwasm_std::{Deps, DepsMut, Env, MessageInfo, Response, StdResult, SubMsg};
+use osmosis_std::shim::Any;
+use osmosis_std::types::cosmos::authz::v1beta1::MsgExec;
+use osmosis_std::types::cosmos::bank::v1beta1::{DenomUnit, Metadata};
+use osmosis_std::types::osmosis::tokenfactory::v1beta1::MsgSetDenomMetadata;
+
+use crate::events::MerkleDropEvent;
+use crate::execute::ensure_setup_owner;
+use crate::msg::DenomMetadataResponse;
+use crate::reply::AUTHZ_EXEC_SET_METADATA_MSG_ID;
+use crate::state::{
+    DenomMetadata, Distribution, CONFIG, DENOM_METADATA, PENDING_DENOM_METADATA, SUBDENOM,
+};
+use crate::ContractError;
+
+const MAX_EXPONENT: u32 = 18;
+const MAX_URI_LENGTH: usize = 256;
+
+// set_denom_metadata issues MsgSetDenomMetadata for the distributed denom on
+// behalf of the owner, who must have granted it to the contract.
+pub fn set_denom_metadata(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    metadata: DenomMetadata,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let configured = DENOM_METADATA.may_load(deps.storage)?.is_some();
+    ensure_setup_owner(&config, &info.sender, configured)?;
+
+    if config.distribution != Distribution::TokenFactory {
+        return Err(ContractError::UnsupportedDistribution {
+            action: String::from("set_denom_metadata"),
+        });
+    }
+
+    validate_denom_metadata(&metadata)?;
+
+    let subdenom = SUBDENOM.load(deps.storage)?;
+    let full_denom = format!("factory/{}/{}", config.owner, subdenom);
+
+    let set_metadata_msg = MsgSetDenomMetadata {
+        sender: config.owner.to_string(),
+        metadata: Some(bank_metadata(&full_denom, &metadata)),
+    };
+
+    let set_metadata_binary: cosmwasm_std::Binary = set_metadata_msg.into();
+
+    let exec_msg = MsgExec {
+        grantee: env.contract.address.to_string(),
+        msgs: vec![Any {
+            type_url: MsgSetDenomMetadata::TYPE_URL.to_string(),
+            value: set_metadata_binary.to_vec(),
+        }],
+    };
+
+    // the metadata is only saved once the exec succeeded, see reply.rs.
+    PENDING_DENOM_METADATA.save(deps.storage, &metadata)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "set_denom_metadata")
+        .add_attribute("symbol", metadata.symbol)
+        .add_submessage(SubMsg::reply_always(
+            exec_msg,
+            AUTHZ_EXEC_SET_METADATA_MSG_ID,
+        ))
+        .add_event(
+            MerkleDropEvent::ConfigUpdate {
+                action: String::from("set_denom_metadata"),
+                sender: info.sender.into_string(),
+                denom: Some(full_denom),
+            }
+            .into(),
+        ))
+}
+
+// validate_denom_metadata rejects metadata the bank module would not accept.
+// The display unit is named after the lowercase symbol, which must therefore
+// be a valid denom.
+pub(crate) fn validate_denom_metadata(metadata: &DenomMetadata) -> Result<(), ContractError> {
+    let invalid = |reason: &str| {
+        Err(ContractError::InvalidDenomMetadata {
+            reason: String::from(reason),
+        })
+    };
+
+    if metadata.name.trim().is_empty() {
+        return invalid("name must not be empty");
+    }
+
+    let symbol = metadata.symbol.as_bytes();
+    if !(3..=12).contains(&symbol.len())
+        || !symbol[0].is_ascii_alphabetic()
+        || !symbol.iter().all(u8::is_ascii_alphanumeric)
+    {
+        return invalid("symbol must be 3 to 12 alphanumeric characters starting with a letter");
+    }
+
+    if metadata.exponent > MAX_EXPONENT {
+        return invalid("exponent must be at most 18");
+    }
+
+    if let Some(uri) = &metadata.uri {
+        if uri.len() > MAX_URI_LENGTH {
+            return invalid("uri must be at most 256 characters");
+        }
+    }
+
+    Ok(())
+}
+
+// bank_metadata describes full_denom as its base unit and, with a non-zero
+// exponent, a display unit named after the symbol.
+fn bank_metadata(full_denom: &str, metadata: &DenomMetadata) -> Metadata {
+    let mut denom_units = vec![DenomUnit {
+        denom: String::from(full_denom),
+        exponent: 0,
+        aliases: vec![],
+    }];
+
+    let display = if metadata.exponent == 0 {
+        String::from(full_denom)
+    } else {
+        let display = metadata.symbol.to_lowercase();
+        denom_units.push(DenomUnit {
+            denom: display.clone(),
+            exponent: metadata.exponent,
+            aliases: vec![],
+        });
+        display
+    };
+
+    Metadata {
+        description: metadata.description.clone(),
+        denom_units,
+        base: String::from(full_denom),
+        display,
+        name: metadata.name.clone(),
+        symbol: metadata.symbol.clone(),
+        uri: metadata.uri.clone().unwrap_or_default(),
+        uri_hash: String::new(),
+    }
+}
+
+pub(crate) fn query_denom_metadata(deps: Deps) -> StdResult<DenomMetadataResponse> {
+    Ok(DenomMetadataResponse {
+        metadata: DENOM_METADATA.may_load(deps.storage)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FULL_DENOM: &str = "factory/osmo1owner/drop";
+
+    fn metadata() -> DenomMetadata {
+        DenomMetadata {
+            name: String::from("Drop Token"),
+            symbol: String::from("DROP"),
+            exponent: 6,
+            description: String::from("Airdropped to early users"),
+            uri: Some(String::from("https://example.com/drop.png")),
+        }
+    }
+
+    #[test]
+    fn bank_metadata_units() {
+        let bank = bank_metadata(FULL_DENOM, &metadata());
+
+        assert_eq!(FULL_DENOM, bank.base);
+        assert_eq!("drop", bank.display);
+        assert_eq!(
+            vec![(FULL_DENOM, 0), ("drop", 6)],
+            bank.denom_units
+                .iter()
+                .map(|unit| (unit.denom.as_str(), unit.exponent))
+                .collect::<Vec<_>>()
+        );
+
+        // without an exponent the base unit is displayed.
+        let bank = bank_metadata(
+            FULL_DENOM,
+            &DenomMetadata {
+                exponent: 0,
+                ..metadata()
+            },
+        );
+        assert_eq!(FULL_DENOM, bank.display);
+        assert_eq!(1, bank.denom_units.len());
+    }
+
+    #[test]
+    fn invalid_denom_metadata() {
+        validate_denom_metadata(&metadata()).unwrap();
+
+        let cases = [
+            DenomMetadata {
+                name: String::from(" "),
+                ..metadata()
+            },
+            DenomMetadata {
+                symbol: String::from("DR"),
+                ..metadata()
+            },
+            DenomMetadata {
+                symbol: String::from("1DROP"),
+                ..metadata()
+            },
+            DenomMetadata {
+                symbol: String::from("DR-OP"),
+                ..metadata()
+            },
+            DenomMetadata {
+                exponent: 19,
+                ..metadata()
+            },
+            DenomMetadata {
+                uri: Some("a".repeat(257)),
+                ..metadata()
+            },
+        ];
+
+        for metadata in cases {
+            let err = validate_denom_metadata(&metadata).unwrap_err();
+            assert!(
+                matches!(err, ContractError::InvalidDenomMetadata { .. }),
+                "{:?}",
+                metadata
+            );
+        }
+    }
+}
@@ -1,16 +1,17 @@
-use cosmwasm_std::{DepsMut, Env, MessageInfo, QuerierWrapper, Response, StdError};
-use osmosis_std::types::{
-    cosmos::authz::v1beta1::AuthzQuerier,
-    osmosis::tokenfactory::v1beta1::{MsgMint, TokenfactoryQuerier},
-};
+use cosmwasm_std::{DepsMut, Env, MessageInfo, Response, StdError};
+use osmosis_std::types::osmosis::tokenfactory::v1beta1::{MsgMint, TokenfactoryQuerier};
 
 use crate::{
-    state::{CONFIG, SUBDENOM},
+    events::MerkleDropEvent,
+    execute::ensure_setup_owner,
+    grants::{
+        grant_expiration, required_spend_limit, validate_grant, validate_send_authorization,
+        BANK_SEND_TYPE_URL,
+    },
+    state::{Distribution, CLAIM_STATS, CONFIG, GRANT_EXPIRATIONS, SUBDENOM},
     ContractError,
 };
 
-pub const BANK_SEND_TYPE_URL: &str = "/cosmos.bank.v1beta1.MsgSend";
-
 pub fn set_subdenom(
     deps: DepsMut,
     env: Env,
@@ -20,10 +21,14 @@ pub fn set_subdenom(
     let config = CONFIG.load(deps.storage)?;
 
     // validate sender
-    if config.owner != info.sender {
-        return Err(ContractError::UnauthorizedSender {
-            sender: info.sender.into_string(),
-            owner: config.owner.into_string(),
+    let configured = SUBDENOM.may_load(deps.storage)?.is_some();
+    ensure_setup_owner(&config, &info.sender, configured)?;
+
+    // escrowed distributions pay out of the contract balance and need
+    // neither a denom nor authz grants.
+    if config.distribution != Distribution::TokenFactory {
+        return Err(ContractError::UnsupportedDistribution {
+            action: String::from("set_subdenom"),
         });
     }
 
@@ -32,7 +37,7 @@ pub fn set_subdenom(
     let full_denom = format!("factory/{}/{}", config.owner, subdenom);
     deps.api
         .debug(&format!("set_subdenom full_denom: {}", full_denom));
-    let response = tf_querier.denom_authority_metadata(full_denom)?;
+    let response = tf_querier.denom_authority_metadata(full_denom.clone())?;
 
     if response.authority_metadata.is_none() {
         return Err(ContractError::Std(StdError::GenericErr {
@@ -46,19 +51,25 @@ pub fn set_subdenom(
         return Err(ContractError::Unauthorized {});
     }
 
-    // ensure that authz grants are created for tokenfactory mint and bank send.
-    validate_grant(
-        &deps.querier,
-        config.owner.as_str(),
-        env.contract.address.as_str(),
-        MsgMint::TYPE_URL,
-    )?;
-    validate_grant(
-        &deps.querier,
-        config.owner.as_str(),
-        env.contract.address.as_str(),
-        BANK_SEND_TYPE_URL,
-    )?;
+    // ensure that authz grants are created for tokenfactory mint and bank send
+    // and that they do not lapse before the claim window closes.
+    let mint_grant = validate_grant(&deps.querier, &env, &config, MsgMint::TYPE_URL)?;
+    let send_grant = validate_grant(&deps.querier, &env, &config, BANK_SEND_TYPE_URL)?;
+
+    // a spend limited send grant must cover everything that claims can still send.
+    let required = required_spend_limit(&config, CLAIM_STATS.load(deps.storage)?.claimed_amount);
+    validate_send_authorization(&send_grant, &full_denom, required)?;
+
+    // remember when the grants lapse so that claims fail with a clear error afterwards.
+    for (msg_type_url, grant) in [
+        (MsgMint::TYPE_URL, mint_grant),
+        (BANK_SEND_TYPE_URL, send_grant),
+    ] {
+        match grant_expiration(&grant) {
+            Some(expiration) => GRANT_EXPIRATIONS.save(deps.storage, msg_type_url, &expiration)?,
+            None => GRANT_EXPIRATIONS.remove(deps.storage, msg_type_url),
+        }
+    }
 
     SUBDENOM.save(deps.storage, &subdenom)?;
 
@@ -66,30 +77,14 @@ pub fn set_subdenom(
 
     Ok(Response::new()
         .add_attribute("method", "set_subdenom")
-        .add_attribute("owner", info.sender)
-        .add_attribute("subdenom", subdenom))
-}
-
-fn validate_grant(
-    querier: &QuerierWrapper,
-    granter: &str,
-    grantee: &str,
-    msg_type_url: &str,
-) -> Result<(), ContractError> {
-    // ensure that authz grant is created
-    let authz_querier = AuthzQuerier::new(querier);
-
-    let grants_response = authz_querier.grants(
-        String::from(granter),
-        String::from(grantee),
-        String::from(msg_type_url),
-        Option::None,
-    )?;
-
-    if grants_response.grants.is_empty() {
-        // TODO: format addresses
-        return Err(ContractError::NoAuthZMintGrant {});
-    }
-
-    Ok(())
+        .add_attribute("owner", info.sender.as_str())
+        .add_attribute("subdenom", subdenom)
+        .add_event(
+            MerkleDropEvent::ConfigUpdate {
+                action: String::from("set_subdenom"),
+                sender: info.sender.into_string(),
+                denom: Some(full_denom),
+            }
+            .into(),
+        ))
 }
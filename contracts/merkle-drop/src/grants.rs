@@ -0,0 +1,398 @@
+use std::str::FromStr;
+
+use cosmwasm_std::{
+    Binary, Decimal, Deps, Env, QuerierWrapper, StdResult, Storage, Timestamp, Uint128,
+};
+use osmosis_std::types::{
+    cosmos::{
+        authz::v1beta1::{AuthzQuerier, GenericAuthorization, Grant},
+        bank::v1beta1::SendAuthorization,
+    },
+    osmosis::tokenfactory::v1beta1::MsgMint,
+};
+
+use crate::msg::{GrantStatus, GrantStatusResponse};
+use crate::state::{Config, Distribution, CONFIG, GRANT_EXPIRATIONS};
+use crate::ContractError;
+
+pub const BANK_SEND_TYPE_URL: &str = "/cosmos.bank.v1beta1.MsgSend";
+
+// REQUIRED_GRANTS are the messages the owner must authorize the contract
+// to execute on its behalf for tokenfactory distributions.
+pub const REQUIRED_GRANTS: [&str; 2] = [MsgMint::TYPE_URL, BANK_SEND_TYPE_URL];
+
+// query_grant returns the grant from granter to grantee for msg_type_url.
+// authz stores at most one grant per message type and answers with an error
+// rather than an empty list when there is no unexpired one.
+pub(crate) fn query_grant(
+    querier: &QuerierWrapper,
+    granter: &str,
+    grantee: &str,
+    msg_type_url: &str,
+) -> Option<Grant> {
+    AuthzQuerier::new(querier)
+        .grants(
+            String::from(granter),
+            String::from(grantee),
+            String::from(msg_type_url),
+            Option::None,
+        )
+        .ok()
+        .and_then(|response| response.grants.into_iter().next())
+}
+
+pub(crate) fn grant_expiration(grant: &Grant) -> Option<Timestamp> {
+    grant.expiration.as_ref().map(|expiration| {
+        Timestamp::from_seconds(expiration.seconds as u64).plus_nanos(expiration.nanos as u64)
+    })
+}
+
+// validate_grant ensures that the owner granted msg_type_url to the contract
+// and that the grant lasts at least until the claim end.
+pub(crate) fn validate_grant(
+    querier: &QuerierWrapper,
+    env: &Env,
+    config: &Config,
+    msg_type_url: &str,
+) -> Result<Grant, ContractError> {
+    let grant = query_grant(
+        querier,
+        config.owner.as_str(),
+        env.contract.address.as_str(),
+        msg_type_url,
+    )
+    .ok_or_else(|| ContractError::NoAuthZGrant {
+        msg_type_url: String::from(msg_type_url),
+    })?;
+
+    if let Some(expiration) = grant_expiration(&grant) {
+        if expiration <= env.block.time {
+            return Err(ContractError::GrantExpired {
+                msg_type_url: String::from(msg_type_url),
+                expiration,
+            });
+        }
+
+        if let Some(claim_end) = config.claim_end {
+            if expiration < claim_end {
+                return Err(ContractError::GrantExpiresBeforeClaimEnd {
+                    msg_type_url: String::from(msg_type_url),
+                    expiration,
+                    claim_end,
+                });
+            }
+        }
+    }
+
+    Ok(grant)
+}
+
+// ensure_grants_active fails with GrantExpired once a grant validated by
+// set_subdenom lapses, unless the owner has renewed it since.
+pub(crate) fn ensure_grants_active(
+    storage: &mut dyn Storage,
+    querier: &QuerierWrapper,
+    env: &Env,
+    config: &Config,
+) -> Result<(), ContractError> {
+    for msg_type_url in REQUIRED_GRANTS {
+        let expiration = match GRANT_EXPIRATIONS.may_load(storage, msg_type_url)? {
+            Some(expiration) if expiration <= env.block.time => expiration,
+            _ => continue,
+        };
+
+        let renewed = query_grant(
+            querier,
+            config.owner.as_str(),
+            env.contract.address.as_str(),
+            msg_type_url,
+        )
+        .map(|grant| grant_expiration(&grant));
+
+        match renewed {
+            Some(None) => GRANT_EXPIRATIONS.remove(storage, msg_type_url),
+            Some(Some(renewed)) if renewed > env.block.time => {
+                GRANT_EXPIRATIONS.save(storage, msg_type_url, &renewed)?
+            }
+            _ => {
+                return Err(ContractError::GrantExpired {
+                    msg_type_url: String::from(msg_type_url),
+                    expiration,
+                })
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// AuthzFailure is a failure of an authz exec that the owner can fix by
+// updating the grants issued to the contract.
+#[derive(Debug, PartialEq)]
+pub(crate) enum AuthzFailure {
+    NotFound,
+    Expired,
+    SpendLimitExceeded,
+}
+
+// classify_authz_failure recognises authz failures in a submessage error.
+// wasmd redacts submessage errors down to their codespace and code, so the
+// redacted form is matched as well as the full error text. An exceeded spend
+// limit is only recognised by its text: redacted, it is the sdk's generic
+// insufficient funds code, which a short balance fails with as well.
+pub(crate) fn classify_authz_failure(reason: &str) -> Option<AuthzFailure> {
+    let matches = |text: &str, redacted: &str| reason.contains(text) || reason.ends_with(redacted);
+
+    if matches("authorization not found", "codespace: authz, code: 2") {
+        Some(AuthzFailure::NotFound)
+    } else if matches("authorization expired", "codespace: authz, code: 6") {
+        Some(AuthzFailure::Expired)
+    } else if reason.contains("spend limit") {
+        Some(AuthzFailure::SpendLimitExceeded)
+    } else {
+        None
+    }
+}
+
+// required_spend_limit is the most that can still be sent out of the owner's
+// account when the total allocation of the tree is known. Claims send the
+// paid out allocation, percentage fee included, along with the lock bonus and
+// the referral reward, both at most their rate of the allocation.
+// claimed_amount counts the full claimed allocations, so the part forfeited
+// to the decay schedule, which is never minted, is not required either.
+pub(crate) fn required_spend_limit(config: &Config, claimed_amount: Uint128) -> Option<Uint128> {
+    let max_bonus = config
+        .lock_options
+        .iter()
+        .map(|option| option.bonus)
+        .max()
+        .unwrap_or_else(Decimal::zero);
+    let referral_rate = config
+        .referral
+        .as_ref()
+        .map_or_else(Decimal::zero, |referral| referral.rate);
+
+    config.total_amount.map(|total_amount| {
+        let remaining = total_amount.saturating_sub(claimed_amount);
+        remaining + remaining * max_bonus + remaining * referral_rate
+    })
+}
+
+// validate_send_authorization accepts generic bank send grants as is and
+// requires send authorizations to have a spend limit in denom that covers
+// what claims can still send, see required_spend_limit. Any positive limit is
+// accepted when the total allocation was not configured.
+pub(crate) fn validate_send_authorization(
+    grant: &Grant,
+    denom: &str,
+    required: Option<Uint128>,
+) -> Result<(), ContractError> {
+    let authorization = match &grant.authorization {
+        Some(authorization) => authorization,
+        None => {
+            return Err(ContractError::NoAuthZGrant {
+                msg_type_url: String::from(BANK_SEND_TYPE_URL),
+            })
+        }
+    };
+
+    if authorization.type_url == GenericAuthorization::TYPE_URL {
+        return Ok(());
+    }
+
+    if authorization.type_url != SendAuthorization::TYPE_URL {
+        return Err(ContractError::UnsupportedAuthorization {
+            type_url: authorization.type_url.clone(),
+        });
+    }
+
+    let send_authorization =
+        SendAuthorization::try_from(Binary::from(authorization.value.clone()))?;
+
+    let spend_limit = send_authorization
+        .spend_limit
+        .iter()
+        .find(|coin| coin.denom == denom)
+        .ok_or_else(|| ContractError::NoSpendLimitForDenom {
+            denom: String::from(denom),
+        })?;
+    let spend_limit = Uint128::from_str(&spend_limit.amount)?;
+
+    let required = required.unwrap_or_else(|| Uint128::new(1));
+    if spend_limit < required {
+        return Err(ContractError::InsufficientSpendLimit {
+            spend_limit,
+            required,
+        });
+    }
+
+    Ok(())
+}
+
+// query_grant_status reports the grants the owner must keep issued to the
+// contract. Escrowed distributions require none.
+pub(crate) fn query_grant_status(deps: Deps, env: Env) -> StdResult<GrantStatusResponse> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if config.distribution != Distribution::TokenFactory {
+        return Ok(GrantStatusResponse { grants: vec![] });
+    }
+
+    let grants = REQUIRED_GRANTS
+        .iter()
+        .map(|msg_type_url| {
+            let grant = query_grant(
+                &deps.querier,
+                config.owner.as_str(),
+                env.contract.address.as_str(),
+                msg_type_url,
+            );
+            let expiration = grant.as_ref().and_then(grant_expiration);
+            let covers_claim_window = grant.is_some()
+                && match (expiration, config.claim_end) {
+                    (None, _) => true,
+                    (Some(expiration), Some(claim_end)) => expiration >= claim_end,
+                    (Some(expiration), None) => expiration > env.block.time,
+                };
+
+            GrantStatus {
+                msg_type_url: String::from(*msg_type_url),
+                found: grant.is_some(),
+                expiration,
+                covers_claim_window,
+            }
+        })
+        .collect();
+
+    Ok(GrantStatusResponse { grants })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env};
+
+    use crate::state::{test_config, LockOption, ReferralConfig};
+
+    #[test]
+    fn classify_authz_failures() {
+        let cases = [
+            (
+                "failed to execute message; message index: 0: authorization not found",
+                Some(AuthzFailure::NotFound),
+            ),
+            ("codespace: authz, code: 2", Some(AuthzFailure::NotFound)),
+            ("authorization expired", Some(AuthzFailure::Expired)),
+            ("codespace: authz, code: 6", Some(AuthzFailure::Expired)),
+            (
+                "requested amount is more than spend limit: insufficient funds",
+                Some(AuthzFailure::SpendLimitExceeded),
+            ),
+            // insufficient funds, whether or not a spend limit was exceeded.
+            ("codespace: sdk, code: 5", None),
+            ("0uosmo is smaller than 100uosmo: insufficient funds", None),
+            ("codespace: authz, code: 22", None),
+            ("codespace: wasm, code: 5", None),
+            ("out of gas", None),
+        ];
+
+        for (reason, expected) in cases {
+            assert_eq!(expected, classify_authz_failure(reason), "{}", reason);
+        }
+    }
+
+    #[test]
+    fn required_spend_limit_covers_bonus_and_reward() {
+        let config = test_config();
+        assert_eq!(None, required_spend_limit(&config, Uint128::zero()));
+
+        let config = Config {
+            total_amount: Some(Uint128::new(1_000)),
+            ..test_config()
+        };
+        assert_eq!(
+            Some(Uint128::new(600)),
+            required_spend_limit(&config, Uint128::new(400))
+        );
+
+        // the largest lock bonus and the referral rate are paid on top.
+        let config = Config {
+            total_amount: Some(Uint128::new(1_000)),
+            lock_options: vec![
+                LockOption {
+                    duration: 86_400,
+                    bonus: Decimal::percent(10),
+                },
+                LockOption {
+                    duration: 604_800,
+                    bonus: Decimal::percent(25),
+                },
+            ],
+            referral: Some(ReferralConfig {
+                rate: Decimal::percent(5),
+                cap: Uint128::new(10),
+            }),
+            ..test_config()
+        };
+        assert_eq!(
+            Some(Uint128::new(780)),
+            required_spend_limit(&config, Uint128::new(400))
+        );
+        assert_eq!(
+            Some(Uint128::zero()),
+            required_spend_limit(&config, Uint128::new(1_000))
+        );
+    }
+
+    #[test]
+    fn ensure_grants_active_unexpired() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        GRANT_EXPIRATIONS
+            .save(
+                deps.as_mut().storage,
+                MsgMint::TYPE_URL,
+                &env.block.time.plus_seconds(1),
+            )
+            .unwrap();
+
+        ensure_grants_active(
+            &mut deps.storage,
+            &QuerierWrapper::new(&deps.querier),
+            &env,
+            &test_config(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn ensure_grants_active_lapsed() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        let expiration = env.block.time.minus_seconds(1);
+        GRANT_EXPIRATIONS
+            .save(deps.as_mut().storage, BANK_SEND_TYPE_URL, &expiration)
+            .unwrap();
+
+        // the mock querier knows no grants, so the lapsed grant was not renewed.
+        let err = ensure_grants_active(
+            &mut deps.storage,
+            &QuerierWrapper::new(&deps.querier),
+            &env,
+            &test_config(),
+        )
+        .unwrap_err();
+
+        assert!(
+            matches!(
+                err,
+                ContractError::GrantExpired { ref msg_type_url, expiration: e }
+                    if msg_type_url == BANK_SEND_TYPE_URL && e == expiration
+            ),
+            "{:?}",
+            err
+        );
+    }
+}
@@ -0,0 +1,327 @@
+use cosmwasm_std::{
+    BankMsg, Binary, Coin, CosmosMsg, DepsMut, Env, Event, MessageInfo, Response, StdResult,
+    Storage, SubMsg,
+};
+use osmosis_std::types::cosmos::base::v1beta1;
+use prost::Message;
+
+use crate::events::MerkleDropEvent;
+use crate::execute::ensure_claimer;
+use crate::msg::IbcLifecycleComplete;
+use crate::reply::IBC_TRANSFER_MSG_ID;
+use crate::state::{
+    Config, Distribution, IbcDestination, IbcTransferState, PostClaimAction, IBC_TRANSFER_STATE,
+    PENDING_IBC_TRANSFERS,
+};
+use crate::ContractError;
+
+pub const DEFAULT_IBC_TIMEOUT_SECONDS: u64 = 60 * 10;
+
+pub const MSG_TRANSFER_TYPE_URL: &str = "/ibc.applications.transfer.v1.MsgTransfer";
+
+// MsgTransfer is the transfer message of ibc-go, along with the memo
+// ibc-hooks reads the ibc_callback from. IbcMsg::Transfer has no memo in
+// CosmWasm 1.1.
+#[derive(Clone, PartialEq, Message)]
+pub struct MsgTransfer {
+    #[prost(string, tag = "1")]
+    pub source_port: String,
+    #[prost(string, tag = "2")]
+    pub source_channel: String,
+    #[prost(message, optional, tag = "3")]
+    pub token: Option<v1beta1::Coin>,
+    #[prost(string, tag = "4")]
+    pub sender: String,
+    #[prost(string, tag = "5")]
+    pub receiver: String,
+    #[prost(uint64, tag = "7")]
+    pub timeout_timestamp: u64,
+    #[prost(string, tag = "8")]
+    pub memo: String,
+}
+
+// validate_ibc_destination checks the IBC destination a claimer set.
+pub(crate) fn validate_ibc_destination(
+    info: &MessageInfo,
+    config: &Config,
+    claimer_addr: &str,
+    destination: &Option<IbcDestination>,
+    lock_duration: Option<u64>,
+    post_claim_action: &Option<PostClaimAction>,
+) -> Result<(), ContractError> {
+    if destination.is_none() {
+        return Ok(());
+    }
+
+    if let Distribution::Cw20 { .. } = config.distribution {
+        return Err(ContractError::UnsupportedDistribution {
+            action: String::from("ibc_destination"),
+        });
+    }
+
+    if lock_duration.is_some() || post_claim_action.is_some() {
+        return Err(ContractError::IbcDestinationConflict {});
+    }
+
+    ensure_claimer(
+        &info.sender,
+        claimer_addr,
+        ContractError::IbcDestinationRequiresClaimer {},
+    )?;
+
+    Ok(())
+}
+
+// ibc_transfer_msg creates the transfer of the claimed amount of denom, held
+// by the contract, less the forfeited part and fee, to the receiver over the
+// channel of the transfer. The claim is recorded in its reply, once the
+// transfer is dispatched. The memo names the contract as ibc_callback, for
+// ibc-hooks to report the acknowledgement or timeout of the packet to it, see
+// ibc_lifecycle_complete.
+pub(crate) fn ibc_transfer_msg(
+    storage: &mut dyn Storage,
+    env: &Env,
+    transfer: IbcTransferState,
+    timeout: Option<u64>,
+) -> StdResult<SubMsg> {
+    let timeout = timeout.unwrap_or(DEFAULT_IBC_TIMEOUT_SECONDS);
+
+    IBC_TRANSFER_STATE.save(storage, &transfer)?;
+
+    let transfer = MsgTransfer {
+        source_port: String::from("transfer"),
+        source_channel: transfer.channel,
+        token: Some(v1beta1::Coin {
+            denom: transfer.denom,
+            amount: (transfer.amount - transfer.forfeited - transfer.fee).to_string(),
+        }),
+        sender: env.contract.address.to_string(),
+        receiver: transfer.receiver,
+        timeout_timestamp: env.block.time.plus_seconds(timeout).nanos(),
+        memo: format!("{{\"ibc_callback\":\"{}\"}}", env.contract.address),
+    };
+
+    let msg = CosmosMsg::Stargate {
+        type_url: String::from(MSG_TRANSFER_TYPE_URL),
+        value: Binary(transfer.encode_to_vec()),
+    };
+
+    Ok(SubMsg::reply_on_success(msg, IBC_TRANSFER_MSG_ID))
+}
+
+// packet_sequence reads the sequence of the packet sent by an IBC transfer
+// from its send_packet event.
+pub(crate) fn packet_sequence(events: &[Event]) -> Option<u64> {
+    events
+        .iter()
+        .filter(|event| event.ty == "send_packet")
+        .flat_map(|event| event.attributes.iter())
+        .find(|attr| attr.key == "packet_sequence")
+        .and_then(|attr| attr.value.parse().ok())
+}
+
+// ibc_lifecycle_complete settles a pending transfer of claimed tokens. The
+// transfer module refunds failed and timed out transfers to the contract,
+// which forwards the refund to the claimer on this chain.
+pub fn ibc_lifecycle_complete(
+    deps: DepsMut,
+    msg: IbcLifecycleComplete,
+) -> Result<Response, ContractError> {
+    let (channel, sequence, reason) = match msg {
+        IbcLifecycleComplete::IbcAck {
+            channel,
+            sequence,
+            success,
+            ..
+        } => (
+            channel,
+            sequence,
+            if success { None } else { Some("error") },
+        ),
+        IbcLifecycleComplete::IbcTimeout { channel, sequence } => {
+            (channel, sequence, Some("timeout"))
+        }
+    };
+
+    let pending = match PENDING_IBC_TRANSFERS.may_load(deps.storage, (&channel, sequence))? {
+        Some(pending) => pending,
+        // not a transfer of claimed tokens.
+        None => return Ok(Response::new()),
+    };
+    PENDING_IBC_TRANSFERS.remove(deps.storage, (&channel, sequence));
+
+    let reason = match reason {
+        Some(reason) => reason,
+        None => {
+            return Ok(Response::new()
+                .add_attribute("method", "ibc_ack")
+                .add_attribute("sequence", sequence.to_string()))
+        }
+    };
+
+    Ok(Response::new()
+        .add_attribute("method", "ibc_refund")
+        .add_message(BankMsg::Send {
+            to_address: pending.claimer_addr.clone(),
+            amount: vec![Coin {
+                denom: pending.denom.clone(),
+                amount: pending.amount,
+            }],
+        })
+        .add_event(
+            MerkleDropEvent::IbcRefund {
+                address: pending.claimer_addr,
+                amount: pending.amount,
+                denom: pending.denom,
+                channel,
+                sequence,
+                reason: String::from(reason),
+            }
+            .into(),
+        ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, MOCK_CONTRACT_ADDR};
+    use cosmwasm_std::Uint128;
+
+    use crate::state::PendingIbcTransfer;
+
+    const CHANNEL: &str = "channel-0";
+
+    fn save_pending(deps: DepsMut) {
+        PENDING_IBC_TRANSFERS
+            .save(
+                deps.storage,
+                (CHANNEL, 7),
+                &PendingIbcTransfer {
+                    claimer_addr: String::from("claimer"),
+                    amount: Uint128::new(100),
+                    denom: String::from("uosmo"),
+                },
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn transfer_registers_ibc_callback() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        let msg = ibc_transfer_msg(
+            deps.as_mut().storage,
+            &env,
+            IbcTransferState {
+                sender: String::from("claimer"),
+                claimer_addr: String::from("claimer"),
+                amount: Uint128::new(100),
+                forfeited: Uint128::new(10),
+                fee: Uint128::new(5),
+                denom: String::from("uosmo"),
+                channel: String::from(CHANNEL),
+                receiver: String::from("cosmos1receiver"),
+            },
+            None,
+        )
+        .unwrap();
+        assert_eq!(IBC_TRANSFER_MSG_ID, msg.id);
+
+        let value = match msg.msg {
+            CosmosMsg::Stargate { type_url, value } => {
+                assert_eq!(MSG_TRANSFER_TYPE_URL, type_url);
+                value
+            }
+            msg => panic!("unexpected message {:?}", msg),
+        };
+
+        assert_eq!(
+            MsgTransfer {
+                source_port: String::from("transfer"),
+                source_channel: String::from(CHANNEL),
+                token: Some(v1beta1::Coin {
+                    denom: String::from("uosmo"),
+                    amount: String::from("85"),
+                }),
+                sender: String::from(MOCK_CONTRACT_ADDR),
+                receiver: String::from("cosmos1receiver"),
+                timeout_timestamp: env
+                    .block
+                    .time
+                    .plus_seconds(DEFAULT_IBC_TIMEOUT_SECONDS)
+                    .nanos(),
+                memo: format!("{{\"ibc_callback\":\"{}\"}}", MOCK_CONTRACT_ADDR),
+            },
+            MsgTransfer::decode(value.as_slice()).unwrap()
+        );
+    }
+
+    #[test]
+    fn packet_sequence_from_send_packet() {
+        let events = vec![
+            Event::new("transfer").add_attribute("amount", "100uosmo"),
+            Event::new("send_packet")
+                .add_attribute("packet_src_channel", CHANNEL)
+                .add_attribute("packet_sequence", "7"),
+        ];
+
+        assert_eq!(Some(7), packet_sequence(&events));
+        assert_eq!(None, packet_sequence(&events[..1]));
+    }
+
+    #[test]
+    fn ibc_ack_success_settles_transfer() {
+        let mut deps = mock_dependencies();
+        save_pending(deps.as_mut());
+
+        let res = ibc_lifecycle_complete(
+            deps.as_mut(),
+            IbcLifecycleComplete::IbcAck {
+                channel: String::from(CHANNEL),
+                sequence: 7,
+                ack: String::from("AQ=="),
+                success: true,
+            },
+        )
+        .unwrap();
+
+        assert!(res.messages.is_empty());
+        assert!(!PENDING_IBC_TRANSFERS.has(&deps.storage, (CHANNEL, 7)));
+    }
+
+    #[test]
+    fn ibc_timeout_refunds_claimer() {
+        let mut deps = mock_dependencies();
+        save_pending(deps.as_mut());
+
+        let res = ibc_lifecycle_complete(
+            deps.as_mut(),
+            IbcLifecycleComplete::IbcTimeout {
+                channel: String::from(CHANNEL),
+                sequence: 7,
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: String::from("claimer"),
+                amount: vec![Coin::new(100, "uosmo")],
+            }),
+            res.messages[0].msg
+        );
+        assert!(!PENDING_IBC_TRANSFERS.has(&deps.storage, (CHANNEL, 7)));
+
+        // the refund is only sent once.
+        let res = ibc_lifecycle_complete(
+            deps.as_mut(),
+            IbcLifecycleComplete::IbcTimeout {
+                channel: String::from(CHANNEL),
+                sequence: 7,
+            },
+        )
+        .unwrap();
+        assert!(res.messages.is_empty());
+    }
+}
@@ -1,8 +1,14 @@
 pub mod contract;
+mod decay;
 mod error;
+pub mod events;
 pub mod execute;
+mod grants;
+pub mod ibc;
 pub mod msg;
+pub mod remote_claim;
 pub mod reply;
 pub mod state;
+pub mod sudo;
 
 pub use crate::error::ContractError;
@@ -1,9 +1,76 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::Uint128;
+use cosmwasm_std::{to_binary, Binary, CosmosMsg, Decimal, StdResult, Timestamp, Uint128, WasmMsg};
+use cw20::Cw20ReceiveMsg;
+
+use crate::state::{
+    ClaimFee, ClaimStats, Config, DecaySchedule, DenomMetadata, Distribution, HashFunction,
+    HookFailurePolicy, IbcDestination, LockOption, PostClaimAction, RateLimits, RateUsage,
+    ReferralConfig, RemoteClaimSource,
+};
+
+/// Upgrades a drop instantiated by an earlier version of the contract.
+#[cw_serde]
+pub struct MigrateMsg {}
 
 #[cw_serde]
 pub struct InstantiateMsg {
     pub merkle_root: String,
+    /// Hash function the merkle tree is built with. Defaults to SHA3-256.
+    pub hash_function: Option<HashFunction>,
+    /// Defaults to minting the owner's tokenfactory denom.
+    pub distribution: Option<Distribution>,
+    /// Time from which claims are rejected. Required for the owner
+    /// to withdraw the remaining escrow.
+    pub claim_end: Option<Timestamp>,
+    /// Sum of all amounts in the tree. When set, a spend limited bank send
+    /// grant must cover the part of it that is not claimed yet.
+    pub total_amount: Option<Uint128>,
+    /// Defaults to the sender. Set to the governance module address for
+    /// drops run on behalf of the chain community.
+    pub owner: Option<String>,
+    /// When true, owner-level execute messages are rejected and the drop
+    /// is administered through sudo only. Defaults to false.
+    pub governance_only: Option<bool>,
+    /// Lock durations claimers can opt into for a bonus. None by default.
+    pub lock_options: Option<Vec<LockOption>>,
+    /// Pool claimers can join with their claim through the join_pool
+    /// post-claim action. None by default.
+    pub join_pool_id: Option<u64>,
+    /// Companion contracts on other chains allowed to relay claims over IBC.
+    /// None by default.
+    pub remote_claim_sources: Option<Vec<RemoteClaimSource>>,
+    /// Scales claimed allocations down over time. Allocations are paid in
+    /// full by default.
+    pub decay: Option<DecaySchedule>,
+    /// Caps the claims paid out per block and per rolling window.
+    /// Unlimited by default.
+    pub rate_limits: Option<RateLimits>,
+    /// Fee charged on claims and routed to a treasury. Free by default.
+    pub claim_fee: Option<ClaimFee>,
+    /// Rewards referrers of claims. Disabled by default.
+    pub referral: Option<ReferralConfig>,
+}
+
+// test_instantiate_msg instantiates a drop with every optional setting left to
+// its default, for unit tests to override with struct update syntax.
+#[cfg(test)]
+pub(crate) fn test_instantiate_msg() -> InstantiateMsg {
+    InstantiateMsg {
+        merkle_root: String::from("root"),
+        hash_function: None,
+        distribution: None,
+        claim_end: None,
+        total_amount: None,
+        owner: None,
+        governance_only: None,
+        lock_options: None,
+        join_pool_id: None,
+        remote_claim_sources: None,
+        decay: None,
+        rate_limits: None,
+        claim_fee: None,
+        referral: None,
+    }
 }
 
 #[cw_serde]
@@ -11,11 +78,184 @@ pub enum ExecuteMsg {
     SetSubDenom {
         subdenom: String,
     },
+    /// Sets the bank metadata of the tokenfactory denom, through the
+    /// MsgSetDenomMetadata grant issued by the owner. Owner only, after SetSubDenom.
+    SetDenomMetadata {
+        metadata: DenomMetadata,
+    },
     Claim {
-        proof: String,
+        proof: ClaimProof,
+        /// Format of the proof. Defaults to legacy, the format of the claims
+        /// sent before the others were introduced.
+        proof_version: Option<ProofVersion>,
         amount: Uint128,
         claimer_addr: String,
+        /// How the claim is paid out. Paid out to the claimer as it is by
+        /// default.
+        options: Option<ClaimOptions>,
+    },
+    /// Escrows the attached funds for claims. Owner only.
+    Fund {},
+    /// Sends the unclaimed escrow to the recipient, or the owner if unset.
+    /// Owner only, after the claim end.
+    WithdrawRemaining {
+        recipient: Option<String>,
     },
+    /// CW20 hook, see ReceiveMsg.
+    Receive(Cw20ReceiveMsg),
+    /// Registers a contract to be sent a ClaimHookMsg on every claim.
+    /// The failure policy defaults to ignore. Owner only.
+    AddHook {
+        address: String,
+        failure_policy: Option<HookFailurePolicy>,
+    },
+    /// Owner only.
+    RemoveHook {
+        address: String,
+    },
+    /// Blocks the addresses from claiming. Owner only.
+    AddToDenylist {
+        addresses: Vec<String>,
+    },
+    /// Owner only.
+    RemoveFromDenylist {
+        addresses: Vec<String>,
+    },
+    /// Replaces the rate limits, or lifts them if unset. Resets the usage
+    /// counted so far. Owner only.
+    UpdateRateLimits {
+        rate_limits: Option<RateLimits>,
+    },
+}
+
+/// ClaimOptions are the per-claim options of a Claim, all unset by default.
+#[cw_serde]
+#[derive(Default)]
+pub struct ClaimOptions {
+    /// Locks the claimed amount and its bonus in the claimer's name for
+    /// one of the configured lock durations. Must be sent by the claimer,
+    /// who must have granted MsgLockTokens to the contract.
+    pub lock_duration: Option<u64>,
+    /// Swaps the claimed amount or joins a pool with it, and delivers the
+    /// result to the claimer. Must be sent by the claimer and cannot be
+    /// combined with a lock.
+    pub post_claim_action: Option<PostClaimAction>,
+    /// Transfers the claimed amount to an account on another chain instead
+    /// of paying it out locally. Must be sent by the claimer and cannot be
+    /// combined with a lock or post-claim action.
+    pub ibc_destination: Option<IbcDestination>,
+    /// Address that referred the claimer, rewarded with a share of the
+    /// claim. It must be another address that already claimed.
+    pub referrer: Option<String>,
+}
+
+/// ClaimProof is the merkle proof of a claim, in one of the formats below.
+/// Its shape must match the proof_version the claim declares, legacy unless
+/// set, so that the proof string sent by existing clients is still accepted
+/// as it is and no other format is ever inferred from a proof.
+#[cw_serde]
+#[serde(untagged)]
+pub enum ClaimProof {
+    /// JSON proof as written by merkle-cli, in which each hash is the list of
+    /// the ASCII bytes of its base64 encoding. Several kilobytes for deep
+    /// trees, kept for existing clients.
+    Legacy(String),
+    /// Raw 32-byte sibling hashes from the leaf up. Bit i of path is set when
+    /// the i-th sibling is on the left. Much cheaper to send and parse.
+    Compact { hashes: Vec<Binary>, path: u64 },
+    /// Raw 32-byte sibling hashes from the leaf up, without sibling order, as
+    /// in an OpenZeppelin proof. Only for the sorted_keccak256 and
+    /// cw20_merkle_airdrop hash functions.
+    Hashes { hashes: Vec<Binary> },
+    /// Hex encoded sibling hashes from the leaf up, as in cw20-merkle-airdrop
+    /// and OpenZeppelin proof files. Only for the sorted_keccak256 and
+    /// cw20_merkle_airdrop hash functions.
+    Hex { hex: Vec<String> },
+}
+
+impl ClaimProof {
+    /// The format the proof is in.
+    pub fn version(&self) -> ProofVersion {
+        match self {
+            ClaimProof::Legacy(_) => ProofVersion::Legacy,
+            ClaimProof::Compact { .. } => ProofVersion::Compact,
+            ClaimProof::Hashes { .. } => ProofVersion::Hashes,
+            ClaimProof::Hex { .. } => ProofVersion::Hex,
+        }
+    }
+}
+
+/// ProofVersion is the format a claim declares its proof in, one per
+/// ClaimProof variant.
+#[cw_serde]
+#[derive(Copy, Default)]
+pub enum ProofVersion {
+    #[default]
+    Legacy,
+    Compact,
+    Hashes,
+    Hex,
+}
+
+/// Messages the chain delivers through the sudo entry point. Governance
+/// actions and ibc-hooks callbacks are separate enums, so that neither can be
+/// delivered as the other.
+#[cw_serde]
+#[serde(untagged)]
+pub enum SudoMsg {
+    Governance(GovernanceSudoMsg),
+    IbcHooks(IbcHooksSudoMsg),
+}
+
+/// Messages that chain governance can execute through the sudo entry point,
+/// whatever the owner.
+#[cw_serde]
+pub enum GovernanceSudoMsg {
+    /// Rejects claims until unpaused.
+    Pause {},
+    Unpause {},
+    /// Replaces the merkle root. Claims already made stay recorded.
+    UpdateRoot {
+        merkle_root: String,
+    },
+    /// Sends the whole escrow to the recipient and pauses the drop.
+    /// Not supported by tokenfactory distributions.
+    Clawback {
+        recipient: String,
+    },
+}
+
+/// Callbacks of the ibc-hooks middleware, which delivers them through the
+/// sudo entry point.
+#[cw_serde]
+pub enum IbcHooksSudoMsg {
+    /// Acknowledgement or timeout of an IBC transfer of claimed tokens.
+    IbcLifecycleComplete(IbcLifecycleComplete),
+}
+
+/// IbcLifecycleComplete is the callback ibc-hooks sends to the contract that
+/// sent a transfer.
+#[cw_serde]
+pub enum IbcLifecycleComplete {
+    IbcAck {
+        /// Source channel of the transfer.
+        channel: String,
+        sequence: u64,
+        ack: String,
+        success: bool,
+    },
+    IbcTimeout {
+        /// Source channel of the transfer.
+        channel: String,
+        sequence: u64,
+    },
+}
+
+/// Messages accepted through the CW20 `Send` hook of the distributed token.
+#[cw_serde]
+pub enum ReceiveMsg {
+    /// Escrows the sent tokens for claims. Owner only.
+    Fund {},
 }
 
 #[cw_serde]
@@ -26,6 +266,45 @@ pub enum QueryMsg {
 
     #[returns(GetSubdenomResponse)]
     GetSubdenom {},
+
+    /// Metadata last set with SetDenomMetadata.
+    #[returns(DenomMetadataResponse)]
+    DenomMetadata {},
+
+    #[returns(Config)]
+    GetConfig {},
+
+    #[returns(ClaimStats)]
+    GetStats {},
+
+    /// Reports the authz grants the owner must keep issued to the contract.
+    #[returns(GrantStatusResponse)]
+    GrantStatus {},
+
+    #[returns(ListHooksResponse)]
+    ListHooks {},
+
+    /// Share of an allocation paid out to a claim made now.
+    #[returns(DecayMultiplierResponse)]
+    DecayMultiplier {},
+
+    /// Addresses blocked from claiming, in ascending order.
+    #[returns(DenylistResponse)]
+    Denylist {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Claims paid out in the current block and rolling window.
+    #[returns(RateLimitUsageResponse)]
+    RateLimitUsage {},
+
+    /// Rewards earned by referrers, in ascending order of address.
+    #[returns(ReferralEarningsResponse)]
+    ReferralEarnings {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
 }
 
 #[cw_serde]
@@ -33,7 +312,141 @@ pub struct GetRootResponse {
     pub root: String,
 }
 
+#[cw_serde]
+pub struct DecayMultiplierResponse {
+    /// Share of an allocation paid out to a claim made now.
+    pub multiplier: Decimal,
+}
+
 #[cw_serde]
 pub struct GetSubdenomResponse {
     pub subdenom: String,
 }
+
+#[cw_serde]
+pub struct DenomMetadataResponse {
+    pub metadata: Option<DenomMetadata>,
+}
+
+#[cw_serde]
+pub struct GrantStatusResponse {
+    pub grants: Vec<GrantStatus>,
+}
+
+#[cw_serde]
+pub struct GrantStatus {
+    pub msg_type_url: String,
+    /// Whether an unexpired grant is issued to the contract.
+    pub found: bool,
+    /// None if the grant is not found or never expires.
+    pub expiration: Option<Timestamp>,
+    /// Whether the grant lasts until the claim end, or is still active
+    /// when no claim end is configured.
+    pub covers_claim_window: bool,
+}
+
+#[cw_serde]
+pub struct ListHooksResponse {
+    pub hooks: Vec<HookResponse>,
+}
+
+#[cw_serde]
+pub struct DenylistResponse {
+    pub addresses: Vec<String>,
+}
+
+#[cw_serde]
+pub struct RateLimitUsageResponse {
+    pub rate_limits: Option<RateLimits>,
+    /// Claims paid out in the current block.
+    pub block: RateUsage,
+    /// Estimated claims paid out in the rolling window ending now. Zero
+    /// without a window limit.
+    pub window: RateUsage,
+}
+
+#[cw_serde]
+pub struct ReferralEarningsResponse {
+    pub referrers: Vec<ReferrerEarnings>,
+}
+
+#[cw_serde]
+pub struct ReferrerEarnings {
+    pub referrer: String,
+    /// Number of rewarded referrals.
+    pub referrals: u64,
+    pub amount: Uint128,
+}
+
+#[cw_serde]
+pub struct HookResponse {
+    pub address: String,
+    pub failure_policy: HookFailurePolicy,
+}
+
+/// ClaimHookMsg is sent to every registered hook once a claim is paid out.
+#[cw_serde]
+pub struct ClaimHookMsg {
+    pub address: String,
+    pub amount: Uint128,
+    pub denom: String,
+}
+
+impl ClaimHookMsg {
+    /// serializes the message wrapped in ClaimHookExecuteMsg.
+    pub fn into_binary(self) -> StdResult<Binary> {
+        to_binary(&ClaimHookExecuteMsg::ClaimHook(self))
+    }
+
+    /// creates a cosmos msg calling the hook contract_addr.
+    pub fn into_cosmos_msg<T: Into<String>>(self, contract_addr: T) -> StdResult<CosmosMsg> {
+        Ok(WasmMsg::Execute {
+            contract_addr: contract_addr.into(),
+            msg: self.into_binary()?,
+            funds: vec![],
+        }
+        .into())
+    }
+}
+
+/// ClaimHookExecuteMsg is the execute message hook contracts must accept.
+#[cw_serde]
+pub enum ClaimHookExecuteMsg {
+    ClaimHook(ClaimHookMsg),
+}
+
+/// RemoteClaimPacket is the IBC packet a companion contract on another chain
+/// sends to claim on behalf of address, once it verified the sender controls it.
+#[cw_serde]
+pub struct RemoteClaimPacket {
+    /// Address in the merkle tree.
+    pub address: String,
+    pub amount: Uint128,
+    /// Proof in any of the formats of a local claim, see ClaimProof.
+    pub proof: ClaimProof,
+    /// Format of the proof, legacy unless set.
+    pub proof_version: Option<ProofVersion>,
+    /// Account on this chain the claim is paid out to.
+    pub recipient: String,
+}
+
+/// RemoteClaimResult is the JSON encoded result of an acknowledged
+/// RemoteClaimPacket.
+#[cw_serde]
+pub struct RemoteClaimResult {
+    /// Sent to the recipient.
+    pub paid: Uint128,
+    /// Percentage claim fee withheld for the treasury. Fixed claim fees are
+    /// not supported with remote claims, see ContractError::RemoteClaimFixedFee.
+    pub fee: Uint128,
+    /// Part of the allocation forfeited to the decay schedule.
+    pub forfeited: Uint128,
+}
+
+/// RemoteClaimAck acknowledges a RemoteClaimPacket. Results carry a
+/// RemoteClaimResult, errors the ContractError the claim failed with.
+#[cw_serde]
+pub enum RemoteClaimAck {
+    Result(Binary),
+    Error(String),
+}
@@ -0,0 +1,500 @@
+use cosmwasm_std::{
+    from_binary, to_binary, BankMsg, Binary, Coin, DepsMut, Env, IbcChannel, IbcOrder, IbcPacket,
+    IbcReceiveResponse, StdResult,
+};
+
+use crate::decay::forfeited_amount;
+use crate::events::{ClaimStage, MerkleDropEvent};
+use crate::execute::claim::{
+    claim_key, claim_leaf, ensure_proof_version, record_claim, verify_proof,
+};
+use crate::execute::claim_fee::{fee_payment, percentage_fee};
+use crate::execute::denylist::ensure_not_blocked;
+use crate::execute::fund::escrow_balance;
+use crate::execute::hooks::remote_claim_hooks;
+use crate::execute::rate_limit::enforce_rate_limits;
+use crate::msg::{RemoteClaimAck, RemoteClaimPacket, RemoteClaimResult};
+use crate::state::{ClaimFee, Config, Distribution, FeeAmount, CLAIMED_ADDRESSES, CONFIG};
+use crate::ContractError;
+
+pub const REMOTE_CLAIM_VERSION: &str = "merkle-drop-claim-1";
+pub const REMOTE_CLAIM_ORDER: IbcOrder = IbcOrder::Unordered;
+
+// validate_remote_claims checks that a drop accepting remote claims can pay
+// them out. Claims are paid out of the escrow while the packet is received, see
+// receive_claim, and fixed fees cannot be paid since no funds come with a packet.
+pub(crate) fn validate_remote_claims(
+    distribution: &Distribution,
+    claim_fee: &Option<ClaimFee>,
+) -> Result<(), ContractError> {
+    if !matches!(distribution, Distribution::Escrow { .. }) {
+        return Err(ContractError::UnsupportedDistribution {
+            action: String::from("remote_claim"),
+        });
+    }
+
+    if let Some(ClaimFee {
+        amount: FeeAmount::Fixed { .. },
+        ..
+    }) = claim_fee
+    {
+        return Err(ContractError::RemoteClaimFixedFee {});
+    }
+
+    Ok(())
+}
+
+// validate_channel only lets the configured companion contracts open claim
+// channels, since they are trusted to check who relays a claim.
+pub(crate) fn validate_channel(
+    config: &Config,
+    channel: &IbcChannel,
+    counterparty_version: Option<&str>,
+) -> Result<(), ContractError> {
+    if channel.order != REMOTE_CLAIM_ORDER
+        || channel.version != REMOTE_CLAIM_VERSION
+        || counterparty_version.map_or(false, |version| version != REMOTE_CLAIM_VERSION)
+    {
+        return Err(ContractError::InvalidRemoteClaimChannel {
+            version: String::from(REMOTE_CLAIM_VERSION),
+        });
+    }
+
+    let known = config.remote_claim_sources.iter().any(|source| {
+        source.connection_id == channel.connection_id
+            && source.port_id == channel.counterparty_endpoint.port_id
+    });
+    if !known {
+        return Err(ContractError::UnknownRemoteClaimSource {
+            connection_id: channel.connection_id.clone(),
+            port_id: channel.counterparty_endpoint.port_id.clone(),
+        });
+    }
+
+    Ok(())
+}
+
+// receive_claim verifies and pays out a claim relayed by a companion contract.
+// ibc_packet_receive acknowledges a failed claim with its error rather than
+// failing, which commits whatever was written before the error. Writes are
+// therefore left to the end: enforce_rate_limits only saves the usage once
+// the limits are met, and record_claim must stay the last fallible step.
+// Drops accepting remote claims are checked by validate_remote_claims.
+pub(crate) fn receive_claim(
+    deps: DepsMut,
+    env: &Env,
+    packet: &IbcPacket,
+) -> Result<IbcReceiveResponse, ContractError> {
+    let RemoteClaimPacket {
+        address,
+        amount,
+        proof,
+        proof_version,
+        recipient,
+    } = from_binary(&packet.data)?;
+    ensure_proof_version(&proof, proof_version.unwrap_or_default())?;
+
+    let config = CONFIG.load(deps.storage)?;
+
+    let denom = match &config.distribution {
+        Distribution::Escrow { denom } => denom.clone(),
+        _ => {
+            return Err(ContractError::UnsupportedDistribution {
+                action: String::from("remote_claim"),
+            })
+        }
+    };
+
+    if config.paused {
+        return Err(ContractError::Paused {});
+    }
+
+    if let Some(claim_end) = config.claim_end {
+        if env.block.time >= claim_end {
+            return Err(ContractError::ClaimWindowClosed { claim_end });
+        }
+    }
+
+    let recipient = deps.api.addr_validate(&recipient)?;
+
+    ensure_not_blocked(deps.storage, &address)?;
+    ensure_not_blocked(deps.storage, recipient.as_str())?;
+
+    let claim = claim_key(&address, amount);
+    if CLAIMED_ADDRESSES.has(deps.storage, &claim) {
+        return Err(ContractError::AlreadyClaimed { claim });
+    }
+
+    verify_proof(
+        &config.merkle_root,
+        &config.hash_function,
+        &proof,
+        claim_leaf(&config.hash_function, &address, amount),
+    )?;
+
+    let forfeited = forfeited_amount(&config.decay, env.block.time, amount);
+    let payout = amount - forfeited;
+    let fee = percentage_fee(&config, payout);
+
+    let balance = escrow_balance(deps.as_ref(), env, &config.distribution)?;
+    if balance < payout {
+        return Err(ContractError::InsufficientEscrow {
+            balance,
+            required: payout,
+        });
+    }
+
+    let hooks = remote_claim_hooks(deps.storage, &address, payout - fee, &denom)?;
+
+    enforce_rate_limits(deps.storage, env, &config, payout)?;
+    record_claim(deps.storage, &address, amount, forfeited)?;
+
+    let mut response = IbcReceiveResponse::new();
+    if let Some((treasury, event)) = fee_payment(&config, &address, fee, &denom) {
+        response = response
+            .add_message(BankMsg::Send {
+                to_address: treasury,
+                amount: vec![Coin {
+                    denom: denom.clone(),
+                    amount: fee,
+                }],
+            })
+            .add_event(event.into());
+    }
+
+    let result = RemoteClaimResult {
+        paid: payout - fee,
+        fee,
+        forfeited,
+    };
+
+    Ok(response
+        .set_ack(ack_success(&result)?)
+        .add_attribute("method", "remote_claim")
+        .add_attribute("recipient", recipient.as_str())
+        .add_message(BankMsg::Send {
+            to_address: recipient.into_string(),
+            amount: vec![Coin {
+                denom: denom.clone(),
+                amount: payout - fee,
+            }],
+        })
+        .add_submessages(hooks)
+        .add_event(
+            MerkleDropEvent::Claim {
+                stage: ClaimStage::Completed,
+                sender: packet.src.port_id.clone(),
+                address,
+                amount,
+                denom,
+            }
+            .into(),
+        ))
+}
+
+pub(crate) fn ack_success(result: &RemoteClaimResult) -> StdResult<Binary> {
+    to_binary(&RemoteClaimAck::Result(to_binary(result)?))
+}
+
+pub(crate) fn ack_error(err: &ContractError) -> StdResult<Binary> {
+    to_binary(&RemoteClaimAck::Error(err.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_dependencies_with_balance, mock_env, mock_ibc_packet_recv,
+        mock_info,
+    };
+    use cosmwasm_std::{coins, Addr, Decimal, IbcChannelOpenMsg, IbcEndpoint, ReplyOn, Uint128};
+    use merkle::proof::Proof;
+    use merkle::Tree;
+
+    use crate::contract::{ibc_channel_open, ibc_packet_receive, instantiate};
+    use crate::msg::{test_instantiate_msg, ClaimProof, InstantiateMsg, ProofVersion};
+    use crate::reply::CLAIM_HOOK_MSG_ID;
+    use crate::state::{HookFailurePolicy, RemoteClaimSource, CLAIM_STATS, HOOKS};
+
+    const CONNECTION: &str = "connection-0";
+    const COMPANION_PORT: &str = "wasm.juno1companion";
+    const REMOTE_ADDR: &str = "juno1claimer";
+    const AMOUNT: u128 = 1_000;
+
+    fn channel(port_id: &str, version: &str) -> IbcChannel {
+        IbcChannel::new(
+            IbcEndpoint {
+                port_id: String::from("wasm.osmo1drop"),
+                channel_id: String::from("channel-1"),
+            },
+            IbcEndpoint {
+                port_id: String::from(port_id),
+                channel_id: String::from("channel-9"),
+            },
+            REMOTE_CLAIM_ORDER,
+            version,
+            CONNECTION,
+        )
+    }
+
+    // remote_claim_packet builds a tree of remote addresses and the packet
+    // claiming REMOTE_ADDR's allocation.
+    fn remote_claim_packet() -> (String, RemoteClaimPacket) {
+        let leaves = vec![
+            format!("{}{}", REMOTE_ADDR, AMOUNT),
+            format!("{}{}", "juno1other", AMOUNT),
+        ];
+        let tree: Tree = Tree::new(&leaves).unwrap();
+
+        let packet = RemoteClaimPacket {
+            address: String::from(REMOTE_ADDR),
+            amount: Uint128::new(AMOUNT),
+            proof: ClaimProof::Legacy(
+                serde_json_wasm::to_string(&tree.find_proof(&leaves[0]).unwrap()).unwrap(),
+            ),
+            proof_version: None,
+            recipient: String::from("osmo1recipient"),
+        };
+
+        (base64::encode(tree.get_root().unwrap()), packet)
+    }
+
+    fn paid(amount: u128) -> RemoteClaimResult {
+        RemoteClaimResult {
+            paid: Uint128::new(amount),
+            fee: Uint128::zero(),
+            forfeited: Uint128::zero(),
+        }
+    }
+
+    fn instantiate_escrow(deps: DepsMut, merkle_root: String, claim_fee: Option<ClaimFee>) {
+        instantiate(
+            deps,
+            mock_env(),
+            mock_info("owner", &[]),
+            InstantiateMsg {
+                merkle_root,
+                distribution: Some(Distribution::Escrow {
+                    denom: String::from("uosmo"),
+                }),
+                remote_claim_sources: Some(vec![RemoteClaimSource {
+                    connection_id: String::from(CONNECTION),
+                    port_id: String::from(COMPANION_PORT),
+                }]),
+                claim_fee,
+                ..test_instantiate_msg()
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn channel_open_only_from_remote_claim_sources() {
+        let mut deps = mock_dependencies();
+        instantiate_escrow(deps.as_mut(), String::from("root"), None);
+
+        let open =
+            |port_id: &str, version: &str| IbcChannelOpenMsg::new_init(channel(port_id, version));
+
+        ibc_channel_open(
+            deps.as_mut(),
+            mock_env(),
+            open(COMPANION_PORT, REMOTE_CLAIM_VERSION),
+        )
+        .unwrap();
+
+        let err = ibc_channel_open(
+            deps.as_mut(),
+            mock_env(),
+            open("wasm.juno1stranger", REMOTE_CLAIM_VERSION),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::UnknownRemoteClaimSource { .. }
+        ));
+
+        let err = ibc_channel_open(deps.as_mut(), mock_env(), open(COMPANION_PORT, "ics20-1"))
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::InvalidRemoteClaimChannel { .. }
+        ));
+    }
+
+    #[test]
+    fn remote_claim_pays_out_recipient() {
+        let mut deps = mock_dependencies_with_balance(&coins(10_000, "uosmo"));
+        let (root, packet) = remote_claim_packet();
+        instantiate_escrow(deps.as_mut(), root, None);
+
+        let msg = mock_ibc_packet_recv("channel-1", &packet).unwrap();
+        let res = ibc_packet_receive(deps.as_mut(), mock_env(), msg).unwrap();
+
+        assert_eq!(ack_success(&paid(AMOUNT)).unwrap(), res.acknowledgement);
+        assert_eq!(
+            cosmwasm_std::CosmosMsg::Bank(BankMsg::Send {
+                to_address: String::from("osmo1recipient"),
+                amount: coins(AMOUNT, "uosmo"),
+            }),
+            res.messages[0].msg
+        );
+        assert_eq!(1, CLAIM_STATS.load(&deps.storage).unwrap().claimed_count);
+
+        // a second claim is acknowledged with the error.
+        let msg = mock_ibc_packet_recv("channel-1", &packet).unwrap();
+        let res = ibc_packet_receive(deps.as_mut(), mock_env(), msg).unwrap();
+
+        let err = ContractError::AlreadyClaimed {
+            claim: claim_key(REMOTE_ADDR, Uint128::new(AMOUNT)),
+        };
+        assert_eq!(ack_error(&err).unwrap(), res.acknowledgement);
+        assert!(res.messages.is_empty());
+    }
+
+    #[test]
+    fn remote_claim_hook_failures_acknowledged() {
+        let mut deps = mock_dependencies_with_balance(&coins(10_000, "uosmo"));
+        let (root, packet) = remote_claim_packet();
+        instantiate_escrow(deps.as_mut(), root, None);
+        HOOKS
+            .save(
+                deps.as_mut().storage,
+                &Addr::unchecked("hook"),
+                &HookFailurePolicy::Revert,
+            )
+            .unwrap();
+
+        let msg = mock_ibc_packet_recv("channel-1", &packet).unwrap();
+        let res = ibc_packet_receive(deps.as_mut(), mock_env(), msg).unwrap();
+
+        // even a hook with the revert policy cannot fail the packet.
+        assert_eq!(ack_success(&paid(AMOUNT)).unwrap(), res.acknowledgement);
+        let hook = res.messages.last().unwrap();
+        assert_eq!(ReplyOn::Error, hook.reply_on);
+        assert_eq!(CLAIM_HOOK_MSG_ID, hook.id);
+    }
+
+    #[test]
+    fn remote_claim_compact_proof() {
+        let mut deps = mock_dependencies_with_balance(&coins(10_000, "uosmo"));
+        let (root, packet) = remote_claim_packet();
+        instantiate_escrow(deps.as_mut(), root, None);
+
+        let compact = match &packet.proof {
+            ClaimProof::Legacy(proof) => serde_json_wasm::from_str::<Proof>(proof)
+                .unwrap()
+                .to_compact()
+                .unwrap(),
+            _ => unreachable!(),
+        };
+        let packet = RemoteClaimPacket {
+            proof: ClaimProof::Compact {
+                hashes: compact
+                    .hashes
+                    .iter()
+                    .map(|hash| Binary::from(hash.as_ref()))
+                    .collect(),
+                path: compact.path,
+            },
+            ..packet
+        };
+
+        // like local claims, the format must be declared.
+        let msg = mock_ibc_packet_recv("channel-1", &packet).unwrap();
+        let res = ibc_packet_receive(deps.as_mut(), mock_env(), msg).unwrap();
+        let err = ContractError::ProofVersionMismatch {
+            version: ProofVersion::Legacy,
+        };
+        assert_eq!(ack_error(&err).unwrap(), res.acknowledgement);
+
+        let msg = mock_ibc_packet_recv(
+            "channel-1",
+            &RemoteClaimPacket {
+                proof_version: Some(ProofVersion::Compact),
+                ..packet
+            },
+        )
+        .unwrap();
+        let res = ibc_packet_receive(deps.as_mut(), mock_env(), msg).unwrap();
+        assert_eq!(ack_success(&paid(AMOUNT)).unwrap(), res.acknowledgement);
+    }
+
+    #[test]
+    fn remote_claim_ack_reports_fee() {
+        let mut deps = mock_dependencies_with_balance(&coins(10_000, "uosmo"));
+        let (root, packet) = remote_claim_packet();
+        instantiate_escrow(
+            deps.as_mut(),
+            root,
+            Some(ClaimFee {
+                amount: FeeAmount::Percentage {
+                    rate: Decimal::percent(10),
+                },
+                treasury: Addr::unchecked("treasury"),
+            }),
+        );
+
+        let msg = mock_ibc_packet_recv("channel-1", &packet).unwrap();
+        let res = ibc_packet_receive(deps.as_mut(), mock_env(), msg).unwrap();
+
+        let result = RemoteClaimResult {
+            paid: Uint128::new(900),
+            fee: Uint128::new(100),
+            forfeited: Uint128::zero(),
+        };
+        assert_eq!(ack_success(&result).unwrap(), res.acknowledgement);
+    }
+
+    #[test]
+    fn remote_claims_need_escrow_without_fixed_fee() {
+        let err = validate_remote_claims(&Distribution::TokenFactory, &None).unwrap_err();
+        assert!(matches!(err, ContractError::UnsupportedDistribution { .. }));
+
+        let cw20 = Distribution::Cw20 {
+            address: Addr::unchecked("cw20"),
+        };
+        let err = validate_remote_claims(&cw20, &None).unwrap_err();
+        assert!(matches!(err, ContractError::UnsupportedDistribution { .. }));
+
+        let escrow = Distribution::Escrow {
+            denom: String::from("uosmo"),
+        };
+        let err = validate_remote_claims(
+            &escrow,
+            &Some(ClaimFee {
+                amount: FeeAmount::Fixed {
+                    coin: Coin::new(10, "uosmo"),
+                },
+                treasury: Addr::unchecked("treasury"),
+            }),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::RemoteClaimFixedFee {}));
+
+        validate_remote_claims(&escrow, &None).unwrap();
+    }
+
+    #[test]
+    fn remote_claim_invalid_proof_acknowledged() {
+        let mut deps = mock_dependencies_with_balance(&coins(10_000, "uosmo"));
+        let (root, packet) = remote_claim_packet();
+        instantiate_escrow(deps.as_mut(), root, None);
+
+        let msg = mock_ibc_packet_recv(
+            "channel-1",
+            &RemoteClaimPacket {
+                amount: Uint128::new(AMOUNT + 1),
+                ..packet
+            },
+        )
+        .unwrap();
+        let res = ibc_packet_receive(deps.as_mut(), mock_env(), msg).unwrap();
+
+        assert_eq!(
+            ack_error(&ContractError::FailedVerifyProof {}).unwrap(),
+            res.acknowledgement
+        );
+        assert_eq!(0, CLAIM_STATS.load(&deps.storage).unwrap().claimed_count);
+    }
+}
@@ -1,24 +1,43 @@
-use cosmwasm_std::{DepsMut, Reply, Response, SubMsg, SubMsgResponse, SubMsgResult};
+use cosmwasm_std::{
+    BankMsg, Binary, DepsMut, Env, Event, Reply, Response, StdError, Storage, SubMsg,
+    SubMsgResponse, SubMsgResult, Uint128,
+};
 use osmosis_std::shim::Any;
 use osmosis_std::types::cosmos::authz::v1beta1::MsgExec;
 use osmosis_std::types::cosmos::bank::v1beta1::MsgSend;
 use osmosis_std::types::cosmos::base::v1beta1::Coin;
+use osmosis_std::types::osmosis::gamm::v1beta1::{
+    MsgJoinSwapExternAmountIn, MsgJoinSwapExternAmountInResponse, MsgSwapExactAmountIn,
+    MsgSwapExactAmountInResponse,
+};
+use osmosis_std::types::osmosis::lockup::MsgLockTokens;
+use osmosis_std::types::osmosis::tokenfactory::v1beta1::{MsgMint, MsgSetDenomMetadata};
 
-use crate::state::CONFIG;
-use crate::{
-    execute::set_subdenom::BANK_SEND_TYPE_URL,
-    state::{CLAIMED_ADDRESSES, REPLY_STATE},
-    ContractError,
+use crate::events::{ClaimStage, MerkleDropEvent};
+use crate::execute::claim::record_claim;
+use crate::execute::claim_fee::fee_payment;
+use crate::execute::hooks::claim_hooks;
+use crate::execute::lock::lock_tokens;
+use crate::execute::post_claim::post_claim_msg;
+use crate::grants::{classify_authz_failure, AuthzFailure, BANK_SEND_TYPE_URL};
+use crate::ibc::{ibc_transfer_msg, packet_sequence};
+use crate::state::{
+    IbcTransferState, MintReplyState, PendingIbcTransfer, CONFIG, DENOM_METADATA,
+    GRANT_EXPIRATIONS, IBC_TRANSFER_STATE, PENDING_DENOM_METADATA, PENDING_IBC_TRANSFERS,
+    POST_CLAIM_STATE, REPLY_STATE,
 };
+use crate::ContractError;
 
 pub const AUTHZ_EXEC_MINT_MSG_ID: u64 = 1;
 pub const AUTHZ_EXEC_SEND_MSG_ID: u64 = 2;
+pub const CLAIM_HOOK_MSG_ID: u64 = 3;
+pub const AUTHZ_EXEC_LOCK_MSG_ID: u64 = 4;
+pub const POST_CLAIM_SWAP_MSG_ID: u64 = 5;
+pub const POST_CLAIM_JOIN_MSG_ID: u64 = 6;
+pub const IBC_TRANSFER_MSG_ID: u64 = 7;
+pub const AUTHZ_EXEC_SET_METADATA_MSG_ID: u64 = 8;
 
-pub fn handle_mint_reply(
-    deps: DepsMut,
-    msg: Reply,
-    contract_address: String,
-) -> Result<Response, ContractError> {
+pub fn handle_mint_reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
     deps.api.debug("mint reply reached");
 
     match msg.result {
@@ -27,42 +46,96 @@ pub fn handle_mint_reply(
 
             let mint_reply_state = REPLY_STATE.load(deps.storage, AUTHZ_EXEC_MINT_MSG_ID)?;
 
-            let owner = CONFIG.load(deps.storage)?.owner;
+            let config = CONFIG.load(deps.storage)?;
+            let owner = config.owner.clone();
+
+            // tokens routed through a post-claim action or IBC are sent to the
+            // contract, which swaps, joins the pool or transfers them in the send reply.
+            let recipient = if mint_reply_state.post_claim_action.is_some()
+                || mint_reply_state.ibc_destination.is_some()
+            {
+                env.contract.address.to_string()
+            } else {
+                mint_reply_state.claimer_addr.clone()
+            };
 
             let msg_send = MsgSend {
                 from_address: owner.to_string(),
-                to_address: mint_reply_state.claimer_addr,
+                to_address: recipient,
                 amount: vec![Coin {
-                    denom: mint_reply_state.denom,
-                    amount: mint_reply_state.amount.to_string(),
+                    denom: mint_reply_state.denom.clone(),
+                    amount: (mint_reply_state.amount
+                        - mint_reply_state.forfeited
+                        - mint_reply_state.fee)
+                        .checked_add(mint_reply_state.bonus)?
+                        .to_string(),
                 }],
             };
 
-            let msg_send_binary: cosmwasm_std::Binary = msg_send.into();
+            let mut msgs = vec![bank_send_any(msg_send)];
+            let mut response = Response::new();
 
-            let msg_send_any = Any {
-                type_url: String::from(BANK_SEND_TYPE_URL),
-                value: msg_send_binary.to_vec(),
-            };
+            // the fee is sent to the treasury in the same exec.
+            if let Some((treasury, event)) = fee_payment(
+                &config,
+                &mint_reply_state.claimer_addr,
+                mint_reply_state.fee,
+                &mint_reply_state.denom,
+            ) {
+                msgs.push(bank_send_any(MsgSend {
+                    from_address: owner.to_string(),
+                    to_address: treasury,
+                    amount: vec![Coin {
+                        denom: mint_reply_state.denom.clone(),
+                        amount: mint_reply_state.fee.to_string(),
+                    }],
+                }));
+                response = response.add_event(event.into());
+            }
+
+            if let Some(referral) = &mint_reply_state.referral {
+                msgs.push(bank_send_any(MsgSend {
+                    from_address: owner.to_string(),
+                    to_address: referral.referrer.clone(),
+                    amount: vec![Coin {
+                        denom: mint_reply_state.denom.clone(),
+                        amount: referral.amount.to_string(),
+                    }],
+                }));
+                response = response.add_event(
+                    MerkleDropEvent::Referral {
+                        referrer: referral.referrer.clone(),
+                        address: mint_reply_state.claimer_addr.clone(),
+                        amount: referral.amount,
+                        denom: mint_reply_state.denom.clone(),
+                    }
+                    .into(),
+                );
+            }
 
             let exec_msg = MsgExec {
-                grantee: contract_address,
-                msgs: vec![msg_send_any],
+                grantee: env.contract.address.to_string(),
+                msgs,
             };
 
-            return Ok(Response::new()
-                .add_submessage(SubMsg::reply_on_success(exec_msg, AUTHZ_EXEC_SEND_MSG_ID))
-                .add_attribute("reply", "tf_mint"));
+            Ok(response
+                .add_submessage(SubMsg::reply_always(exec_msg, AUTHZ_EXEC_SEND_MSG_ID))
+                .add_event(claim_event(ClaimStage::Minted, mint_reply_state)))
         }
-        SubMsgResult::Err(e) => {
-            deps.api.debug(&e);
+        SubMsgResult::Err(reason) => {
+            deps.api.debug(&reason);
+
+            Err(submsg_failure(
+                deps.storage,
+                &env,
+                MsgMint::TYPE_URL,
+                reason,
+            ))
         }
     }
-
-    Err(ContractError::FailedToMint {})
 }
 
-pub fn handle_send_reply(deps: DepsMut, msg: Reply) -> Result<Response, ContractError> {
+pub fn handle_send_reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
     deps.api.debug("send reply reached");
 
     match msg.result {
@@ -71,21 +144,558 @@ pub fn handle_send_reply(deps: DepsMut, msg: Reply) -> Result<Response, Contract
 
             let mint_reply_state = REPLY_STATE.load(deps.storage, AUTHZ_EXEC_MINT_MSG_ID)?;
 
-            CLAIMED_ADDRESSES.save(deps.storage, &mint_reply_state.claimer_addr, &true)?;
-
             // Prune mint reply state
             REPLY_STATE.remove(deps.storage, AUTHZ_EXEC_MINT_MSG_ID);
 
-            return Ok(Response::new()
-                .add_attribute("reply", "send")
-                .add_attribute("merkle-drop-denom", mint_reply_state.denom)
-                .add_attribute("merkle-drop-amount", mint_reply_state.amount.to_string())
-                .add_attribute("merkle-drop-receiver", mint_reply_state.claimer_addr));
+            // an IBC claim is recorded once the transfer is dispatched, see
+            // handle_ibc_transfer_reply.
+            if let Some(destination) = mint_reply_state.ibc_destination {
+                return Ok(Response::new().add_submessage(ibc_transfer_msg(
+                    deps.storage,
+                    &env,
+                    IbcTransferState {
+                        sender: mint_reply_state.sender,
+                        claimer_addr: mint_reply_state.claimer_addr,
+                        amount: mint_reply_state.amount,
+                        forfeited: mint_reply_state.forfeited,
+                        fee: mint_reply_state.fee,
+                        denom: mint_reply_state.denom,
+                        channel: destination.channel,
+                        receiver: destination.receiver,
+                    },
+                    destination.timeout,
+                )?));
+            }
+
+            record_claim(
+                deps.storage,
+                &mint_reply_state.claimer_addr,
+                mint_reply_state.amount,
+                mint_reply_state.forfeited,
+            )?;
+
+            let paid = mint_reply_state.amount - mint_reply_state.forfeited - mint_reply_state.fee;
+
+            let hooks = claim_hooks(
+                deps.storage,
+                &mint_reply_state.claimer_addr,
+                paid,
+                &mint_reply_state.denom,
+            )?;
+
+            let mut response = Response::new();
+
+            if let Some(action) = mint_reply_state.post_claim_action.clone() {
+                let config = CONFIG.load(deps.storage)?;
+                response = response.add_submessage(post_claim_msg(
+                    deps.storage,
+                    &env,
+                    &config,
+                    action,
+                    &mint_reply_state.claimer_addr,
+                    paid,
+                    &mint_reply_state.denom,
+                )?);
+            }
+
+            // the minted amount and bonus are locked once they reached the claimer.
+            if let Some(duration) = mint_reply_state.lock_duration {
+                let payout = paid.checked_add(mint_reply_state.bonus)?;
+
+                response = response
+                    .add_submessage(lock_tokens(
+                        &env,
+                        &mint_reply_state.claimer_addr,
+                        duration,
+                        payout,
+                        &mint_reply_state.denom,
+                    ))
+                    .add_event(
+                        MerkleDropEvent::Lock {
+                            address: mint_reply_state.claimer_addr.clone(),
+                            amount: payout,
+                            denom: mint_reply_state.denom.clone(),
+                            duration,
+                            bonus: mint_reply_state.bonus,
+                        }
+                        .into(),
+                    );
+            }
+
+            Ok(response
+                .add_submessages(hooks)
+                .add_event(claim_event(ClaimStage::Completed, mint_reply_state)))
+        }
+        SubMsgResult::Err(reason) => {
+            deps.api.debug(&reason);
+
+            if classify_authz_failure(&reason) == Some(AuthzFailure::SpendLimitExceeded) {
+                let state = REPLY_STATE.load(deps.storage, AUTHZ_EXEC_MINT_MSG_ID)?;
+                return Err(ContractError::SpendLimitExceeded {
+                    amount: (state.amount - state.forfeited)
+                        .checked_add(state.bonus)?
+                        .checked_add(
+                            state
+                                .referral
+                                .map_or_else(Uint128::zero, |referral| referral.amount),
+                        )?,
+                    denom: state.denom,
+                });
+            }
+
+            Err(submsg_failure(
+                deps.storage,
+                &env,
+                BANK_SEND_TYPE_URL,
+                reason,
+            ))
+        }
+    }
+}
+
+// handle_lock_reply fails the claim when the claimer's tokens could not be
+// locked, so that claimers who opted into a lock never keep the bonus unlocked.
+pub fn handle_lock_reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.result {
+        SubMsgResult::Ok(_) => Ok(Response::new()),
+        SubMsgResult::Err(reason) => {
+            deps.api.debug(&reason);
+
+            Err(submsg_failure(
+                deps.storage,
+                &env,
+                MsgLockTokens::TYPE_URL,
+                reason,
+            ))
+        }
+    }
+}
+
+// handle_set_denom_metadata_reply saves the metadata once it was set, or fails
+// SetDenomMetadata with the grant problem behind it.
+pub fn handle_set_denom_metadata_reply(
+    deps: DepsMut,
+    env: Env,
+    msg: Reply,
+) -> Result<Response, ContractError> {
+    let metadata = PENDING_DENOM_METADATA.load(deps.storage)?;
+    PENDING_DENOM_METADATA.remove(deps.storage);
+
+    match msg.result {
+        SubMsgResult::Ok(_) => {
+            DENOM_METADATA.save(deps.storage, &metadata)?;
+            Ok(Response::new())
         }
-        SubMsgResult::Err(e) => {
-            deps.api.debug(&e);
+        SubMsgResult::Err(reason) => {
+            deps.api.debug(&reason);
+
+            Err(submsg_failure(
+                deps.storage,
+                &env,
+                MsgSetDenomMetadata::TYPE_URL,
+                reason,
+            ))
         }
     }
+}
+
+// handle_post_claim_reply is only reached once the swap or pool join of a
+// post-claim action succeeded. It forwards the tokens that came out to the claimer.
+pub fn handle_post_claim_reply(deps: DepsMut, msg: Reply) -> Result<Response, ContractError> {
+    let state = POST_CLAIM_STATE.load(deps.storage)?;
+    POST_CLAIM_STATE.remove(deps.storage);
+
+    let data = match msg.result {
+        SubMsgResult::Ok(SubMsgResponse {
+            data: Some(data), ..
+        }) => Some(data),
+        _ => None,
+    };
+
+    let (action, amount_out) = if msg.id == POST_CLAIM_SWAP_MSG_ID {
+        let response: MsgSwapExactAmountInResponse =
+            post_claim_response(data, MsgSwapExactAmountIn::TYPE_URL)?;
+        ("swap", response.token_out_amount)
+    } else {
+        let response: MsgJoinSwapExternAmountInResponse =
+            post_claim_response(data, MsgJoinSwapExternAmountIn::TYPE_URL)?;
+        ("join_pool", response.share_out_amount)
+    };
+    let amount_out: Uint128 = amount_out.parse()?;
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: state.claimer_addr.clone(),
+            amount: vec![cosmwasm_std::Coin {
+                denom: state.denom_out.clone(),
+                amount: amount_out,
+            }],
+        })
+        .add_event(
+            MerkleDropEvent::PostClaim {
+                action: String::from(action),
+                address: state.claimer_addr,
+                amount: state.amount,
+                denom: state.denom,
+                amount_out,
+                denom_out: state.denom_out,
+            }
+            .into(),
+        ))
+}
+
+fn post_claim_response<T>(data: Option<Binary>, msg_type_url: &str) -> Result<T, ContractError>
+where
+    T: TryFrom<Binary, Error = StdError>,
+{
+    let data = data.ok_or_else(|| ContractError::MissingPostClaimResponse {
+        msg_type_url: String::from(msg_type_url),
+    })?;
+    Ok(T::try_from(data)?)
+}
+
+// handle_ibc_transfer_reply is only reached once the IBC transfer of a claim
+// was dispatched. It records the claim and tracks the packet until it is
+// acknowledged, see ibc_lifecycle_complete.
+pub fn handle_ibc_transfer_reply(deps: DepsMut, msg: Reply) -> Result<Response, ContractError> {
+    let state = IBC_TRANSFER_STATE.load(deps.storage)?;
+    IBC_TRANSFER_STATE.remove(deps.storage);
+
+    let sequence = match msg.result {
+        SubMsgResult::Ok(SubMsgResponse { events, .. }) => packet_sequence(&events),
+        SubMsgResult::Err(_) => None,
+    }
+    .ok_or(ContractError::MissingPacketSequence {})?;
+
+    record_claim(
+        deps.storage,
+        &state.claimer_addr,
+        state.amount,
+        state.forfeited,
+    )?;
+
+    let transferred = state.amount - state.forfeited - state.fee;
+
+    PENDING_IBC_TRANSFERS.save(
+        deps.storage,
+        (&state.channel, sequence),
+        &PendingIbcTransfer {
+            claimer_addr: state.claimer_addr.clone(),
+            amount: transferred,
+            denom: state.denom.clone(),
+        },
+    )?;
+
+    let hooks = claim_hooks(deps.storage, &state.claimer_addr, transferred, &state.denom)?;
+
+    Ok(Response::new()
+        .add_submessages(hooks)
+        .add_event(
+            MerkleDropEvent::IbcTransfer {
+                address: state.claimer_addr.clone(),
+                amount: transferred,
+                denom: state.denom.clone(),
+                channel: state.channel,
+                receiver: state.receiver,
+                sequence,
+            }
+            .into(),
+        )
+        .add_event(
+            MerkleDropEvent::Claim {
+                stage: ClaimStage::Completed,
+                sender: state.sender,
+                address: state.claimer_addr,
+                amount: state.amount,
+                denom: state.denom,
+            }
+            .into(),
+        ))
+}
 
-    Err(ContractError::FailedToMint {})
+// handle_hook_reply is only reached when a hook with the ignore failure
+// policy failed. The failure is recorded and the claim goes through.
+pub fn handle_hook_reply(deps: DepsMut, msg: Reply) -> Result<Response, ContractError> {
+    match msg.result {
+        SubMsgResult::Ok(_) => Ok(Response::new()),
+        SubMsgResult::Err(reason) => {
+            deps.api.debug(&format!("claim hook failed: {}", reason));
+
+            Ok(Response::new()
+                .add_attribute("claim_hook", "failed")
+                .add_attribute("reason", reason))
+        }
+    }
+}
+
+fn bank_send_any(msg_send: MsgSend) -> Any {
+    let msg_send_binary: Binary = msg_send.into();
+
+    Any {
+        type_url: String::from(BANK_SEND_TYPE_URL),
+        value: msg_send_binary.to_vec(),
+    }
+}
+
+fn claim_event(stage: ClaimStage, state: MintReplyState) -> Event {
+    MerkleDropEvent::Claim {
+        stage,
+        sender: state.sender,
+        address: state.claimer_addr,
+        amount: state.amount,
+        denom: state.denom,
+    }
+    .into()
+}
+
+// submsg_failure maps the error of a failed authz exec of msg_type_url to
+// the grant problem behind it, or to MintFailed / SendFailed /
+// SetDenomMetadataFailed / LockFailed with the underlying error text when it
+// is not a known authz failure.
+fn submsg_failure(
+    storage: &dyn Storage,
+    env: &Env,
+    msg_type_url: &str,
+    reason: String,
+) -> ContractError {
+    match classify_authz_failure(&reason) {
+        Some(AuthzFailure::NotFound) => ContractError::NoAuthZGrant {
+            msg_type_url: String::from(msg_type_url),
+        },
+        Some(AuthzFailure::Expired) => ContractError::GrantExpired {
+            msg_type_url: String::from(msg_type_url),
+            expiration: GRANT_EXPIRATIONS
+                .may_load(storage, msg_type_url)
+                .ok()
+                .flatten()
+                .unwrap_or(env.block.time),
+        },
+        _ if msg_type_url == MsgMint::TYPE_URL => ContractError::MintFailed { reason },
+        _ if msg_type_url == BANK_SEND_TYPE_URL => ContractError::SendFailed { reason },
+        _ if msg_type_url == MsgSetDenomMetadata::TYPE_URL => {
+            ContractError::SetDenomMetadataFailed { reason }
+        }
+        _ => ContractError::LockFailed { reason },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env};
+    use cosmwasm_std::Uint128;
+
+    use crate::state::DenomMetadata;
+
+    fn failed_reply(id: u64, reason: &str) -> Reply {
+        Reply {
+            id,
+            result: SubMsgResult::Err(String::from(reason)),
+        }
+    }
+
+    fn save_reply_state(deps: DepsMut) {
+        REPLY_STATE
+            .save(
+                deps.storage,
+                AUTHZ_EXEC_MINT_MSG_ID,
+                &MintReplyState {
+                    sender: String::from("sender"),
+                    claimer_addr: String::from("claimer"),
+                    amount: Uint128::new(100),
+                    denom: String::from("factory/owner/subdenom"),
+                    bonus: Uint128::zero(),
+                    forfeited: Uint128::zero(),
+                    fee: Uint128::zero(),
+                    lock_duration: None,
+                    post_claim_action: None,
+                    ibc_destination: None,
+                    referral: None,
+                },
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn mint_reply_missing_grant() {
+        let mut deps = mock_dependencies();
+        save_reply_state(deps.as_mut());
+
+        let err = handle_mint_reply(
+            deps.as_mut(),
+            mock_env(),
+            failed_reply(AUTHZ_EXEC_MINT_MSG_ID, "codespace: authz, code: 2"),
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            ContractError::NoAuthZGrant {
+                msg_type_url: String::from(MsgMint::TYPE_URL),
+            }
+            .to_string(),
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn mint_reply_authz_failures() {
+        let env = mock_env();
+        let not_found = ContractError::NoAuthZGrant {
+            msg_type_url: String::from(MsgMint::TYPE_URL),
+        };
+        let expired = ContractError::GrantExpired {
+            msg_type_url: String::from(MsgMint::TYPE_URL),
+            expiration: env.block.time,
+        };
+
+        // each failure as reported in full and as redacted by wasmd.
+        let cases = [
+            (
+                "failed to get grant with given granter: osmo1owner, grantee: osmo1drop & msgType: /osmosis.tokenfactory.v1beta1.MsgMint : authorization not found",
+                &not_found,
+            ),
+            ("codespace: authz, code: 2", &not_found),
+            ("authorization expired", &expired),
+            ("codespace: authz, code: 6", &expired),
+        ];
+
+        for (reason, expected) in cases {
+            let mut deps = mock_dependencies();
+            save_reply_state(deps.as_mut());
+
+            let err = handle_mint_reply(
+                deps.as_mut(),
+                env.clone(),
+                failed_reply(AUTHZ_EXEC_MINT_MSG_ID, reason),
+            )
+            .unwrap_err();
+
+            assert_eq!(expected.to_string(), err.to_string(), "{}", reason);
+        }
+    }
+
+    #[test]
+    fn mint_reply_keeps_reason() {
+        let mut deps = mock_dependencies();
+        save_reply_state(deps.as_mut());
+
+        let err = handle_mint_reply(
+            deps.as_mut(),
+            mock_env(),
+            failed_reply(AUTHZ_EXEC_MINT_MSG_ID, "out of gas"),
+        )
+        .unwrap_err();
+
+        assert_eq!("Failed to mint: out of gas", err.to_string());
+    }
+
+    #[test]
+    fn send_reply_spend_limit_exceeded() {
+        let mut deps = mock_dependencies();
+        save_reply_state(deps.as_mut());
+
+        let err = handle_send_reply(
+            deps.as_mut(),
+            mock_env(),
+            failed_reply(
+                AUTHZ_EXEC_SEND_MSG_ID,
+                "requested amount is more than spend limit: insufficient funds",
+            ),
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            ContractError::SpendLimitExceeded {
+                amount: Uint128::new(100),
+                denom: String::from("factory/owner/subdenom"),
+            }
+            .to_string(),
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn send_reply_insufficient_funds() {
+        let mut deps = mock_dependencies();
+        save_reply_state(deps.as_mut());
+
+        // a redacted insufficient funds error does not tell a spend limit
+        // from a short balance.
+        let err = handle_send_reply(
+            deps.as_mut(),
+            mock_env(),
+            failed_reply(AUTHZ_EXEC_SEND_MSG_ID, "codespace: sdk, code: 5"),
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            ContractError::SendFailed {
+                reason: String::from("codespace: sdk, code: 5"),
+            }
+            .to_string(),
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn send_reply_expired_grant() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        save_reply_state(deps.as_mut());
+
+        let expiration = env.block.time.minus_seconds(60);
+        GRANT_EXPIRATIONS
+            .save(deps.as_mut().storage, BANK_SEND_TYPE_URL, &expiration)
+            .unwrap();
+
+        let err = handle_send_reply(
+            deps.as_mut(),
+            env,
+            failed_reply(AUTHZ_EXEC_SEND_MSG_ID, "codespace: authz, code: 6"),
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            ContractError::GrantExpired {
+                msg_type_url: String::from(BANK_SEND_TYPE_URL),
+                expiration,
+            }
+            .to_string(),
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn set_denom_metadata_reply_saves_metadata() {
+        let mut deps = mock_dependencies();
+        let metadata = DenomMetadata {
+            name: String::from("Drop Token"),
+            symbol: String::from("DROP"),
+            exponent: 6,
+            description: String::new(),
+            uri: None,
+        };
+        PENDING_DENOM_METADATA
+            .save(deps.as_mut().storage, &metadata)
+            .unwrap();
+
+        handle_set_denom_metadata_reply(
+            deps.as_mut(),
+            mock_env(),
+            Reply {
+                id: AUTHZ_EXEC_SET_METADATA_MSG_ID,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: None,
+                }),
+            },
+        )
+        .unwrap();
+
+        assert_eq!(metadata, DENOM_METADATA.load(&deps.storage).unwrap());
+        assert!(PENDING_DENOM_METADATA
+            .may_load(&deps.storage)
+            .unwrap()
+            .is_none());
+    }
 }
@@ -1,26 +1,399 @@
 use cosmwasm_schema::cw_serde;
 
-use cosmwasm_std::{Addr, Uint128};
+use cosmwasm_std::{Addr, Coin, Decimal, Timestamp, Uint128};
 use cw_storage_plus::{Item, Map};
 
+/// Distribution describes where the tokens paid out on claim come from.
+#[cw_serde]
+#[derive(Default)]
+pub enum Distribution {
+    /// Mint the owner's tokenfactory denom through the authz grants issued to the contract.
+    #[default]
+    TokenFactory,
+    /// Send native or IBC tokens that the owner escrowed in the contract with `Fund {}`.
+    Escrow { denom: String },
+    /// Transfer CW20 tokens that the owner escrowed in the contract through the CW20 `Send` hook.
+    Cw20 { address: Addr },
+}
+
+/// HashFunction is the hash function a merkle tree is built with.
+#[cw_serde]
+#[derive(Default)]
+pub enum HashFunction {
+    /// SHA3-256, used by merkle-cli by default.
+    #[default]
+    Sha3_256,
+    /// SHA-256.
+    Sha256,
+    /// Keccak-256, as used by EVM tooling.
+    Keccak256,
+    /// OpenZeppelin's StandardMerkleTree with a ["string", "uint256"] leaf
+    /// encoding of address and amount: Keccak-256 with sorted pairs and
+    /// double hashed leaves. Roots are 0x-prefixed hex encoded and proofs are
+    /// plain lists of hashes, as MerkleProof.verify takes them.
+    SortedKeccak256,
+    /// SHA-256 with sorted pairs and no prefixes, as used by
+    /// cw20-merkle-airdrop. Roots are hex encoded, so that its snapshots and
+    /// proofs can be reused.
+    Cw20MerkleAirdrop,
+}
+
+/// DenomMetadata describes the tokenfactory denom to wallets and explorers.
+#[cw_serde]
+pub struct DenomMetadata {
+    /// Display name, e.g. "Drop Token".
+    pub name: String,
+    /// Ticker, e.g. "DROP". The display unit is named after it in lowercase.
+    pub symbol: String,
+    /// Decimals of the display unit, e.g. 6 for 1 DROP = 1000000 base units.
+    pub exponent: u32,
+    pub description: String,
+    /// Link to further information, e.g. the token logo.
+    pub uri: Option<String>,
+}
+
+/// LockOption is a lock duration claimers can opt into and the bonus it earns.
+#[cw_serde]
+pub struct LockOption {
+    /// Lock duration in seconds, as accepted by the lockup module.
+    pub duration: u64,
+    /// Paid on top of the claimed amount and locked along with it,
+    /// e.g. 0.1 for a 10% bonus.
+    pub bonus: Decimal,
+}
+
+/// SwapRoute is a hop of a post-claim swap.
+#[cw_serde]
+pub struct SwapRoute {
+    pub pool_id: u64,
+    pub token_out_denom: String,
+}
+
+/// PostClaimAction routes the claimed tokens through a pool before they reach the claimer.
+#[cw_serde]
+pub enum PostClaimAction {
+    /// Swap the claimed amount along routes. The claim fails if less than
+    /// token_out_min_amount of the last route's denom comes out.
+    Swap {
+        routes: Vec<SwapRoute>,
+        token_out_min_amount: Uint128,
+    },
+    /// Join the configured pool with the claimed amount. The claim fails if
+    /// less than share_out_min_amount pool shares come out.
+    JoinPool { share_out_min_amount: Uint128 },
+}
+
+/// IbcDestination is a remote chain account the claimed tokens are transferred to.
+#[cw_serde]
+pub struct IbcDestination {
+    /// Transfer channel on this chain.
+    pub channel: String,
+    /// Address on the remote chain.
+    pub receiver: String,
+    /// Seconds after which the transfer times out and is refunded to the
+    /// claimer on this chain. Defaults to 10 minutes.
+    pub timeout: Option<u64>,
+}
+
+/// RemoteClaimSource is a companion contract on another chain allowed to
+/// relay claims over IBC.
+#[cw_serde]
+pub struct RemoteClaimSource {
+    /// Connection to the other chain on this chain.
+    pub connection_id: String,
+    /// Port of the companion contract on the other chain, e.g. "wasm.<address>".
+    pub port_id: String,
+}
+
+/// DecaySchedule scales claimed allocations down over time to reward early claimers.
+/// The forfeited part of each allocation is not paid out.
+#[cw_serde]
+pub struct DecaySchedule {
+    /// Allocations are paid out in full until start.
+    pub start: Timestamp,
+    /// From start, the paid out share decays linearly to min_multiplier at end.
+    pub end: Timestamp,
+    /// Share paid out from end on, e.g. 0.5 for half the allocation.
+    pub min_multiplier: Decimal,
+}
+
+/// RateLimit caps the claims paid out within a block.
+#[cw_serde]
+pub struct RateLimit {
+    pub max_claims: Option<u64>,
+    /// Cap on the paid out amount, lock bonuses included.
+    pub max_amount: Option<Uint128>,
+}
+
+/// WindowRateLimit caps the claims paid out within a rolling time window.
+#[cw_serde]
+pub struct WindowRateLimit {
+    /// Window length in seconds.
+    pub seconds: u64,
+    pub max_claims: Option<u64>,
+    /// Cap on the paid out amount, lock bonuses included.
+    pub max_amount: Option<Uint128>,
+}
+
+/// RateLimits bound how fast the allocation can be claimed, e.g. with a leaked proof set.
+#[cw_serde]
+pub struct RateLimits {
+    pub per_block: Option<RateLimit>,
+    pub per_window: Option<WindowRateLimit>,
+}
+
+/// FeeAmount is how much a claim fee charges.
+#[cw_serde]
+pub enum FeeAmount {
+    /// Paid by the claim sender in the funds attached to the claim.
+    Fixed { coin: Coin },
+    /// Share of the paid out allocation withheld from the claimer, e.g. 0.01 for 1%.
+    Percentage { rate: Decimal },
+}
+
+/// ClaimFee is charged on claims to fund relayers and infrastructure.
+#[cw_serde]
+pub struct ClaimFee {
+    pub amount: FeeAmount,
+    /// Receives the fees.
+    pub treasury: Addr,
+}
+
+/// ReferralConfig rewards claimers who referred other claimers.
+#[cw_serde]
+pub struct ReferralConfig {
+    /// Share of the referred claim's paid out allocation rewarded to the
+    /// referrer, e.g. 0.05 for 5%.
+    pub rate: Decimal,
+    /// Total a single referrer can earn.
+    pub cap: Uint128,
+}
+
+/// ReferralReward is paid to a referrer on a referred claim.
+#[cw_serde]
+pub struct ReferralReward {
+    pub referrer: String,
+    pub amount: Uint128,
+}
+
+// Config was first saved with only merkle_root and owner. The fields added
+// since default to the behaviour of those drops, so that their config still
+// loads once migrated.
 #[cw_serde]
 pub struct Config {
     pub merkle_root: String,
+    #[serde(default)]
+    pub hash_function: HashFunction,
     pub owner: Addr,
+    #[serde(default)]
+    pub distribution: Distribution,
+    // claims are rejected from this time on and the owner may withdraw
+    // whatever is left in escrow.
+    #[serde(default)]
+    pub claim_end: Option<Timestamp>,
+    // sum of all amounts in the tree, used to check that spend limited
+    // send grants cover the unclaimed allocation.
+    #[serde(default)]
+    pub total_amount: Option<Uint128>,
+    // owner-level execute messages are rejected, the drop is administered
+    // through sudo.
+    #[serde(default)]
+    pub governance_only: bool,
+    // claims are rejected while paused.
+    #[serde(default)]
+    pub paused: bool,
+    // lock durations claimers can opt into, none if empty.
+    #[serde(default)]
+    pub lock_options: Vec<LockOption>,
+    // pool claimers can join with their claim, none if unset.
+    #[serde(default)]
+    pub join_pool_id: Option<u64>,
+    // companion contracts allowed to open claim channels, none if empty.
+    #[serde(default)]
+    pub remote_claim_sources: Vec<RemoteClaimSource>,
+    // claimed allocations are scaled down over time, paid in full if unset.
+    #[serde(default)]
+    pub decay: Option<DecaySchedule>,
+    // claims beyond these limits are rejected, unlimited if unset.
+    #[serde(default)]
+    pub rate_limits: Option<RateLimits>,
+    // charged on every claim, free if unset.
+    #[serde(default)]
+    pub claim_fee: Option<ClaimFee>,
+    // claimers can name a referrer to be rewarded, disabled if unset.
+    #[serde(default)]
+    pub referral: Option<ReferralConfig>,
+}
+
+// test_config is a TokenFactory drop owned by "owner" with every optional
+// setting off, for unit tests to override with struct update syntax.
+#[cfg(test)]
+pub(crate) fn test_config() -> Config {
+    Config {
+        merkle_root: String::from("root"),
+        hash_function: HashFunction::Sha3_256,
+        owner: Addr::unchecked("owner"),
+        distribution: Distribution::TokenFactory,
+        claim_end: None,
+        total_amount: None,
+        governance_only: false,
+        paused: false,
+        lock_options: vec![],
+        join_pool_id: None,
+        remote_claim_sources: vec![],
+        decay: None,
+        rate_limits: None,
+        claim_fee: None,
+        referral: None,
+    }
 }
 
 #[cw_serde]
 pub struct MintReplyState {
+    // account that submitted the claim
+    pub sender: String,
     pub claimer_addr: String,
     pub amount: Uint128,
     pub denom: String,
+    // minted and sent on top of amount when the claimer opted into a lock.
+    pub bonus: Uint128,
+    // part of amount lost to the decay schedule, neither minted nor sent.
+    pub forfeited: Uint128,
+    // part of the paid out amount sent to the treasury instead of the claimer.
+    pub fee: Uint128,
+    pub lock_duration: Option<u64>,
+    // the minted amount is sent to the contract and routed through a pool.
+    pub post_claim_action: Option<PostClaimAction>,
+    // the minted amount is sent to the contract and transferred over IBC.
+    pub ibc_destination: Option<IbcDestination>,
+    // minted and sent to the referrer on top of amount.
+    pub referral: Option<ReferralReward>,
+}
+
+#[cw_serde]
+pub struct PostClaimState {
+    pub claimer_addr: String,
+    pub amount: Uint128,
+    pub denom: String,
+    // denom delivered to the claimer once the post-claim action succeeded.
+    pub denom_out: String,
+}
+
+/// HookFailurePolicy decides what a failing claim hook does to the claim.
+#[cw_serde]
+pub enum HookFailurePolicy {
+    /// The hook failure is recorded in the claim events and the claim goes through.
+    Ignore,
+    /// The hook failure reverts the claim.
+    Revert,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct ClaimStats {
+    pub claimed_count: u64,
+    pub claimed_amount: Uint128,
+    // part of the claimed amount lost to the decay schedule and left to the treasury.
+    pub forfeited_amount: Uint128,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct RateUsage {
+    pub claims: u64,
+    pub amount: Uint128,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct RateLimitUsage {
+    // height of the block usage is counted for.
+    pub height: u64,
+    pub block: RateUsage,
+    // index of the fixed window usage is counted for, the block time in
+    // seconds divided by the window length.
+    pub window: u64,
+    pub current_window: RateUsage,
+    pub previous_window: RateUsage,
 }
 
 pub const CONFIG: Item<Config> = Item::new("config");
 
 pub const SUBDENOM: Item<String> = Item::new("subdenom");
 
+// DENOM_METADATA holds the metadata last set for the tokenfactory denom.
+pub const DENOM_METADATA: Item<DenomMetadata> = Item::new("denom_metadata");
+
+// PENDING_DENOM_METADATA persists the metadata being set until its reply, which
+// saves it to DENOM_METADATA once the authz exec succeeded.
+pub const PENDING_DENOM_METADATA: Item<DenomMetadata> = Item::new("pending_denom_metadata");
+
 pub const CLAIMED_ADDRESSES: Map<&str, bool> = Map::new("claimed");
 
+pub const CLAIM_STATS: Item<ClaimStats> = Item::new("claim_stats");
+
+// CLAIMED_BY holds the amount claimed by each address, so that referrers
+// can be checked to have claimed.
+pub const CLAIMED_BY: Map<&str, Uint128> = Map::new("claimed_by");
+
+#[cw_serde]
+#[derive(Default)]
+pub struct ReferralEarnings {
+    pub referrals: u64,
+    pub amount: Uint128,
+}
+
+// REFERRAL_EARNINGS holds the rewards paid to each referrer.
+pub const REFERRAL_EARNINGS: Map<&Addr, ReferralEarnings> = Map::new("referral_earnings");
+
+// GRANT_EXPIRATIONS holds the expiration of each authz grant validated by set_subdenom,
+// keyed by message type url. Grants without an expiration have no entry.
+pub const GRANT_EXPIRATIONS: Map<&str, Timestamp> = Map::new("grant_expirations");
+
+// RATE_LIMIT_USAGE counts the claims paid out against the configured rate limits.
+pub const RATE_LIMIT_USAGE: Item<RateLimitUsage> = Item::new("rate_limit_usage");
+
+// DENYLIST holds the addresses blocked from claiming by the owner.
+pub const DENYLIST: Map<&Addr, ()> = Map::new("denylist");
+
+// HOOKS are the contracts notified of every claim, see ClaimHookMsg.
+pub const HOOKS: Map<&Addr, HookFailurePolicy> = Map::new("hooks");
+
+// POST_CLAIM_STATE persists the post-claim action of a claim until its reply,
+// which forwards the resulting tokens to the claimer.
+pub const POST_CLAIM_STATE: Item<PostClaimState> = Item::new("post_claim_state");
+
+#[cw_serde]
+pub struct IbcTransferState {
+    // account that submitted the claim
+    pub sender: String,
+    pub claimer_addr: String,
+    pub amount: Uint128,
+    // part of amount lost to the decay schedule, not transferred.
+    pub forfeited: Uint128,
+    // part of the paid out amount sent to the treasury, not transferred.
+    pub fee: Uint128,
+    pub denom: String,
+    pub channel: String,
+    pub receiver: String,
+}
+
+// IBC_TRANSFER_STATE persists an IBC transfer of claimed tokens until its reply,
+// which records the claim once the transfer is dispatched.
+pub const IBC_TRANSFER_STATE: Item<IbcTransferState> = Item::new("ibc_transfer_state");
+
+#[cw_serde]
+pub struct PendingIbcTransfer {
+    pub claimer_addr: String,
+    pub amount: Uint128,
+    pub denom: String,
+}
+
+// PENDING_IBC_TRANSFERS are the dispatched transfers of claimed tokens waiting for
+// an acknowledgement, keyed by source channel and packet sequence. Failed or timed
+// out transfers are refunded to the claimer on this chain.
+pub const PENDING_IBC_TRANSFERS: Map<(&str, u64), PendingIbcTransfer> =
+    Map::new("pending_ibc_transfers");
+
 // MINT_REPLY_STATE persists data from tf mint message creation until the reply receipt.
 pub const REPLY_STATE: Map<u64, MintReplyState> = Map::new("mint_reply_state");
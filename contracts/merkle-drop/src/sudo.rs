@@ -0,0 +1,87 @@
+use cosmwasm_std::{DepsMut, Env, Response};
+
+use crate::events::MerkleDropEvent;
+use crate::execute::claim::decode_root;
+use crate::execute::fund::{escrow_asset, escrow_balance, escrow_transfer};
+use crate::state::{Distribution, CONFIG};
+use crate::ContractError;
+
+// GOVERNANCE_SENDER is the sender reported in events emitted from sudo.
+pub const GOVERNANCE_SENDER: &str = "governance";
+
+pub fn set_paused(deps: DepsMut, paused: bool) -> Result<Response, ContractError> {
+    CONFIG.update(deps.storage, |mut config| -> Result<_, ContractError> {
+        config.paused = paused;
+        Ok(config)
+    })?;
+
+    let action = if paused { "pause" } else { "unpause" };
+
+    Ok(Response::new().add_attribute("method", action).add_event(
+        MerkleDropEvent::ConfigUpdate {
+            action: String::from(action),
+            sender: String::from(GOVERNANCE_SENDER),
+            denom: None,
+        }
+        .into(),
+    ))
+}
+
+pub fn update_root(deps: DepsMut, merkle_root: String) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    decode_root(&merkle_root, &config.hash_function)?;
+
+    config.merkle_root = merkle_root;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "update_root")
+        .add_event(
+            MerkleDropEvent::ConfigUpdate {
+                action: String::from("update_root"),
+                sender: String::from(GOVERNANCE_SENDER),
+                denom: None,
+            }
+            .into(),
+        ))
+}
+
+// clawback returns the whole escrow to recipient, regardless of the claim
+// window, and pauses the drop since nothing is left to claim.
+pub fn clawback(deps: DepsMut, env: Env, recipient: String) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if config.distribution == Distribution::TokenFactory {
+        return Err(ContractError::UnsupportedDistribution {
+            action: String::from("clawback"),
+        });
+    }
+
+    let recipient = deps.api.addr_validate(&recipient)?;
+
+    let balance = escrow_balance(deps.as_ref(), &env, &config.distribution)?;
+    if balance.is_zero() {
+        return Err(ContractError::NothingToWithdraw {});
+    }
+
+    config.paused = true;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_message(escrow_transfer(
+            &config.distribution,
+            recipient.as_str(),
+            balance,
+        )?)
+        .add_attribute("method", "clawback")
+        .add_event(
+            MerkleDropEvent::Withdraw {
+                sender: String::from(GOVERNANCE_SENDER),
+                recipient: recipient.into_string(),
+                amount: balance,
+                denom: escrow_asset(&config.distribution)?,
+            }
+            .into(),
+        ))
+}
@@ -1,36 +1,184 @@
 mod test_env;
 use cosmwasm_std::{Coin, Uint128};
-use merkle_drop::msg::ExecuteMsg;
+use merkle::proof::Proof;
+use merkle::Tree;
+use merkle_drop::msg::{ClaimProof, ExecuteMsg, InstantiateMsg, ProofVersion};
+use merkle_drop::state::Distribution;
 use osmosis_testing::{
     cosmrs::proto::cosmos::bank::v1beta1::{QueryBalanceRequest, QueryBalanceResponse},
-    Module, Runner, Wasm,
+    Account, Module, OsmosisTestApp, Runner, SigningAccount, Wasm,
 };
 use test_env::*;
 
-const VALID_PROOF_STR: &str = "[{\"is_left_sibling\":true,\"hash\":[89,79,106,114,49,69,77,102,68,119,114,48,69,84,73,103,82,71,97,108,48,79,108,53,105,56,82,103,111,57,85,51,76,70,82,90,115,66,97,78,89,51,73,61]},{\"is_left_sibling\":false,\"hash\":[80,54,110,55,43,55,72,72,111,52,109,104,79,104,102,105,108,83,43,118,87,54,88,85,88,113,48,115,105,99,83,116,116,52,112,54,119,114,68,48,113,47,73,61]},{\"is_left_sibling\":true,\"hash\":[79,79,110,66,86,100,72,56,121,84,70,57,115,78,65,56,80,85,81,97,111,71,89,119,81,89,87,83,109,71,116,89,56,79,118,85,118,98,73,83,122,74,77,61]},{\"is_left_sibling\":false,\"hash\":[102,65,68,121,57,69,49,118,56,70,78,78,81,53,109,47,50,120,78,55,103,110,119,89,78,82,104,80,83,53,69,105,79,53,115,79,77,43,118,106,50,98,56,61]}]";
-const TO_VERIFY_VALID2_ADDR_AMOUNT: &str = "osmo1hqslwuc8ukaaaxfmahgnquyqx3w0tmrluwxmxj";
-
 test_claim!(
     claim
     should succeed,
 
-    proof: String::from(VALID_PROOF_STR),
-    amount: Uint128::from(1421901 as u128)
+    proof: valid_proof(),
+    proof_version: None,
+    amount: Uint128::from(VALID_AMOUNT)
 );
 
+test_claim!(
+    claim_compact_proof
+    should succeed,
+
+    proof: compact_proof(&serde_json_wasm::from_str::<Proof>(VALID_PROOF_STR).unwrap()),
+    proof_version: Some(ProofVersion::Compact),
+    amount: Uint128::from(VALID_AMOUNT)
+);
+
+#[test]
+fn claim_with_spend_limited_grant_succeeds() {
+    let test_env = TestEnv::with_instantiate_msg(InstantiateMsg {
+        total_amount: Some(Uint128::from(VALID_AMOUNT)),
+        ..default_instantiate_msg()
+    });
+
+    test_env.execute_msg_grant_mint();
+    test_env.execute_msg_grant_bank_send_limited(VALID_AMOUNT);
+
+    let wasm = Wasm::new(&test_env.app);
+    wasm.execute(
+        &test_env.contract_address,
+        &ExecuteMsg::SetSubDenom {
+            subdenom: String::from(VALID_SUBDENOM),
+        },
+        &[],
+        &test_env.owner,
+    )
+    .unwrap();
+
+    let claim_sender = test_env
+        .app
+        .init_account(&[Coin::new(1_000_000_000_000, "uosmo")])
+        .unwrap();
+
+    let res = wasm.execute(
+        &test_env.contract_address,
+        &ExecuteMsg::Claim {
+            proof: valid_proof(),
+            proof_version: None,
+            amount: Uint128::from(VALID_AMOUNT),
+            claimer_addr: String::from(TO_VERIFY_VALID2_ADDR_AMOUNT),
+            options: None,
+        },
+        &[],
+        &claim_sender,
+    );
+    assert!(res.is_ok(), "{:?}", res.unwrap_err());
+
+    assert_eq!(
+        Uint128::from(VALID_AMOUNT),
+        test_env.query_balance(TO_VERIFY_VALID2_ADDR_AMOUNT, &test_env.full_denom)
+    );
+}
+
+#[test]
+fn compact_proof_uses_less_gas() {
+    const DEPTH: usize = 10;
+    const AMOUNT: u128 = 1_000;
+
+    // two claimers of a full tree, whose proofs are all DEPTH hashes long.
+    let app = OsmosisTestApp::new();
+    let claimers: Vec<SigningAccount> = (0..2)
+        .map(|_| {
+            app.init_account(&[Coin::new(1_000_000_000_000, "uosmo")])
+                .unwrap()
+        })
+        .collect();
+    let mut leaves: Vec<String> = claimers
+        .iter()
+        .map(|claimer| format!("{}{}", claimer.address(), AMOUNT))
+        .collect();
+    leaves.extend((leaves.len()..1 << DEPTH).map(|i| format!("osmo1filler{}{}", i, AMOUNT)));
+    let tree: Tree = Tree::new(&leaves).unwrap();
+
+    let test_env = TestEnv::with_app(
+        app,
+        InstantiateMsg {
+            merkle_root: base64::encode(tree.get_root().unwrap()),
+            distribution: Some(Distribution::Escrow {
+                denom: String::from("uosmo"),
+            }),
+            ..default_instantiate_msg()
+        },
+    );
+
+    let wasm = Wasm::new(&test_env.app);
+    wasm.execute(
+        &test_env.contract_address,
+        &ExecuteMsg::Fund {},
+        &[Coin::new(2 * AMOUNT, "uosmo")],
+        &test_env.owner,
+    )
+    .unwrap();
+
+    let proofs: Vec<Proof> = leaves[..2]
+        .iter()
+        .map(|leaf| tree.find_proof(leaf).unwrap())
+        .collect();
+    assert!(proofs
+        .iter()
+        .all(|proof| proof.get_num_entries() == DEPTH as u128));
+
+    let gas_used =
+        |claimer: &SigningAccount, proof: ClaimProof, proof_version: Option<ProofVersion>| {
+            wasm.execute(
+                &test_env.contract_address,
+                &ExecuteMsg::Claim {
+                    proof,
+                    proof_version,
+                    amount: Uint128::new(AMOUNT),
+                    claimer_addr: claimer.address(),
+                    options: None,
+                },
+                &[],
+                &test_env.owner,
+            )
+            .unwrap()
+            .gas_info
+            .gas_used
+        };
+
+    // the compact claim goes first, so that it pays for the storage that the
+    // first claim initializes.
+    let compact = gas_used(
+        &claimers[1],
+        compact_proof(&proofs[1]),
+        Some(ProofVersion::Compact),
+    );
+    let legacy = gas_used(
+        &claimers[0],
+        ClaimProof::Legacy(serde_json_wasm::to_string(&proofs[0]).unwrap()),
+        None,
+    );
+
+    assert!(
+        compact < legacy,
+        "legacy claim used {} gas, compact claim {} gas",
+        legacy,
+        compact
+    );
+}
+
 // ======= helpers ========
 
 #[macro_export]
 macro_rules! test_claim {
-    ($test_name:ident should succeed, proof: $proof:expr, amount: $amount:expr) => {
+    ($test_name:ident should succeed, proof: $proof:expr, proof_version: $proof_version:expr, amount: $amount:expr) => {
         #[test]
         fn $test_name() {
-            test_claim_success_case($proof, $amount)
+            test_claim_success_case($proof, $proof_version, $amount)
         }
     };
 }
 
-fn test_claim_success_case(proof: String, amount: Uint128) {
+fn test_claim_success_case(
+    proof: ClaimProof,
+    proof_version: Option<ProofVersion>,
+    amount: Uint128,
+) {
     let test_env = TestEnv::new();
 
     test_env.execute_msg_grant_mint();
@@ -58,8 +206,10 @@ fn test_claim_success_case(proof: String, amount: Uint128) {
 
     let msg = ExecuteMsg::Claim {
         proof: proof,
+        proof_version,
         amount: amount,
         claimer_addr: claimer_addr.clone(),
+        options: None,
     };
 
     let wasm = Wasm::new(&app);
@@ -0,0 +1,159 @@
+mod test_env;
+use cosmwasm_std::{coins, Addr, Coin, Decimal, Empty, Uint128};
+use cw_multi_test::{App, AppBuilder, AppResponse, Contract, ContractWrapper, Executor};
+use merkle_drop::msg::{ExecuteMsg, InstantiateMsg};
+use merkle_drop::state::{ClaimFee, Distribution, FeeAmount};
+use merkle_drop::ContractError;
+use test_env::{default_instantiate_msg, valid_proof, TO_VERIFY_VALID2_ADDR_AMOUNT, VALID_AMOUNT};
+
+const OWNER: &str = "owner";
+const CLAIM_SENDER: &str = "claim_sender";
+const TREASURY: &str = "treasury";
+const DENOM: &str = "uosmo";
+const FEE_DENOM: &str = "uion";
+const FUND_AMOUNT: u128 = 10_000_000;
+
+#[test]
+fn fixed_fee_routed_to_treasury() {
+    let mut env = ClaimFeeEnv::new(FeeAmount::Fixed {
+        coin: Coin::new(100, FEE_DENOM),
+    });
+
+    env.claim(&coins(150, FEE_DENOM)).unwrap();
+
+    assert_eq!(Uint128::new(100), env.balance(TREASURY, FEE_DENOM));
+    // the excess is refunded.
+    assert_eq!(
+        Uint128::new(1_000 - 100),
+        env.balance(CLAIM_SENDER, FEE_DENOM)
+    );
+    assert_eq!(
+        Uint128::from(VALID_AMOUNT),
+        env.balance(TO_VERIFY_VALID2_ADDR_AMOUNT, DENOM)
+    );
+}
+
+#[test]
+fn insufficient_fixed_fee_fails() {
+    let mut env = ClaimFeeEnv::new(FeeAmount::Fixed {
+        coin: Coin::new(100, FEE_DENOM),
+    });
+
+    let err = env.claim(&coins(99, FEE_DENOM)).unwrap_err();
+    assert_eq!(
+        ContractError::InsufficientClaimFee {
+            required: Coin::new(100, FEE_DENOM),
+        }
+        .to_string(),
+        err.root_cause().to_string()
+    );
+    assert_eq!(Uint128::zero(), env.balance(TREASURY, FEE_DENOM));
+}
+
+#[test]
+fn percentage_fee_withheld_from_claimer() {
+    let mut env = ClaimFeeEnv::new(FeeAmount::Percentage {
+        rate: Decimal::percent(1),
+    });
+
+    env.claim(&[]).unwrap();
+
+    let fee = Uint128::from(VALID_AMOUNT) * Decimal::percent(1);
+    assert_eq!(fee, env.balance(TREASURY, DENOM));
+    assert_eq!(
+        Uint128::from(VALID_AMOUNT) - fee,
+        env.balance(TO_VERIFY_VALID2_ADDR_AMOUNT, DENOM)
+    );
+    assert_eq!(
+        Uint128::new(FUND_AMOUNT - VALID_AMOUNT),
+        env.balance(env.drop.as_str(), DENOM)
+    );
+}
+
+// ======= helpers ========
+
+struct ClaimFeeEnv {
+    app: App,
+    drop: Addr,
+}
+
+impl ClaimFeeEnv {
+    fn new(fee: FeeAmount) -> Self {
+        let mut app = AppBuilder::new().build(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &Addr::unchecked(OWNER), coins(FUND_AMOUNT, DENOM))
+                .unwrap();
+            router
+                .bank
+                .init_balance(
+                    storage,
+                    &Addr::unchecked(CLAIM_SENDER),
+                    coins(1_000, FEE_DENOM),
+                )
+                .unwrap();
+        });
+
+        let code_id = app.store_code(merkle_drop_contract());
+        let drop = app
+            .instantiate_contract(
+                code_id,
+                Addr::unchecked(OWNER),
+                &InstantiateMsg {
+                    distribution: Some(Distribution::Escrow {
+                        denom: String::from(DENOM),
+                    }),
+                    claim_fee: Some(ClaimFee {
+                        amount: fee,
+                        treasury: Addr::unchecked(TREASURY),
+                    }),
+                    ..default_instantiate_msg()
+                },
+                &[],
+                "merkle-drop",
+                None,
+            )
+            .unwrap();
+
+        app.execute_contract(
+            Addr::unchecked(OWNER),
+            drop.clone(),
+            &ExecuteMsg::Fund {},
+            &coins(FUND_AMOUNT, DENOM),
+        )
+        .unwrap();
+
+        ClaimFeeEnv { app, drop }
+    }
+
+    fn claim(&mut self, funds: &[Coin]) -> anyhow::Result<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(CLAIM_SENDER),
+            self.drop.clone(),
+            &ExecuteMsg::Claim {
+                proof: valid_proof(),
+                proof_version: None,
+                amount: Uint128::from(VALID_AMOUNT),
+                claimer_addr: String::from(TO_VERIFY_VALID2_ADDR_AMOUNT),
+                options: None,
+            },
+            funds,
+        )
+    }
+
+    fn balance(&self, address: &str, denom: &str) -> Uint128 {
+        let Coin { amount, .. } = self.app.wrap().query_balance(address, denom).unwrap();
+        amount
+    }
+}
+
+fn merkle_drop_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new(
+            merkle_drop::contract::execute,
+            merkle_drop::contract::instantiate,
+            merkle_drop::contract::query,
+        )
+        .with_reply(merkle_drop::contract::reply),
+    )
+}
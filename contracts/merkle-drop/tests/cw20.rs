@@ -0,0 +1,313 @@
+mod test_env;
+use cosmwasm_std::{to_binary, Addr, Empty, Timestamp, Uint128};
+use cw20::{BalanceResponse, Cw20Coin, Cw20ExecuteMsg, Cw20QueryMsg};
+use cw_multi_test::{App, AppResponse, Contract, ContractWrapper, Executor};
+use merkle_drop::events::CLAIM_EVENT;
+use merkle_drop::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, ReceiveMsg};
+use merkle_drop::state::{ClaimStats, Distribution};
+use merkle_drop::ContractError;
+use test_env::{default_instantiate_msg, valid_proof, TO_VERIFY_VALID2_ADDR_AMOUNT, VALID_AMOUNT};
+
+const OWNER: &str = "owner";
+const CLAIM_SENDER: &str = "claim_sender";
+const FUND_AMOUNT: u128 = 10_000_000;
+const CLAIM_WINDOW_SECONDS: u64 = 60 * 60;
+
+#[test]
+fn cw20_claim_succeeds() {
+    let mut env = Cw20Env::new();
+    env.fund(FUND_AMOUNT);
+
+    env.claim().unwrap();
+
+    assert_eq!(
+        Uint128::from(VALID_AMOUNT),
+        env.balance(TO_VERIFY_VALID2_ADDR_AMOUNT)
+    );
+    assert_eq!(
+        Uint128::from(FUND_AMOUNT - VALID_AMOUNT),
+        env.balance(env.drop.as_str())
+    );
+
+    let stats: ClaimStats = env
+        .app
+        .wrap()
+        .query_wasm_smart(&env.drop, &QueryMsg::GetStats {})
+        .unwrap();
+    assert_eq!(
+        ClaimStats {
+            claimed_count: 1,
+            claimed_amount: Uint128::from(VALID_AMOUNT),
+            forfeited_amount: Uint128::zero(),
+        },
+        stats
+    );
+}
+
+#[test]
+fn cw20_claim_emits_claim_event() {
+    let mut env = Cw20Env::new();
+    env.fund(FUND_AMOUNT);
+
+    let res = env.claim().unwrap();
+
+    let event = res
+        .events
+        .iter()
+        .find(|event| event.ty == format!("wasm-{}", CLAIM_EVENT))
+        .expect("claim event");
+    let attribute = |key: &str| {
+        event
+            .attributes
+            .iter()
+            .find(|attr| attr.key == key)
+            .map(|attr| attr.value.clone())
+    };
+
+    assert_eq!(Some(String::from("completed")), attribute("stage"));
+    assert_eq!(Some(String::from(CLAIM_SENDER)), attribute("sender"));
+    assert_eq!(
+        Some(String::from(TO_VERIFY_VALID2_ADDR_AMOUNT)),
+        attribute("address")
+    );
+    assert_eq!(Some(VALID_AMOUNT.to_string()), attribute("amount"));
+    assert_eq!(Some(env.token.to_string()), attribute("denom"));
+}
+
+#[test]
+fn cw20_claim_twice_fails() {
+    let mut env = Cw20Env::new();
+    env.fund(FUND_AMOUNT);
+
+    env.claim().unwrap();
+    let err = env.claim().unwrap_err();
+
+    assert_eq!(
+        ContractError::AlreadyClaimed {
+            claim: format!("{}{}", TO_VERIFY_VALID2_ADDR_AMOUNT, VALID_AMOUNT),
+        }
+        .to_string(),
+        err.root_cause().to_string()
+    );
+}
+
+#[test]
+fn cw20_claim_insufficient_escrow_fails() {
+    let mut env = Cw20Env::new();
+    env.fund(VALID_AMOUNT - 1);
+
+    let err = env.claim().unwrap_err();
+
+    assert_eq!(
+        ContractError::InsufficientEscrow {
+            balance: Uint128::from(VALID_AMOUNT - 1),
+            required: Uint128::from(VALID_AMOUNT),
+        }
+        .to_string(),
+        err.root_cause().to_string()
+    );
+}
+
+#[test]
+fn cw20_claim_after_claim_end_fails() {
+    let mut env = Cw20Env::new();
+    env.fund(FUND_AMOUNT);
+
+    env.app
+        .update_block(|block| block.time = block.time.plus_seconds(CLAIM_WINDOW_SECONDS));
+
+    let err = env.claim().unwrap_err();
+
+    assert!(
+        err.root_cause()
+            .to_string()
+            .starts_with("Claim window ended"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn cw20_fund_with_other_token_fails() {
+    let mut env = Cw20Env::new();
+
+    let other_token = instantiate_cw20(&mut env.app, FUND_AMOUNT);
+    let err = env
+        .app
+        .execute_contract(
+            Addr::unchecked(OWNER),
+            other_token.clone(),
+            &Cw20ExecuteMsg::Send {
+                contract: env.drop.to_string(),
+                amount: Uint128::from(FUND_AMOUNT),
+                msg: to_binary(&ReceiveMsg::Fund {}).unwrap(),
+            },
+            &[],
+        )
+        .unwrap_err();
+
+    assert_eq!(
+        ContractError::UnexpectedCw20 {
+            token: other_token.to_string(),
+        }
+        .to_string(),
+        err.root_cause().to_string()
+    );
+}
+
+#[test]
+fn cw20_withdraw_remaining_after_claim_end_succeeds() {
+    let mut env = Cw20Env::new();
+    env.fund(FUND_AMOUNT);
+    env.claim().unwrap();
+
+    let withdraw = ExecuteMsg::WithdrawRemaining { recipient: None };
+
+    let err = env
+        .app
+        .execute_contract(Addr::unchecked(OWNER), env.drop.clone(), &withdraw, &[])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::ClaimWindowOpen {}.to_string(),
+        err.root_cause().to_string()
+    );
+
+    env.app
+        .update_block(|block| block.time = block.time.plus_seconds(CLAIM_WINDOW_SECONDS));
+
+    env.app
+        .execute_contract(Addr::unchecked(OWNER), env.drop.clone(), &withdraw, &[])
+        .unwrap();
+
+    assert_eq!(
+        Uint128::from(FUND_AMOUNT - VALID_AMOUNT),
+        env.balance(OWNER)
+    );
+    assert_eq!(Uint128::zero(), env.balance(env.drop.as_str()));
+}
+
+// ======= helpers ========
+
+struct Cw20Env {
+    app: App,
+    token: Addr,
+    drop: Addr,
+}
+
+impl Cw20Env {
+    fn new() -> Self {
+        let mut app = App::default();
+
+        let token = instantiate_cw20(&mut app, FUND_AMOUNT);
+
+        let claim_end = app.block_info().time.plus_seconds(CLAIM_WINDOW_SECONDS);
+        let drop = instantiate_merkle_drop(&mut app, &token, claim_end);
+
+        Cw20Env { app, token, drop }
+    }
+
+    fn fund(&mut self, amount: u128) {
+        self.app
+            .execute_contract(
+                Addr::unchecked(OWNER),
+                self.token.clone(),
+                &Cw20ExecuteMsg::Send {
+                    contract: self.drop.to_string(),
+                    amount: Uint128::from(amount),
+                    msg: to_binary(&ReceiveMsg::Fund {}).unwrap(),
+                },
+                &[],
+            )
+            .unwrap();
+    }
+
+    fn claim(&mut self) -> anyhow::Result<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(CLAIM_SENDER),
+            self.drop.clone(),
+            &ExecuteMsg::Claim {
+                proof: valid_proof(),
+                proof_version: None,
+                amount: Uint128::from(VALID_AMOUNT),
+                claimer_addr: String::from(TO_VERIFY_VALID2_ADDR_AMOUNT),
+                options: None,
+            },
+            &[],
+        )
+    }
+
+    fn balance(&self, address: &str) -> Uint128 {
+        let response: BalanceResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(
+                &self.token,
+                &Cw20QueryMsg::Balance {
+                    address: String::from(address),
+                },
+            )
+            .unwrap();
+        response.balance
+    }
+}
+
+fn merkle_drop_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new(
+            merkle_drop::contract::execute,
+            merkle_drop::contract::instantiate,
+            merkle_drop::contract::query,
+        )
+        .with_reply(merkle_drop::contract::reply),
+    )
+}
+
+fn cw20_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        cw20_base::contract::execute,
+        cw20_base::contract::instantiate,
+        cw20_base::contract::query,
+    ))
+}
+
+fn instantiate_cw20(app: &mut App, owner_balance: u128) -> Addr {
+    let code_id = app.store_code(cw20_contract());
+    app.instantiate_contract(
+        code_id,
+        Addr::unchecked(OWNER),
+        &cw20_base::msg::InstantiateMsg {
+            name: String::from("Drop Token"),
+            symbol: String::from("DROP"),
+            decimals: 6,
+            initial_balances: vec![Cw20Coin {
+                address: String::from(OWNER),
+                amount: Uint128::from(owner_balance),
+            }],
+            mint: None,
+            marketing: None,
+        },
+        &[],
+        "drop-token",
+        None,
+    )
+    .unwrap()
+}
+
+fn instantiate_merkle_drop(app: &mut App, token: &Addr, claim_end: Timestamp) -> Addr {
+    let code_id = app.store_code(merkle_drop_contract());
+    app.instantiate_contract(
+        code_id,
+        Addr::unchecked(OWNER),
+        &InstantiateMsg {
+            distribution: Some(Distribution::Cw20 {
+                address: token.clone(),
+            }),
+            claim_end: Some(claim_end),
+            ..default_instantiate_msg()
+        },
+        &[],
+        "merkle-drop",
+        None,
+    )
+    .unwrap()
+}
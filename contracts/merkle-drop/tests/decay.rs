@@ -0,0 +1,191 @@
+mod test_env;
+use cosmwasm_std::{coins, Addr, Coin, Decimal, Empty, Timestamp, Uint128};
+use cw_multi_test::{App, AppBuilder, AppResponse, Contract, ContractWrapper, Executor};
+use merkle_drop::msg::{DecayMultiplierResponse, ExecuteMsg, InstantiateMsg, QueryMsg};
+use merkle_drop::state::{ClaimStats, DecaySchedule, Distribution};
+use test_env::{default_instantiate_msg, valid_proof, TO_VERIFY_VALID2_ADDR_AMOUNT, VALID_AMOUNT};
+
+const OWNER: &str = "owner";
+const CLAIM_SENDER: &str = "claim_sender";
+const DENOM: &str = "uosmo";
+const FUND_AMOUNT: u128 = 10_000_000;
+const DAY: u64 = 60 * 60 * 24;
+
+#[test]
+fn claim_before_decay_paid_in_full() {
+    let mut env = DecayEnv::new();
+
+    env.claim().unwrap();
+
+    assert_eq!(Decimal::one(), env.multiplier());
+    assert_eq!(
+        Uint128::from(VALID_AMOUNT),
+        env.balance(TO_VERIFY_VALID2_ADDR_AMOUNT)
+    );
+    assert_eq!(Uint128::zero(), env.stats().forfeited_amount);
+}
+
+#[test]
+fn claim_during_decay_forfeits_part() {
+    let mut env = DecayEnv::new();
+
+    // halfway through the decay, 75% of the allocation is paid out.
+    env.advance(28 * DAY);
+    assert_eq!(Decimal::percent(75), env.multiplier());
+
+    env.claim().unwrap();
+
+    let paid = Uint128::from(VALID_AMOUNT) * Decimal::percent(75);
+    assert_eq!(paid, env.balance(TO_VERIFY_VALID2_ADDR_AMOUNT));
+    assert_eq!(
+        ClaimStats {
+            claimed_count: 1,
+            claimed_amount: Uint128::from(VALID_AMOUNT),
+            forfeited_amount: Uint128::from(VALID_AMOUNT) - paid,
+        },
+        env.stats()
+    );
+}
+
+#[test]
+fn claim_after_decay_paid_minimum() {
+    let mut env = DecayEnv::new();
+
+    env.advance(60 * DAY);
+    env.claim().unwrap();
+
+    assert_eq!(
+        Uint128::from(VALID_AMOUNT) * Decimal::percent(50),
+        env.balance(TO_VERIFY_VALID2_ADDR_AMOUNT)
+    );
+}
+
+#[test]
+fn invalid_decay_schedule_rejected() {
+    let mut app = App::default();
+    let code_id = app.store_code(merkle_drop_contract());
+
+    let start = app.block_info().time;
+    app.instantiate_contract(
+        code_id,
+        Addr::unchecked(OWNER),
+        &InstantiateMsg {
+            decay: Some(DecaySchedule {
+                start,
+                end: start,
+                min_multiplier: Decimal::percent(50),
+            }),
+            ..default_instantiate_msg()
+        },
+        &[],
+        "merkle-drop",
+        None,
+    )
+    .unwrap_err();
+}
+
+// ======= helpers ========
+
+struct DecayEnv {
+    app: App,
+    drop: Addr,
+}
+
+impl DecayEnv {
+    // new instantiates an escrow drop paid in full for 14 days, then decaying
+    // linearly to 50% over the following 28 days.
+    fn new() -> Self {
+        let mut app = AppBuilder::new().build(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &Addr::unchecked(OWNER), coins(FUND_AMOUNT, DENOM))
+                .unwrap()
+        });
+
+        let now = app.block_info().time;
+        let code_id = app.store_code(merkle_drop_contract());
+        let drop = app
+            .instantiate_contract(
+                code_id,
+                Addr::unchecked(OWNER),
+                &InstantiateMsg {
+                    distribution: Some(Distribution::Escrow {
+                        denom: String::from(DENOM),
+                    }),
+                    decay: Some(DecaySchedule {
+                        start: now.plus_seconds(14 * DAY),
+                        end: now.plus_seconds(42 * DAY),
+                        min_multiplier: Decimal::percent(50),
+                    }),
+                    ..default_instantiate_msg()
+                },
+                &[],
+                "merkle-drop",
+                None,
+            )
+            .unwrap();
+
+        app.execute_contract(
+            Addr::unchecked(OWNER),
+            drop.clone(),
+            &ExecuteMsg::Fund {},
+            &coins(FUND_AMOUNT, DENOM),
+        )
+        .unwrap();
+
+        DecayEnv { app, drop }
+    }
+
+    fn advance(&mut self, seconds: u64) {
+        self.app.update_block(|block| {
+            block.time = Timestamp::from_nanos(block.time.nanos() + seconds * 1_000_000_000)
+        });
+    }
+
+    fn claim(&mut self) -> anyhow::Result<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(CLAIM_SENDER),
+            self.drop.clone(),
+            &ExecuteMsg::Claim {
+                proof: valid_proof(),
+                proof_version: None,
+                amount: Uint128::from(VALID_AMOUNT),
+                claimer_addr: String::from(TO_VERIFY_VALID2_ADDR_AMOUNT),
+                options: None,
+            },
+            &[],
+        )
+    }
+
+    fn multiplier(&self) -> Decimal {
+        let response: DecayMultiplierResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(&self.drop, &QueryMsg::DecayMultiplier {})
+            .unwrap();
+        response.multiplier
+    }
+
+    fn stats(&self) -> ClaimStats {
+        self.app
+            .wrap()
+            .query_wasm_smart(&self.drop, &QueryMsg::GetStats {})
+            .unwrap()
+    }
+
+    fn balance(&self, address: &str) -> Uint128 {
+        let Coin { amount, .. } = self.app.wrap().query_balance(address, DENOM).unwrap();
+        amount
+    }
+}
+
+fn merkle_drop_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new(
+            merkle_drop::contract::execute,
+            merkle_drop::contract::instantiate,
+            merkle_drop::contract::query,
+        )
+        .with_reply(merkle_drop::contract::reply),
+    )
+}
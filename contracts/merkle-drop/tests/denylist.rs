@@ -0,0 +1,178 @@
+mod test_env;
+use cosmwasm_std::{coins, Addr, Coin, Empty, Uint128};
+use cw_multi_test::{App, AppBuilder, AppResponse, Contract, ContractWrapper, Executor};
+use merkle_drop::msg::{DenylistResponse, ExecuteMsg, InstantiateMsg, QueryMsg};
+use merkle_drop::state::Distribution;
+use merkle_drop::ContractError;
+use test_env::{default_instantiate_msg, valid_proof, TO_VERIFY_VALID2_ADDR_AMOUNT, VALID_AMOUNT};
+
+const OWNER: &str = "owner";
+const CLAIM_SENDER: &str = "claim_sender";
+const DENOM: &str = "uosmo";
+const FUND_AMOUNT: u128 = 10_000_000;
+
+#[test]
+fn blocked_address_cannot_claim() {
+    let mut env = DenylistEnv::new();
+
+    env.update(ExecuteMsg::AddToDenylist {
+        addresses: vec![String::from(TO_VERIFY_VALID2_ADDR_AMOUNT)],
+    })
+    .unwrap();
+
+    let err = env.claim().unwrap_err();
+    assert_eq!(
+        ContractError::AddressBlocked {
+            address: String::from(TO_VERIFY_VALID2_ADDR_AMOUNT),
+        }
+        .to_string(),
+        err.root_cause().to_string()
+    );
+
+    env.update(ExecuteMsg::RemoveFromDenylist {
+        addresses: vec![String::from(TO_VERIFY_VALID2_ADDR_AMOUNT)],
+    })
+    .unwrap();
+
+    env.claim().unwrap();
+    assert_eq!(
+        Uint128::from(VALID_AMOUNT),
+        env.balance(TO_VERIFY_VALID2_ADDR_AMOUNT)
+    );
+}
+
+#[test]
+fn denylist_owner_only() {
+    let mut env = DenylistEnv::new();
+
+    let err = env
+        .app
+        .execute_contract(
+            Addr::unchecked(CLAIM_SENDER),
+            env.drop.clone(),
+            &ExecuteMsg::AddToDenylist {
+                addresses: vec![String::from(TO_VERIFY_VALID2_ADDR_AMOUNT)],
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::UnauthorizedSender {
+            sender: String::from(CLAIM_SENDER),
+            owner: String::from(OWNER),
+        }
+        .to_string(),
+        err.root_cause().to_string()
+    );
+}
+
+#[test]
+fn query_denylist_paginated() {
+    let mut env = DenylistEnv::new();
+
+    env.update(ExecuteMsg::AddToDenylist {
+        addresses: vec![
+            String::from("addr3"),
+            String::from("addr1"),
+            String::from("addr2"),
+        ],
+    })
+    .unwrap();
+
+    assert_eq!(vec!["addr1", "addr2"], env.denylist(None, Some(2)));
+    assert_eq!(
+        vec!["addr3"],
+        env.denylist(Some(String::from("addr2")), Some(2))
+    );
+    assert_eq!(vec!["addr1", "addr2", "addr3"], env.denylist(None, None));
+}
+
+// ======= helpers ========
+
+struct DenylistEnv {
+    app: App,
+    drop: Addr,
+}
+
+impl DenylistEnv {
+    fn new() -> Self {
+        let mut app = AppBuilder::new().build(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &Addr::unchecked(OWNER), coins(FUND_AMOUNT, DENOM))
+                .unwrap()
+        });
+
+        let code_id = app.store_code(merkle_drop_contract());
+        let drop = app
+            .instantiate_contract(
+                code_id,
+                Addr::unchecked(OWNER),
+                &InstantiateMsg {
+                    distribution: Some(Distribution::Escrow {
+                        denom: String::from(DENOM),
+                    }),
+                    ..default_instantiate_msg()
+                },
+                &[],
+                "merkle-drop",
+                None,
+            )
+            .unwrap();
+
+        app.execute_contract(
+            Addr::unchecked(OWNER),
+            drop.clone(),
+            &ExecuteMsg::Fund {},
+            &coins(FUND_AMOUNT, DENOM),
+        )
+        .unwrap();
+
+        DenylistEnv { app, drop }
+    }
+
+    fn update(&mut self, msg: ExecuteMsg) -> anyhow::Result<AppResponse> {
+        self.app
+            .execute_contract(Addr::unchecked(OWNER), self.drop.clone(), &msg, &[])
+    }
+
+    fn claim(&mut self) -> anyhow::Result<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(CLAIM_SENDER),
+            self.drop.clone(),
+            &ExecuteMsg::Claim {
+                proof: valid_proof(),
+                proof_version: None,
+                amount: Uint128::from(VALID_AMOUNT),
+                claimer_addr: String::from(TO_VERIFY_VALID2_ADDR_AMOUNT),
+                options: None,
+            },
+            &[],
+        )
+    }
+
+    fn denylist(&self, start_after: Option<String>, limit: Option<u32>) -> Vec<String> {
+        let response: DenylistResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(&self.drop, &QueryMsg::Denylist { start_after, limit })
+            .unwrap();
+        response.addresses
+    }
+
+    fn balance(&self, address: &str) -> Uint128 {
+        let Coin { amount, .. } = self.app.wrap().query_balance(address, DENOM).unwrap();
+        amount
+    }
+}
+
+fn merkle_drop_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new(
+            merkle_drop::contract::execute,
+            merkle_drop::contract::instantiate,
+            merkle_drop::contract::query,
+        )
+        .with_reply(merkle_drop::contract::reply),
+    )
+}
@@ -0,0 +1,138 @@
+mod test_env;
+use cosmwasm_std::{Coin, Timestamp, Uint128};
+use merkle_drop::msg::{ExecuteMsg, InstantiateMsg};
+use merkle_drop::state::Distribution;
+use osmosis_testing::{Account, Module, Wasm};
+use test_env::*;
+
+const ESCROW_DENOM: &str = "uion";
+const ESCROW_AMOUNT: u128 = 10_000_000;
+
+#[test]
+fn escrow_claim_succeeds() {
+    let test_env = escrow_env(now_seconds() + 60 * 60);
+    let wasm = Wasm::new(&test_env.app);
+
+    let claim_sender = test_env
+        .app
+        .init_account(&[Coin::new(1_000_000_000_000, "uosmo")])
+        .unwrap();
+
+    let res = wasm.execute(
+        &test_env.contract_address,
+        &ExecuteMsg::Claim {
+            proof: valid_proof(),
+            proof_version: None,
+            amount: Uint128::from(VALID_AMOUNT),
+            claimer_addr: String::from(TO_VERIFY_VALID2_ADDR_AMOUNT),
+            options: None,
+        },
+        &[],
+        &claim_sender,
+    );
+    assert!(res.is_ok(), "{:?}", res.unwrap_err());
+
+    assert_eq!(
+        Uint128::from(VALID_AMOUNT),
+        test_env.query_balance(TO_VERIFY_VALID2_ADDR_AMOUNT, ESCROW_DENOM)
+    );
+    assert_eq!(
+        Uint128::from(ESCROW_AMOUNT - VALID_AMOUNT),
+        test_env.query_balance(&test_env.contract_address, ESCROW_DENOM)
+    );
+}
+
+#[test]
+fn escrow_set_subdenom_fails() {
+    let test_env = escrow_env(now_seconds() + 60 * 60);
+    let wasm = Wasm::new(&test_env.app);
+
+    let err = wasm
+        .execute(
+            &test_env.contract_address,
+            &ExecuteMsg::SetSubDenom {
+                subdenom: String::from(VALID_SUBDENOM),
+            },
+            &[],
+            &test_env.owner,
+        )
+        .unwrap_err();
+
+    assert!(
+        format!("{:?}", err).contains("not supported by the configured distribution"),
+        "{:?}",
+        err
+    );
+}
+
+#[test]
+fn escrow_withdraw_before_claim_end_fails() {
+    let test_env = escrow_env(now_seconds() + 60 * 60);
+    let wasm = Wasm::new(&test_env.app);
+
+    let err = wasm
+        .execute(
+            &test_env.contract_address,
+            &ExecuteMsg::WithdrawRemaining { recipient: None },
+            &[],
+            &test_env.owner,
+        )
+        .unwrap_err();
+
+    assert!(
+        format!("{:?}", err).contains("Claim window is still open"),
+        "{:?}",
+        err
+    );
+}
+
+#[test]
+fn escrow_withdraw_after_claim_end_succeeds() {
+    // claim window already ended by the time the contract is funded.
+    let test_env = escrow_env(now_seconds() - 1);
+    let wasm = Wasm::new(&test_env.app);
+
+    let recipient = test_env.app.init_account(&[Coin::new(1, "uosmo")]).unwrap();
+
+    let res = wasm.execute(
+        &test_env.contract_address,
+        &ExecuteMsg::WithdrawRemaining {
+            recipient: Some(recipient.address()),
+        },
+        &[],
+        &test_env.owner,
+    );
+    assert!(res.is_ok(), "{:?}", res.unwrap_err());
+
+    assert_eq!(
+        Uint128::from(ESCROW_AMOUNT),
+        test_env.query_balance(&recipient.address(), ESCROW_DENOM)
+    );
+    assert_eq!(
+        Uint128::zero(),
+        test_env.query_balance(&test_env.contract_address, ESCROW_DENOM)
+    );
+}
+
+// ======= helpers ========
+
+fn escrow_env(claim_end_seconds: u64) -> TestEnv {
+    let test_env = TestEnv::with_instantiate_msg(InstantiateMsg {
+        distribution: Some(Distribution::Escrow {
+            denom: String::from(ESCROW_DENOM),
+        }),
+        claim_end: Some(Timestamp::from_seconds(claim_end_seconds)),
+        ..default_instantiate_msg()
+    });
+
+    let wasm = Wasm::new(&test_env.app);
+    wasm.execute(
+        &test_env.contract_address,
+        &ExecuteMsg::Fund {},
+        &[Coin::new(ESCROW_AMOUNT, ESCROW_DENOM)],
+        &test_env.owner,
+    )
+    .unwrap();
+
+    test_env
+}
@@ -0,0 +1,289 @@
+mod test_env;
+use cosmwasm_std::{
+    coins, to_binary, Addr, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdError,
+    StdResult, Uint128,
+};
+use cw_multi_test::{App, AppBuilder, AppResponse, Contract, ContractWrapper, Executor};
+use cw_storage_plus::Item;
+use merkle_drop::msg::{
+    ClaimHookExecuteMsg, ClaimHookMsg, ExecuteMsg, HookResponse, InstantiateMsg, ListHooksResponse,
+    QueryMsg,
+};
+use merkle_drop::state::{ClaimStats, Distribution, HookFailurePolicy};
+use merkle_drop::ContractError;
+use test_env::{default_instantiate_msg, valid_proof, TO_VERIFY_VALID2_ADDR_AMOUNT, VALID_AMOUNT};
+
+const OWNER: &str = "owner";
+const CLAIM_SENDER: &str = "claim_sender";
+const DENOM: &str = "uosmo";
+const FUND_AMOUNT: u128 = 10_000_000;
+
+#[test]
+fn hook_receives_claim() {
+    let mut env = HookEnv::new();
+    let hook = env.instantiate_hook(recording_hook_contract());
+    env.add_hook(&hook, None).unwrap();
+
+    env.claim().unwrap();
+
+    let received: Option<ClaimHookMsg> = env.app.wrap().query_wasm_smart(&hook, &Empty {}).unwrap();
+    assert_eq!(
+        Some(ClaimHookMsg {
+            address: String::from(TO_VERIFY_VALID2_ADDR_AMOUNT),
+            amount: Uint128::from(VALID_AMOUNT),
+            denom: String::from(DENOM),
+        }),
+        received
+    );
+}
+
+#[test]
+fn failing_hook_ignored() {
+    let mut env = HookEnv::new();
+    let hook = env.instantiate_hook(failing_hook_contract());
+    env.add_hook(&hook, Some(HookFailurePolicy::Ignore))
+        .unwrap();
+
+    let res = env.claim().unwrap();
+
+    assert!(res.events.iter().any(|event| event
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "claim_hook" && attr.value == "failed")));
+    assert_eq!(1, env.stats().claimed_count);
+}
+
+#[test]
+fn failing_hook_reverts_claim() {
+    let mut env = HookEnv::new();
+    let hook = env.instantiate_hook(failing_hook_contract());
+    env.add_hook(&hook, Some(HookFailurePolicy::Revert))
+        .unwrap();
+
+    env.claim().unwrap_err();
+
+    assert_eq!(ClaimStats::default(), env.stats());
+}
+
+#[test]
+fn add_and_remove_hooks() {
+    let mut env = HookEnv::new();
+    let hook = env.instantiate_hook(recording_hook_contract());
+
+    let err = env
+        .app
+        .execute_contract(
+            Addr::unchecked(CLAIM_SENDER),
+            env.drop.clone(),
+            &ExecuteMsg::AddHook {
+                address: hook.to_string(),
+                failure_policy: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::UnauthorizedSender {
+            sender: String::from(CLAIM_SENDER),
+            owner: String::from(OWNER),
+        }
+        .to_string(),
+        err.root_cause().to_string()
+    );
+
+    env.add_hook(&hook, Some(HookFailurePolicy::Revert))
+        .unwrap();
+    let err = env.add_hook(&hook, None).unwrap_err();
+    assert_eq!(
+        ContractError::HookAlreadyRegistered {
+            address: hook.to_string(),
+        }
+        .to_string(),
+        err.root_cause().to_string()
+    );
+
+    assert_eq!(
+        vec![HookResponse {
+            address: hook.to_string(),
+            failure_policy: HookFailurePolicy::Revert,
+        }],
+        env.hooks()
+    );
+
+    env.app
+        .execute_contract(
+            Addr::unchecked(OWNER),
+            env.drop.clone(),
+            &ExecuteMsg::RemoveHook {
+                address: hook.to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+    assert!(env.hooks().is_empty());
+}
+
+// ======= helpers ========
+
+struct HookEnv {
+    app: App,
+    drop: Addr,
+}
+
+impl HookEnv {
+    fn new() -> Self {
+        let mut app = AppBuilder::new().build(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &Addr::unchecked(OWNER), coins(FUND_AMOUNT, DENOM))
+                .unwrap()
+        });
+
+        let code_id = app.store_code(merkle_drop_contract());
+        let drop = app
+            .instantiate_contract(
+                code_id,
+                Addr::unchecked(OWNER),
+                &InstantiateMsg {
+                    distribution: Some(Distribution::Escrow {
+                        denom: String::from(DENOM),
+                    }),
+                    ..default_instantiate_msg()
+                },
+                &[],
+                "merkle-drop",
+                None,
+            )
+            .unwrap();
+
+        app.execute_contract(
+            Addr::unchecked(OWNER),
+            drop.clone(),
+            &ExecuteMsg::Fund {},
+            &coins(FUND_AMOUNT, DENOM),
+        )
+        .unwrap();
+
+        HookEnv { app, drop }
+    }
+
+    fn instantiate_hook(&mut self, contract: Box<dyn Contract<Empty>>) -> Addr {
+        let code_id = self.app.store_code(contract);
+        self.app
+            .instantiate_contract(
+                code_id,
+                Addr::unchecked(OWNER),
+                &Empty {},
+                &[],
+                "hook",
+                None,
+            )
+            .unwrap()
+    }
+
+    fn add_hook(
+        &mut self,
+        hook: &Addr,
+        failure_policy: Option<HookFailurePolicy>,
+    ) -> anyhow::Result<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(OWNER),
+            self.drop.clone(),
+            &ExecuteMsg::AddHook {
+                address: hook.to_string(),
+                failure_policy,
+            },
+            &[],
+        )
+    }
+
+    fn claim(&mut self) -> anyhow::Result<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(CLAIM_SENDER),
+            self.drop.clone(),
+            &ExecuteMsg::Claim {
+                proof: valid_proof(),
+                proof_version: None,
+                amount: Uint128::from(VALID_AMOUNT),
+                claimer_addr: String::from(TO_VERIFY_VALID2_ADDR_AMOUNT),
+                options: None,
+            },
+            &[],
+        )
+    }
+
+    fn stats(&self) -> ClaimStats {
+        self.app
+            .wrap()
+            .query_wasm_smart(&self.drop, &QueryMsg::GetStats {})
+            .unwrap()
+    }
+
+    fn hooks(&self) -> Vec<HookResponse> {
+        let response: ListHooksResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(&self.drop, &QueryMsg::ListHooks {})
+            .unwrap();
+        response.hooks
+    }
+}
+
+fn merkle_drop_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new(
+            merkle_drop::contract::execute,
+            merkle_drop::contract::instantiate,
+            merkle_drop::contract::query,
+        )
+        .with_reply(merkle_drop::contract::reply),
+    )
+}
+
+// ======= hook contracts ========
+
+const LAST_CLAIM: Item<ClaimHookMsg> = Item::new("last_claim");
+
+fn hook_instantiate(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+    Ok(Response::new())
+}
+
+fn recording_hook_execute(
+    deps: DepsMut,
+    _: Env,
+    _: MessageInfo,
+    msg: ClaimHookExecuteMsg,
+) -> StdResult<Response> {
+    let ClaimHookExecuteMsg::ClaimHook(claim) = msg;
+    LAST_CLAIM.save(deps.storage, &claim)?;
+    Ok(Response::new())
+}
+
+fn failing_hook_execute(
+    _: DepsMut,
+    _: Env,
+    _: MessageInfo,
+    _: ClaimHookExecuteMsg,
+) -> StdResult<Response> {
+    Err(StdError::generic_err("hook failed"))
+}
+
+fn hook_query(deps: Deps, _: Env, _: Empty) -> StdResult<Binary> {
+    to_binary(&LAST_CLAIM.may_load(deps.storage)?)
+}
+
+fn recording_hook_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        recording_hook_execute,
+        hook_instantiate,
+        hook_query,
+    ))
+}
+
+fn failing_hook_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        failing_hook_execute,
+        hook_instantiate,
+        hook_query,
+    ))
+}
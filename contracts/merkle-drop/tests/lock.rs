@@ -0,0 +1,207 @@
+mod test_env;
+use cosmwasm_std::{Coin, Decimal, Uint128};
+use merkle::Tree;
+use merkle_drop::msg::{ClaimOptions, ClaimProof, ExecuteMsg, InstantiateMsg};
+use merkle_drop::state::{Distribution, LockOption};
+use osmosis_std::shim::{Any, Timestamp};
+use osmosis_std::types::cosmos::authz::v1beta1::{GenericAuthorization, Grant, MsgGrant};
+use osmosis_std::types::osmosis::lockup::{
+    AccountLockedCoinsRequest, AccountLockedCoinsResponse, MsgLockTokens,
+};
+use osmosis_testing::cosmrs::tx::MessageExt;
+use osmosis_testing::{
+    Account, ExecuteResponse, Module, OsmosisTestApp, Runner, RunnerError, SigningAccount, Wasm,
+};
+use test_env::*;
+
+const ESCROW_DENOM: &str = "uosmo";
+const ESCROW_AMOUNT: u128 = 10_000_000;
+const CLAIM_AMOUNT: u128 = 1_000_000;
+const LOCK_SECONDS: u64 = 60 * 60 * 24 * 14;
+
+#[test]
+fn claim_and_lock_succeeds() {
+    let env = LockEnv::new();
+    env.grant_lock();
+
+    let res = env.claim(&env.claimer, Some(LOCK_SECONDS));
+    assert!(res.is_ok(), "{:?}", res.unwrap_err());
+
+    // 10% bonus, locked along with the claimed amount.
+    let locked = env
+        .test_env
+        .app
+        .query::<AccountLockedCoinsRequest, AccountLockedCoinsResponse>(
+            "/osmosis.lockup.Query/AccountLockedCoins",
+            &AccountLockedCoinsRequest {
+                owner: env.claimer.address(),
+            },
+        )
+        .unwrap()
+        .coins;
+    assert_eq!(1, locked.len());
+    assert_eq!(ESCROW_DENOM, locked[0].denom);
+    assert_eq!((CLAIM_AMOUNT * 11 / 10).to_string(), locked[0].amount);
+
+    assert_eq!(
+        Uint128::from(ESCROW_AMOUNT - CLAIM_AMOUNT * 11 / 10),
+        env.test_env
+            .query_balance(&env.test_env.contract_address, ESCROW_DENOM)
+    );
+}
+
+#[test]
+fn claim_and_lock_by_other_sender_fails() {
+    let env = LockEnv::new();
+    env.grant_lock();
+
+    let err = env
+        .claim(&env.test_env.owner, Some(LOCK_SECONDS))
+        .unwrap_err();
+
+    assert!(
+        format!("{:?}", err).contains("Only the claimer can opt into a lock"),
+        "{:?}",
+        err
+    );
+}
+
+#[test]
+fn claim_and_lock_unsupported_duration_fails() {
+    let env = LockEnv::new();
+    env.grant_lock();
+
+    let err = env.claim(&env.claimer, Some(LOCK_SECONDS + 1)).unwrap_err();
+
+    assert!(
+        format!("{:?}", err).contains(&format!(
+            "Lock duration {} is not supported",
+            LOCK_SECONDS + 1
+        )),
+        "{:?}",
+        err
+    );
+}
+
+#[test]
+fn claim_and_lock_without_grant_fails() {
+    let env = LockEnv::new();
+
+    let err = env.claim(&env.claimer, Some(LOCK_SECONDS)).unwrap_err();
+
+    assert!(
+        format!("{:?}", err).contains(MsgLockTokens::TYPE_URL),
+        "{:?}",
+        err
+    );
+}
+
+// ======= helpers ========
+
+struct LockEnv {
+    test_env: TestEnv,
+    claimer: SigningAccount,
+    proof: ClaimProof,
+}
+
+impl LockEnv {
+    // new instantiates an escrow drop over a tree that includes an account
+    // the tests can sign for, since the claimer must opt into the lock.
+    fn new() -> Self {
+        let app = OsmosisTestApp::new();
+        let claimer = app
+            .init_account(&[Coin::new(1_000_000_000_000, "uosmo")])
+            .unwrap();
+
+        let leaves = vec![
+            format!("{}{}", claimer.address(), CLAIM_AMOUNT),
+            format!("{}{}", TO_VERIFY_VALID2_ADDR_AMOUNT, VALID_AMOUNT),
+        ];
+        let tree: Tree = Tree::new(&leaves).unwrap();
+        let root = base64::encode(tree.get_root().unwrap());
+        let proof = ClaimProof::Legacy(
+            serde_json_wasm::to_string(&tree.find_proof(&leaves[0]).unwrap()).unwrap(),
+        );
+
+        let test_env = TestEnv::with_app(
+            app,
+            InstantiateMsg {
+                merkle_root: root,
+                distribution: Some(Distribution::Escrow {
+                    denom: String::from(ESCROW_DENOM),
+                }),
+                lock_options: Some(vec![LockOption {
+                    duration: LOCK_SECONDS,
+                    bonus: Decimal::percent(10),
+                }]),
+                ..default_instantiate_msg()
+            },
+        );
+
+        LockEnv {
+            test_env,
+            claimer,
+            proof,
+        }
+    }
+
+    fn grant_lock(&self) {
+        let authorization = GenericAuthorization {
+            msg: String::from(MsgLockTokens::TYPE_URL),
+        };
+
+        let _res: ExecuteResponse<MsgGrant> = self
+            .test_env
+            .app
+            .execute(
+                MsgGrant {
+                    granter: self.claimer.address(),
+                    grantee: self.test_env.contract_address.clone(),
+                    grant: Some(Grant {
+                        authorization: Some(Any {
+                            type_url: String::from(GenericAuthorization::TYPE_URL),
+                            value: authorization.to_bytes().unwrap(),
+                        }),
+                        expiration: Some(Timestamp {
+                            seconds: now_seconds() as i64 + 60 * 60,
+                            nanos: 0,
+                        }),
+                    }),
+                },
+                MsgGrant::TYPE_URL,
+                &self.claimer,
+            )
+            .unwrap();
+    }
+
+    fn claim(
+        &self,
+        sender: &SigningAccount,
+        lock_duration: Option<u64>,
+    ) -> Result<(), RunnerError> {
+        let wasm = Wasm::new(&self.test_env.app);
+        wasm.execute(
+            &self.test_env.contract_address,
+            &ExecuteMsg::Fund {},
+            &[Coin::new(ESCROW_AMOUNT, ESCROW_DENOM)],
+            &self.test_env.owner,
+        )?;
+
+        wasm.execute(
+            &self.test_env.contract_address,
+            &ExecuteMsg::Claim {
+                proof: self.proof.clone(),
+                proof_version: None,
+                amount: Uint128::from(CLAIM_AMOUNT),
+                claimer_addr: self.claimer.address(),
+                options: Some(ClaimOptions {
+                    lock_duration,
+                    ..ClaimOptions::default()
+                }),
+            },
+            &[],
+            sender,
+        )
+        .map(|_| ())
+    }
+}
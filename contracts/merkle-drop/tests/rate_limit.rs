@@ -0,0 +1,255 @@
+mod test_env;
+use cosmwasm_std::{coins, Addr, Empty, Uint128};
+use cw_multi_test::{App, AppBuilder, AppResponse, Contract, ContractWrapper, Executor};
+use merkle::Tree;
+use merkle_drop::msg::{
+    ExecuteMsg, InstantiateMsg, ProofVersion, QueryMsg, RateLimitUsageResponse,
+};
+use merkle_drop::state::{Distribution, RateLimit, RateLimits, RateUsage, WindowRateLimit};
+use merkle_drop::ContractError;
+use test_env::{compact_proof, default_instantiate_msg};
+
+const OWNER: &str = "owner";
+const DENOM: &str = "uosmo";
+const FUND_AMOUNT: u128 = 10_000_000;
+const CLAIM_AMOUNT: u128 = 1_000;
+const CLAIMERS: [&str; 3] = ["osmo1claimera", "osmo1claimerb", "osmo1claimerc"];
+
+#[test]
+fn per_block_claim_limit() {
+    let mut env = RateLimitEnv::new(RateLimits {
+        per_block: Some(RateLimit {
+            max_claims: Some(1),
+            max_amount: None,
+        }),
+        per_window: None,
+    });
+
+    env.claim(0).unwrap();
+    let err = env.claim(1).unwrap_err();
+    assert_eq!(
+        ContractError::ClaimRateLimitExceeded {
+            period: String::from("block"),
+            max_claims: 1,
+        }
+        .to_string(),
+        err.root_cause().to_string()
+    );
+
+    env.app.update_block(|block| block.height += 1);
+    env.claim(1).unwrap();
+}
+
+#[test]
+fn per_window_amount_limit() {
+    let mut env = RateLimitEnv::new(RateLimits {
+        per_block: None,
+        per_window: Some(WindowRateLimit {
+            seconds: 60 * 60,
+            max_claims: None,
+            max_amount: Some(Uint128::new(2 * CLAIM_AMOUNT)),
+        }),
+    });
+
+    env.claim(0).unwrap();
+    env.app.update_block(|block| block.height += 1);
+    env.claim(1).unwrap();
+
+    assert_eq!(
+        RateUsage {
+            claims: 2,
+            amount: Uint128::new(2 * CLAIM_AMOUNT),
+        },
+        env.usage().window
+    );
+
+    env.app.update_block(|block| block.height += 1);
+    let err = env.claim(2).unwrap_err();
+    assert_eq!(
+        ContractError::AmountRateLimitExceeded {
+            period: String::from("window"),
+            max_amount: Uint128::new(2 * CLAIM_AMOUNT),
+        }
+        .to_string(),
+        err.root_cause().to_string()
+    );
+}
+
+#[test]
+fn owner_updates_rate_limits() {
+    let mut env = RateLimitEnv::new(RateLimits {
+        per_block: Some(RateLimit {
+            max_claims: Some(1),
+            max_amount: None,
+        }),
+        per_window: None,
+    });
+
+    env.claim(0).unwrap();
+
+    let err = env
+        .app
+        .execute_contract(
+            Addr::unchecked(CLAIMERS[0]),
+            env.drop.clone(),
+            &ExecuteMsg::UpdateRateLimits { rate_limits: None },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::UnauthorizedSender {
+            sender: String::from(CLAIMERS[0]),
+            owner: String::from(OWNER),
+        }
+        .to_string(),
+        err.root_cause().to_string()
+    );
+
+    env.app
+        .execute_contract(
+            Addr::unchecked(OWNER),
+            env.drop.clone(),
+            &ExecuteMsg::UpdateRateLimits { rate_limits: None },
+            &[],
+        )
+        .unwrap();
+
+    env.claim(1).unwrap();
+    env.claim(2).unwrap();
+    assert_eq!(None, env.usage().rate_limits);
+}
+
+#[test]
+fn usage_kept_unless_window_length_changes() {
+    let window = |seconds: u64, max_claims: u64| RateLimits {
+        per_block: None,
+        per_window: Some(WindowRateLimit {
+            seconds,
+            max_claims: Some(max_claims),
+            max_amount: None,
+        }),
+    };
+    let mut env = RateLimitEnv::new(window(60 * 60, 1));
+
+    env.claim(0).unwrap();
+
+    // raising the limit keeps counting the claims of the window.
+    env.update_rate_limits(window(60 * 60, 2));
+    assert_eq!(1, env.usage().window.claims);
+    env.claim(1).unwrap();
+    assert_eq!(2, env.usage().window.claims);
+
+    // a new window length starts counting over.
+    env.update_rate_limits(window(2 * 60 * 60, 2));
+    assert_eq!(RateUsage::default(), env.usage().window);
+    env.claim(2).unwrap();
+}
+
+// ======= helpers ========
+
+struct RateLimitEnv {
+    app: App,
+    drop: Addr,
+    tree: Tree,
+    leaves: Vec<String>,
+}
+
+impl RateLimitEnv {
+    // new instantiates an escrow drop allocating CLAIM_AMOUNT to each of CLAIMERS.
+    fn new(rate_limits: RateLimits) -> Self {
+        let mut app = AppBuilder::new().build(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &Addr::unchecked(OWNER), coins(FUND_AMOUNT, DENOM))
+                .unwrap()
+        });
+
+        let leaves: Vec<String> = CLAIMERS
+            .iter()
+            .map(|claimer| format!("{}{}", claimer, CLAIM_AMOUNT))
+            .collect();
+        let tree: Tree = Tree::new(&leaves).unwrap();
+
+        let code_id = app.store_code(merkle_drop_contract());
+        let drop = app
+            .instantiate_contract(
+                code_id,
+                Addr::unchecked(OWNER),
+                &InstantiateMsg {
+                    merkle_root: base64::encode(tree.get_root().unwrap()),
+                    distribution: Some(Distribution::Escrow {
+                        denom: String::from(DENOM),
+                    }),
+                    rate_limits: Some(rate_limits),
+                    ..default_instantiate_msg()
+                },
+                &[],
+                "merkle-drop",
+                None,
+            )
+            .unwrap();
+
+        app.execute_contract(
+            Addr::unchecked(OWNER),
+            drop.clone(),
+            &ExecuteMsg::Fund {},
+            &coins(FUND_AMOUNT, DENOM),
+        )
+        .unwrap();
+
+        RateLimitEnv {
+            app,
+            drop,
+            tree,
+            leaves,
+        }
+    }
+
+    fn claim(&mut self, index: usize) -> anyhow::Result<AppResponse> {
+        let proof = self.tree.find_proof(&self.leaves[index]).unwrap();
+
+        self.app.execute_contract(
+            Addr::unchecked(CLAIMERS[index]),
+            self.drop.clone(),
+            &ExecuteMsg::Claim {
+                proof: compact_proof(&proof),
+                proof_version: Some(ProofVersion::Compact),
+                amount: Uint128::new(CLAIM_AMOUNT),
+                claimer_addr: String::from(CLAIMERS[index]),
+                options: None,
+            },
+            &[],
+        )
+    }
+
+    fn update_rate_limits(&mut self, rate_limits: RateLimits) {
+        self.app
+            .execute_contract(
+                Addr::unchecked(OWNER),
+                self.drop.clone(),
+                &ExecuteMsg::UpdateRateLimits {
+                    rate_limits: Some(rate_limits),
+                },
+                &[],
+            )
+            .unwrap();
+    }
+
+    fn usage(&self) -> RateLimitUsageResponse {
+        self.app
+            .wrap()
+            .query_wasm_smart(&self.drop, &QueryMsg::RateLimitUsage {})
+            .unwrap()
+    }
+}
+
+fn merkle_drop_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new(
+            merkle_drop::contract::execute,
+            merkle_drop::contract::instantiate,
+            merkle_drop::contract::query,
+        )
+        .with_reply(merkle_drop::contract::reply),
+    )
+}
@@ -0,0 +1,204 @@
+mod test_env;
+use cosmwasm_std::{coins, Addr, Coin, Decimal, Empty, Uint128};
+use cw_multi_test::{App, AppBuilder, AppResponse, Contract, ContractWrapper, Executor};
+use merkle::Tree;
+use merkle_drop::msg::{
+    ClaimOptions, ExecuteMsg, InstantiateMsg, ProofVersion, QueryMsg, ReferralEarningsResponse,
+    ReferrerEarnings,
+};
+use merkle_drop::state::{Distribution, ReferralConfig};
+use merkle_drop::ContractError;
+use test_env::{compact_proof, default_instantiate_msg};
+
+const OWNER: &str = "owner";
+const DENOM: &str = "uosmo";
+const FUND_AMOUNT: u128 = 10_000_000;
+const CLAIM_AMOUNT: u128 = 1_000;
+const REFERRER: &str = "osmo1referrer";
+const REFERRED: &str = "osmo1referred";
+
+#[test]
+fn referrer_rewarded() {
+    let mut env = ReferralEnv::new();
+
+    env.claim(REFERRER, None).unwrap();
+    env.claim(REFERRED, Some(REFERRER)).unwrap();
+
+    // 5% of the referred claim.
+    assert_eq!(Uint128::new(CLAIM_AMOUNT + 50), env.balance(REFERRER));
+    assert_eq!(Uint128::new(CLAIM_AMOUNT), env.balance(REFERRED));
+    assert_eq!(
+        vec![ReferrerEarnings {
+            referrer: String::from(REFERRER),
+            referrals: 1,
+            amount: Uint128::new(50),
+        }],
+        env.earnings()
+    );
+}
+
+#[test]
+fn unclaimed_referrer_rejected() {
+    let mut env = ReferralEnv::new();
+
+    let err = env.claim(REFERRED, Some(REFERRER)).unwrap_err();
+    assert_eq!(
+        ContractError::InvalidReferrer {
+            referrer: String::from(REFERRER),
+        }
+        .to_string(),
+        err.root_cause().to_string()
+    );
+
+    let err = env.claim(REFERRED, Some(REFERRED)).unwrap_err();
+    assert_eq!(
+        ContractError::InvalidReferrer {
+            referrer: String::from(REFERRED),
+        }
+        .to_string(),
+        err.root_cause().to_string()
+    );
+    assert!(env.earnings().is_empty());
+}
+
+#[test]
+fn referrer_set_by_other_sender_rejected() {
+    let mut env = ReferralEnv::new();
+
+    env.claim(REFERRER, None).unwrap();
+
+    // proofs are public, the referrer cannot submit the claims of others to
+    // be rewarded for them.
+    let err = env
+        .claim_as(REFERRER, REFERRED, Some(REFERRER))
+        .unwrap_err();
+    assert_eq!(
+        ContractError::ReferralRequiresClaimer {}.to_string(),
+        err.root_cause().to_string()
+    );
+
+    // the claim itself can still be submitted by another sender.
+    env.claim_as(REFERRER, REFERRED, None).unwrap();
+    assert_eq!(Uint128::new(CLAIM_AMOUNT), env.balance(REFERRER));
+    assert_eq!(Uint128::new(CLAIM_AMOUNT), env.balance(REFERRED));
+    assert!(env.earnings().is_empty());
+}
+
+// ======= helpers ========
+
+struct ReferralEnv {
+    app: App,
+    drop: Addr,
+    tree: Tree,
+}
+
+impl ReferralEnv {
+    // new instantiates an escrow drop allocating CLAIM_AMOUNT to REFERRER and
+    // REFERRED, rewarding referrers with 5% of the referred claims.
+    fn new() -> Self {
+        let mut app = AppBuilder::new().build(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &Addr::unchecked(OWNER), coins(FUND_AMOUNT, DENOM))
+                .unwrap()
+        });
+
+        let tree: Tree = Tree::new(&[leaf(REFERRER), leaf(REFERRED)]).unwrap();
+
+        let code_id = app.store_code(merkle_drop_contract());
+        let drop = app
+            .instantiate_contract(
+                code_id,
+                Addr::unchecked(OWNER),
+                &InstantiateMsg {
+                    merkle_root: base64::encode(tree.get_root().unwrap()),
+                    distribution: Some(Distribution::Escrow {
+                        denom: String::from(DENOM),
+                    }),
+                    referral: Some(ReferralConfig {
+                        rate: Decimal::percent(5),
+                        cap: Uint128::new(500),
+                    }),
+                    ..default_instantiate_msg()
+                },
+                &[],
+                "merkle-drop",
+                None,
+            )
+            .unwrap();
+
+        app.execute_contract(
+            Addr::unchecked(OWNER),
+            drop.clone(),
+            &ExecuteMsg::Fund {},
+            &coins(FUND_AMOUNT, DENOM),
+        )
+        .unwrap();
+
+        ReferralEnv { app, drop, tree }
+    }
+
+    fn claim(&mut self, claimer: &str, referrer: Option<&str>) -> anyhow::Result<AppResponse> {
+        self.claim_as(claimer, claimer, referrer)
+    }
+
+    fn claim_as(
+        &mut self,
+        sender: &str,
+        claimer: &str,
+        referrer: Option<&str>,
+    ) -> anyhow::Result<AppResponse> {
+        let proof = self.tree.find_proof(&leaf(claimer)).unwrap();
+
+        self.app.execute_contract(
+            Addr::unchecked(sender),
+            self.drop.clone(),
+            &ExecuteMsg::Claim {
+                proof: compact_proof(&proof),
+                proof_version: Some(ProofVersion::Compact),
+                amount: Uint128::new(CLAIM_AMOUNT),
+                claimer_addr: String::from(claimer),
+                options: Some(ClaimOptions {
+                    referrer: referrer.map(String::from),
+                    ..ClaimOptions::default()
+                }),
+            },
+            &[],
+        )
+    }
+
+    fn earnings(&self) -> Vec<ReferrerEarnings> {
+        let response: ReferralEarningsResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(
+                &self.drop,
+                &QueryMsg::ReferralEarnings {
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        response.referrers
+    }
+
+    fn balance(&self, address: &str) -> Uint128 {
+        let Coin { amount, .. } = self.app.wrap().query_balance(address, DENOM).unwrap();
+        amount
+    }
+}
+
+fn leaf(address: &str) -> String {
+    format!("{}{}", address, CLAIM_AMOUNT)
+}
+
+fn merkle_drop_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new(
+            merkle_drop::contract::execute,
+            merkle_drop::contract::instantiate,
+            merkle_drop::contract::query,
+        )
+        .with_reply(merkle_drop::contract::reply),
+    )
+}
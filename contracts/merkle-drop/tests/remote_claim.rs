@@ -0,0 +1,255 @@
+mod test_env;
+use cosmwasm_std::testing::{
+    mock_dependencies, mock_dependencies_with_balance, mock_env, mock_ibc_channel_connect_ack,
+    mock_ibc_channel_open_init, mock_ibc_packet_recv, mock_info, MockApi, MockQuerier, MockStorage,
+};
+use cosmwasm_std::{
+    coins, from_binary, to_binary, Addr, BankMsg, Binary, Coin, CosmosMsg, Env, OwnedDeps, Uint128,
+};
+use merkle::Tree;
+use merkle_drop::contract::{
+    ibc_channel_connect, ibc_channel_open, ibc_packet_receive, instantiate, query,
+};
+use merkle_drop::msg::{
+    InstantiateMsg, ProofVersion, QueryMsg, RemoteClaimAck, RemoteClaimPacket, RemoteClaimResult,
+};
+use merkle_drop::remote_claim::{REMOTE_CLAIM_ORDER, REMOTE_CLAIM_VERSION};
+use merkle_drop::state::{
+    ClaimFee, ClaimStats, Distribution, FeeAmount, RateLimit, RateLimits, RemoteClaimSource,
+};
+use merkle_drop::ContractError;
+use test_env::{compact_proof, default_instantiate_msg};
+
+// the endpoints of the channels mocked by cosmwasm_std::testing.
+const CONNECTION: &str = "connection-2";
+const COMPANION_PORT: &str = "their_port";
+const CHANNEL: &str = "channel-1";
+const DENOM: &str = "uosmo";
+const AMOUNT: u128 = 1_000;
+const CLAIMER: &str = "juno1claimer";
+const OTHER_CLAIMER: &str = "juno1other";
+const RECIPIENT: &str = "osmo1recipient";
+
+#[test]
+fn remote_claim_paid_out_over_channel() {
+    let (mut deps, tree) = remote_claim_env(None);
+
+    let res = receive(&mut deps, mock_env(), &packet(&tree, CLAIMER));
+
+    assert_eq!(
+        RemoteClaimAck::Result(to_binary(&paid()).unwrap()),
+        from_binary(&res.0).unwrap()
+    );
+    assert_eq!(
+        vec![CosmosMsg::Bank(BankMsg::Send {
+            to_address: String::from(RECIPIENT),
+            amount: coins(AMOUNT, DENOM),
+        })],
+        res.1
+    );
+    assert_eq!(1, stats(&deps).claimed_count);
+}
+
+#[test]
+fn remote_claim_failure_not_recorded() {
+    let (mut deps, tree) = remote_claim_env(Some(RateLimits {
+        per_block: Some(RateLimit {
+            max_claims: Some(1),
+            max_amount: None,
+        }),
+        per_window: None,
+    }));
+
+    receive(&mut deps, mock_env(), &packet(&tree, CLAIMER));
+
+    // the rate limit is the last check before the claim is recorded.
+    let res = receive(&mut deps, mock_env(), &packet(&tree, OTHER_CLAIMER));
+    assert_eq!(
+        RemoteClaimAck::Error(
+            ContractError::ClaimRateLimitExceeded {
+                period: String::from("block"),
+                max_claims: 1,
+            }
+            .to_string()
+        ),
+        from_binary(&res.0).unwrap()
+    );
+    assert!(res.1.is_empty());
+    assert_eq!(
+        ClaimStats {
+            claimed_count: 1,
+            claimed_amount: Uint128::new(AMOUNT),
+            forfeited_amount: Uint128::zero(),
+        },
+        stats(&deps)
+    );
+
+    // nothing was written, so the claim goes through in the next block.
+    let mut env = mock_env();
+    env.block.height += 1;
+    let res = receive(&mut deps, env, &packet(&tree, OTHER_CLAIMER));
+    assert_eq!(
+        RemoteClaimAck::Result(to_binary(&paid()).unwrap()),
+        from_binary(&res.0).unwrap()
+    );
+    assert_eq!(2, stats(&deps).claimed_count);
+}
+
+#[test]
+fn remote_claim_tokenfactory_rejected() {
+    let err = instantiate_remote_claims(default_instantiate_msg()).unwrap_err();
+
+    assert_eq!(
+        ContractError::UnsupportedDistribution {
+            action: String::from("remote_claim"),
+        }
+        .to_string(),
+        err.to_string()
+    );
+}
+
+#[test]
+fn remote_claim_cw20_rejected() {
+    let err = instantiate_remote_claims(InstantiateMsg {
+        distribution: Some(Distribution::Cw20 {
+            address: Addr::unchecked("cw20"),
+        }),
+        ..default_instantiate_msg()
+    })
+    .unwrap_err();
+
+    assert_eq!(
+        ContractError::UnsupportedDistribution {
+            action: String::from("remote_claim"),
+        }
+        .to_string(),
+        err.to_string()
+    );
+}
+
+#[test]
+fn remote_claim_fixed_fee_rejected() {
+    let err = instantiate_remote_claims(InstantiateMsg {
+        distribution: Some(Distribution::Escrow {
+            denom: String::from(DENOM),
+        }),
+        claim_fee: Some(ClaimFee {
+            amount: FeeAmount::Fixed {
+                coin: Coin::new(10, DENOM),
+            },
+            treasury: Addr::unchecked("treasury"),
+        }),
+        ..default_instantiate_msg()
+    })
+    .unwrap_err();
+
+    assert_eq!(
+        ContractError::RemoteClaimFixedFee {}.to_string(),
+        err.to_string()
+    );
+}
+
+// ======= helpers ========
+
+type MockDeps = OwnedDeps<MockStorage, MockApi, MockQuerier>;
+
+// remote_claim_env instantiates an escrow drop allocating AMOUNT to CLAIMER
+// and OTHER_CLAIMER, and opens a claim channel from the companion contract.
+fn remote_claim_env(rate_limits: Option<RateLimits>) -> (MockDeps, Tree) {
+    let mut deps = mock_dependencies_with_balance(&coins(10 * AMOUNT, DENOM));
+    let tree: Tree = Tree::new(&[leaf(CLAIMER), leaf(OTHER_CLAIMER)]).unwrap();
+
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        InstantiateMsg {
+            merkle_root: base64::encode(tree.get_root().unwrap()),
+            distribution: Some(Distribution::Escrow {
+                denom: String::from(DENOM),
+            }),
+            remote_claim_sources: Some(remote_claim_sources()),
+            rate_limits,
+            ..default_instantiate_msg()
+        },
+    )
+    .unwrap();
+
+    ibc_channel_open(
+        deps.as_mut(),
+        mock_env(),
+        mock_ibc_channel_open_init(CHANNEL, REMOTE_CLAIM_ORDER, REMOTE_CLAIM_VERSION),
+    )
+    .unwrap();
+    ibc_channel_connect(
+        deps.as_mut(),
+        mock_env(),
+        mock_ibc_channel_connect_ack(CHANNEL, REMOTE_CLAIM_ORDER, REMOTE_CLAIM_VERSION),
+    )
+    .unwrap();
+
+    (deps, tree)
+}
+
+// instantiate_remote_claims instantiates msg accepting claims from the
+// companion contract.
+fn instantiate_remote_claims(msg: InstantiateMsg) -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        InstantiateMsg {
+            remote_claim_sources: Some(remote_claim_sources()),
+            ..msg
+        },
+    )
+    .map(|_| ())
+}
+
+fn remote_claim_sources() -> Vec<RemoteClaimSource> {
+    vec![RemoteClaimSource {
+        connection_id: String::from(CONNECTION),
+        port_id: String::from(COMPANION_PORT),
+    }]
+}
+
+fn packet(tree: &Tree, claimer: &str) -> RemoteClaimPacket {
+    RemoteClaimPacket {
+        address: String::from(claimer),
+        amount: Uint128::new(AMOUNT),
+        proof: compact_proof(&tree.find_proof(&leaf(claimer)).unwrap()),
+        proof_version: Some(ProofVersion::Compact),
+        recipient: String::from(RECIPIENT),
+    }
+}
+
+// receive delivers the packet and returns the acknowledgement and the
+// messages sent.
+fn receive(deps: &mut MockDeps, env: Env, packet: &RemoteClaimPacket) -> (Binary, Vec<CosmosMsg>) {
+    let msg = mock_ibc_packet_recv(CHANNEL, packet).unwrap();
+    let res = ibc_packet_receive(deps.as_mut(), env, msg).unwrap();
+
+    (
+        res.acknowledgement,
+        res.messages.into_iter().map(|msg| msg.msg).collect(),
+    )
+}
+
+fn stats(deps: &MockDeps) -> ClaimStats {
+    from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::GetStats {}).unwrap()).unwrap()
+}
+
+// paid is the result of a claim of AMOUNT without fee or decay.
+fn paid() -> RemoteClaimResult {
+    RemoteClaimResult {
+        paid: Uint128::new(AMOUNT),
+        fee: Uint128::zero(),
+        forfeited: Uint128::zero(),
+    }
+}
+
+fn leaf(address: &str) -> String {
+    format!("{}{}", address, AMOUNT)
+}
@@ -1,6 +1,9 @@
 mod test_env;
-use merkle_drop::msg::{ExecuteMsg, GetSubdenomResponse, QueryMsg};
-use osmosis_testing::{Module, Wasm};
+use cosmwasm_std::{Coin, Timestamp, Uint128};
+use merkle_drop::msg::{
+    ExecuteMsg, GetSubdenomResponse, GrantStatusResponse, InstantiateMsg, QueryMsg,
+};
+use osmosis_testing::{Module, RunnerError, Wasm};
 use test_env::*;
 
 test_set_denom!(
@@ -8,6 +11,124 @@ test_set_denom!(
     should succeed
 );
 
+#[test]
+fn set_denom_spend_limit_covers_total_amount() {
+    let test_env = spend_limited_env(VALID_AMOUNT);
+
+    let res = execute_set_subdenom(&test_env);
+
+    assert!(res.is_ok(), "{:?}", res.unwrap_err());
+}
+
+#[test]
+fn set_denom_spend_limit_below_total_amount_fails() {
+    let test_env = spend_limited_env(VALID_AMOUNT - 1);
+
+    let err = execute_set_subdenom(&test_env).unwrap_err();
+
+    assert!(
+        format!("{:?}", err).contains(&format!(
+            "Send authorization spend limit {} is below the required {}",
+            VALID_AMOUNT - 1,
+            VALID_AMOUNT
+        )),
+        "{:?}",
+        err
+    );
+}
+
+#[test]
+fn set_denom_grants_cover_claim_window() {
+    // the test grants last 5 hours.
+    let test_env = claim_end_env(now_seconds() + 60 * 60);
+
+    let res = execute_set_subdenom(&test_env);
+    assert!(res.is_ok(), "{:?}", res.unwrap_err());
+
+    let wasm = Wasm::new(&test_env.app);
+    let status = wasm
+        .query::<QueryMsg, GrantStatusResponse>(
+            &test_env.contract_address,
+            &QueryMsg::GrantStatus {},
+        )
+        .unwrap();
+
+    assert_eq!(2, status.grants.len());
+    for grant in status.grants {
+        assert!(grant.found, "{:?}", grant);
+        assert!(grant.expiration.is_some(), "{:?}", grant);
+        assert!(grant.covers_claim_window, "{:?}", grant);
+    }
+}
+
+#[test]
+fn set_denom_grants_expire_before_claim_end_fails() {
+    let test_env = claim_end_env(now_seconds() + 10 * 60 * 60);
+
+    let err = execute_set_subdenom(&test_env).unwrap_err();
+
+    assert!(
+        format!("{:?}", err).contains("before the claim end"),
+        "{:?}",
+        err
+    );
+
+    let wasm = Wasm::new(&test_env.app);
+    let status = wasm
+        .query::<QueryMsg, GrantStatusResponse>(
+            &test_env.contract_address,
+            &QueryMsg::GrantStatus {},
+        )
+        .unwrap();
+
+    assert!(status.grants.iter().all(|grant| grant.found));
+    assert!(status.grants.iter().all(|grant| !grant.covers_claim_window));
+}
+
+#[test]
+fn set_denom_governance_only_activates_drop() {
+    let test_env = TestEnv::with_instantiate_msg(InstantiateMsg {
+        governance_only: Some(true),
+        ..default_instantiate_msg()
+    });
+
+    test_env.execute_msg_grant_mint();
+    test_env.execute_msg_grant_bank_send();
+
+    // the owner completes the one time setup.
+    let res = execute_set_subdenom(&test_env);
+    assert!(res.is_ok(), "{:?}", res.unwrap_err());
+
+    let claim_sender = test_env
+        .app
+        .init_account(&[Coin::new(1_000_000_000_000, "uosmo")])
+        .unwrap();
+
+    let wasm = Wasm::new(&test_env.app);
+    let res = wasm.execute(
+        &test_env.contract_address,
+        &ExecuteMsg::Claim {
+            proof: valid_proof(),
+            proof_version: None,
+            amount: Uint128::from(VALID_AMOUNT),
+            claimer_addr: String::from(TO_VERIFY_VALID2_ADDR_AMOUNT),
+            options: None,
+        },
+        &[],
+        &claim_sender,
+    );
+    assert!(res.is_ok(), "{:?}", res.unwrap_err());
+
+    assert_eq!(
+        Uint128::from(VALID_AMOUNT),
+        test_env.query_balance(TO_VERIFY_VALID2_ADDR_AMOUNT, &test_env.full_denom)
+    );
+
+    // later changes go through governance.
+    let err = execute_set_subdenom(&test_env).unwrap_err();
+    assert!(format!("{:?}", err).contains("governance"), "{:?}", err);
+}
+
 // TODO: add edge case tests:
 // - non-owner
 // - contract owner but there is no denom created
@@ -16,6 +137,43 @@ test_set_denom!(
 
 // ======= helpers ========
 
+fn spend_limited_env(spend_limit: u128) -> TestEnv {
+    let test_env = TestEnv::with_instantiate_msg(InstantiateMsg {
+        total_amount: Some(Uint128::from(VALID_AMOUNT)),
+        ..default_instantiate_msg()
+    });
+
+    test_env.execute_msg_grant_mint();
+    test_env.execute_msg_grant_bank_send_limited(spend_limit);
+
+    test_env
+}
+
+fn claim_end_env(claim_end_seconds: u64) -> TestEnv {
+    let test_env = TestEnv::with_instantiate_msg(InstantiateMsg {
+        claim_end: Some(Timestamp::from_seconds(claim_end_seconds)),
+        ..default_instantiate_msg()
+    });
+
+    test_env.execute_msg_grant_mint();
+    test_env.execute_msg_grant_bank_send();
+
+    test_env
+}
+
+fn execute_set_subdenom(test_env: &TestEnv) -> Result<(), RunnerError> {
+    let wasm = Wasm::new(&test_env.app);
+    wasm.execute(
+        &test_env.contract_address,
+        &ExecuteMsg::SetSubDenom {
+            subdenom: String::from(VALID_SUBDENOM),
+        },
+        &[],
+        &test_env.owner,
+    )
+    .map(|_| ())
+}
+
 #[macro_export]
 macro_rules! test_set_denom {
     ($test_name:ident should succeed) => {
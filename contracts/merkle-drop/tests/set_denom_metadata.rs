@@ -0,0 +1,136 @@
+mod test_env;
+use cosmwasm_std::Coin;
+use merkle_drop::msg::{DenomMetadataResponse, ExecuteMsg, QueryMsg};
+use merkle_drop::state::DenomMetadata;
+use osmosis_std::shim::Any;
+use osmosis_std::types::cosmos::authz::v1beta1::GenericAuthorization;
+use osmosis_std::types::osmosis::tokenfactory::v1beta1::MsgSetDenomMetadata;
+use osmosis_testing::cosmrs::tx::MessageExt;
+use osmosis_testing::{Module, RunnerError, SigningAccount, Wasm};
+use test_env::*;
+
+#[test]
+fn set_denom_metadata_saved() {
+    let test_env = metadata_env();
+    test_env.execute_msg_grant_set_metadata();
+
+    let res = execute_set_denom_metadata(&test_env, &test_env.owner);
+    assert!(res.is_ok(), "{:?}", res.unwrap_err());
+
+    assert_eq!(Some(metadata()), query_denom_metadata(&test_env));
+}
+
+#[test]
+fn set_denom_metadata_not_owner_fails() {
+    let test_env = metadata_env();
+    test_env.execute_msg_grant_set_metadata();
+
+    let other = test_env
+        .app
+        .init_account(&[Coin::new(1_000_000_000_000, "uosmo")])
+        .unwrap();
+
+    let err = execute_set_denom_metadata(&test_env, &other).unwrap_err();
+    assert!(
+        format!("{:?}", err).contains("Unauthorized sender"),
+        "{:?}",
+        err
+    );
+    assert_eq!(None, query_denom_metadata(&test_env));
+}
+
+#[test]
+fn set_denom_metadata_without_grant_fails() {
+    let test_env = metadata_env();
+
+    // the authz exec fails and is reported by the reply.
+    let err = execute_set_denom_metadata(&test_env, &test_env.owner).unwrap_err();
+    assert!(
+        format!("{:?}", err).contains(&format!(
+            "AuthZ grant for {:?} is not issued for the contract address",
+            MsgSetDenomMetadata::TYPE_URL
+        )),
+        "{:?}",
+        err
+    );
+    assert_eq!(None, query_denom_metadata(&test_env));
+}
+
+// ======= helpers ========
+
+trait MetadataGranter {
+    fn execute_msg_grant_set_metadata(&self);
+}
+
+impl MetadataGranter for TestEnv {
+    fn execute_msg_grant_set_metadata(&self) {
+        let generic_set_metadata_authorization = GenericAuthorization {
+            msg: String::from(MsgSetDenomMetadata::TYPE_URL),
+        };
+
+        self.execute_msg_grant(
+            Any {
+                type_url: String::from(GenericAuthorization::TYPE_URL),
+                value: generic_set_metadata_authorization.to_bytes().unwrap(),
+            },
+            now_seconds() as i64,
+        )
+    }
+}
+
+// metadata_env sets up a drop whose subdenom is set.
+fn metadata_env() -> TestEnv {
+    let test_env = TestEnv::new();
+
+    test_env.execute_msg_grant_mint();
+    test_env.execute_msg_grant_bank_send();
+
+    Wasm::new(&test_env.app)
+        .execute(
+            &test_env.contract_address,
+            &ExecuteMsg::SetSubDenom {
+                subdenom: String::from(VALID_SUBDENOM),
+            },
+            &[],
+            &test_env.owner,
+        )
+        .unwrap();
+
+    test_env
+}
+
+fn metadata() -> DenomMetadata {
+    DenomMetadata {
+        name: String::from("Drop Token"),
+        symbol: String::from("DROP"),
+        exponent: 6,
+        description: String::from("Airdropped to early users"),
+        uri: None,
+    }
+}
+
+fn execute_set_denom_metadata(
+    test_env: &TestEnv,
+    sender: &SigningAccount,
+) -> Result<(), RunnerError> {
+    Wasm::new(&test_env.app)
+        .execute(
+            &test_env.contract_address,
+            &ExecuteMsg::SetDenomMetadata {
+                metadata: metadata(),
+            },
+            &[],
+            sender,
+        )
+        .map(|_| ())
+}
+
+fn query_denom_metadata(test_env: &TestEnv) -> Option<DenomMetadata> {
+    Wasm::new(&test_env.app)
+        .query::<QueryMsg, DenomMetadataResponse>(
+            &test_env.contract_address,
+            &QueryMsg::DenomMetadata {},
+        )
+        .unwrap()
+        .metadata
+}
@@ -0,0 +1,190 @@
+mod test_env;
+use cosmwasm_std::{coins, Addr, Coin, Empty, Uint128};
+use cw_multi_test::{App, AppBuilder, AppResponse, Contract, ContractWrapper, Executor};
+use merkle_drop::msg::{ExecuteMsg, GovernanceSudoMsg, InstantiateMsg, QueryMsg, SudoMsg};
+use merkle_drop::state::{Config, Distribution};
+use merkle_drop::ContractError;
+use test_env::{default_instantiate_msg, valid_proof, TO_VERIFY_VALID2_ADDR_AMOUNT, VALID_AMOUNT};
+
+const OWNER: &str = "owner";
+const GOVERNANCE: &str = "gov_module";
+const TREASURY: &str = "community_pool";
+const CLAIM_SENDER: &str = "claim_sender";
+const DENOM: &str = "uosmo";
+const FUND_AMOUNT: u128 = 10_000_000;
+
+#[test]
+fn sudo_pause_rejects_claims_until_unpaused() {
+    let mut env = SudoEnv::new(false);
+
+    env.sudo(GovernanceSudoMsg::Pause {}).unwrap();
+
+    let err = env.claim().unwrap_err();
+    assert_eq!(
+        ContractError::Paused {}.to_string(),
+        err.root_cause().to_string()
+    );
+
+    env.sudo(GovernanceSudoMsg::Unpause {}).unwrap();
+
+    env.claim().unwrap();
+    assert_eq!(
+        Uint128::from(VALID_AMOUNT),
+        env.balance(TO_VERIFY_VALID2_ADDR_AMOUNT)
+    );
+}
+
+#[test]
+fn sudo_update_root() {
+    let mut env = SudoEnv::new(false);
+
+    env.sudo(GovernanceSudoMsg::UpdateRoot {
+        merkle_root: String::from("Nz54SQtyBVHwsmEqNI//mxFgiq8MRD7sS92IGkhgMvo="),
+    })
+    .unwrap();
+
+    // the proof no longer matches the root.
+    let err = env.claim().unwrap_err();
+    assert_eq!(
+        ContractError::FailedVerifyProof {}.to_string(),
+        err.root_cause().to_string()
+    );
+}
+
+#[test]
+fn sudo_clawback_during_claim_window() {
+    let mut env = SudoEnv::new(false);
+
+    env.sudo(GovernanceSudoMsg::Clawback {
+        recipient: String::from(TREASURY),
+    })
+    .unwrap();
+
+    assert_eq!(Uint128::from(FUND_AMOUNT), env.balance(TREASURY));
+    assert_eq!(Uint128::zero(), env.balance(env.drop.as_str()));
+    assert!(env.config().paused);
+
+    let err = env
+        .sudo(GovernanceSudoMsg::Clawback {
+            recipient: String::from(TREASURY),
+        })
+        .unwrap_err();
+    assert_eq!(
+        ContractError::NothingToWithdraw {}.to_string(),
+        err.root_cause().to_string()
+    );
+}
+
+#[test]
+fn governance_only_rejects_owner_messages() {
+    let mut env = SudoEnv::new(true);
+
+    let err = env
+        .app
+        .execute_contract(
+            Addr::unchecked(GOVERNANCE),
+            env.drop.clone(),
+            &ExecuteMsg::WithdrawRemaining { recipient: None },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::GovernanceOnly {}.to_string(),
+        err.root_cause().to_string()
+    );
+
+    // claims are not owner-level and keep working.
+    env.claim().unwrap();
+}
+
+// ======= helpers ========
+
+struct SudoEnv {
+    app: App,
+    drop: Addr,
+}
+
+impl SudoEnv {
+    // new instantiates an escrow drop owned by the governance module and funds
+    // it with a plain bank send, which needs no owner-level message.
+    fn new(governance_only: bool) -> Self {
+        let mut app = AppBuilder::new().build(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &Addr::unchecked(OWNER), coins(FUND_AMOUNT, DENOM))
+                .unwrap()
+        });
+
+        let code_id = app.store_code(merkle_drop_contract());
+        let drop = app
+            .instantiate_contract(
+                code_id,
+                Addr::unchecked(OWNER),
+                &InstantiateMsg {
+                    distribution: Some(Distribution::Escrow {
+                        denom: String::from(DENOM),
+                    }),
+                    owner: Some(String::from(GOVERNANCE)),
+                    governance_only: Some(governance_only),
+                    ..default_instantiate_msg()
+                },
+                &[],
+                "merkle-drop",
+                None,
+            )
+            .unwrap();
+
+        app.send_tokens(
+            Addr::unchecked(OWNER),
+            drop.clone(),
+            &coins(FUND_AMOUNT, DENOM),
+        )
+        .unwrap();
+
+        SudoEnv { app, drop }
+    }
+
+    fn sudo(&mut self, msg: GovernanceSudoMsg) -> anyhow::Result<AppResponse> {
+        self.app
+            .wasm_sudo(self.drop.clone(), &SudoMsg::Governance(msg))
+    }
+
+    fn claim(&mut self) -> anyhow::Result<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(CLAIM_SENDER),
+            self.drop.clone(),
+            &ExecuteMsg::Claim {
+                proof: valid_proof(),
+                proof_version: None,
+                amount: Uint128::from(VALID_AMOUNT),
+                claimer_addr: String::from(TO_VERIFY_VALID2_ADDR_AMOUNT),
+                options: None,
+            },
+            &[],
+        )
+    }
+
+    fn config(&self) -> Config {
+        self.app
+            .wrap()
+            .query_wasm_smart(&self.drop, &QueryMsg::GetConfig {})
+            .unwrap()
+    }
+
+    fn balance(&self, address: &str) -> Uint128 {
+        let Coin { amount, .. } = self.app.wrap().query_balance(address, DENOM).unwrap();
+        amount
+    }
+}
+
+fn merkle_drop_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new(
+            merkle_drop::contract::execute,
+            merkle_drop::contract::instantiate,
+            merkle_drop::contract::query,
+        )
+        .with_sudo(merkle_drop::contract::sudo)
+        .with_reply(merkle_drop::contract::reply),
+    )
+}
@@ -1,9 +1,15 @@
+#![allow(dead_code)]
+
 use std::path::PathBuf;
 
-use cosmwasm_std::Coin;
-use merkle_drop::msg::InstantiateMsg;
+use cosmwasm_std::{Binary, Coin, Uint128};
+use merkle::proof::Proof;
+use merkle_drop::msg::{ClaimProof, InstantiateMsg};
 use osmosis_std::types::osmosis::tokenfactory;
 use osmosis_std::types::osmosis::tokenfactory::v1beta1::{MsgCreateDenom, MsgCreateDenomResponse};
+use osmosis_testing::cosmrs::proto::cosmos::bank::v1beta1::{
+    QueryBalanceRequest, QueryBalanceResponse,
+};
 use osmosis_testing::{cosmrs::tx::MessageExt, Module, Wasm};
 use osmosis_testing::{Account, ExecuteResponse, OsmosisTestApp, Runner, SigningAccount};
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -12,12 +18,19 @@ use osmosis_std::{
     shim::{Any, Timestamp},
     types::{
         cosmos::authz::v1beta1::{GenericAuthorization, Grant, MsgGrant},
+        cosmos::bank::v1beta1::SendAuthorization,
+        cosmos::base::v1beta1,
         osmosis::tokenfactory::v1beta1::MsgMint,
     },
 };
 
 const TEST_ROOT: &str = "1V0YcwzXWtB+iuOTob6juiNliUmB278xZIKMnzwjqOU=";
 
+// proof of TO_VERIFY_VALID2_ADDR_AMOUNT with VALID_AMOUNT against TEST_ROOT
+pub const VALID_PROOF_STR: &str = "[{\"is_left_sibling\":true,\"hash\":[89,79,106,114,49,69,77,102,68,119,114,48,69,84,73,103,82,71,97,108,48,79,108,53,105,56,82,103,111,57,85,51,76,70,82,90,115,66,97,78,89,51,73,61]},{\"is_left_sibling\":false,\"hash\":[80,54,110,55,43,55,72,72,111,52,109,104,79,104,102,105,108,83,43,118,87,54,88,85,88,113,48,115,105,99,83,116,116,52,112,54,119,114,68,48,113,47,73,61]},{\"is_left_sibling\":true,\"hash\":[79,79,110,66,86,100,72,56,121,84,70,57,115,78,65,56,80,85,81,97,111,71,89,119,81,89,87,83,109,71,116,89,56,79,118,85,118,98,73,83,122,74,77,61]},{\"is_left_sibling\":false,\"hash\":[102,65,68,121,57,69,49,118,56,70,78,78,81,53,109,47,50,120,78,55,103,110,119,89,78,82,104,80,83,53,69,105,79,53,115,79,77,43,118,106,50,98,56,61]}]";
+pub const TO_VERIFY_VALID2_ADDR_AMOUNT: &str = "osmo1hqslwuc8ukaaaxfmahgnquyqx3w0tmrluwxmxj";
+pub const VALID_AMOUNT: u128 = 1421901;
+
 pub const VALID_SUBDENOM: &str = "subdenom";
 const BANK_SEND_TYPE_URL: &str = "/cosmos.bank.v1beta1.MsgSend";
 
@@ -33,7 +46,16 @@ pub struct TestEnv {
 
 impl TestEnv {
     pub fn new() -> Self {
-        let app = OsmosisTestApp::new();
+        Self::with_instantiate_msg(default_instantiate_msg())
+    }
+
+    pub fn with_instantiate_msg(instantiate_msg: InstantiateMsg) -> Self {
+        Self::with_app(OsmosisTestApp::new(), instantiate_msg)
+    }
+
+    // with_app instantiates the contract on an existing app, so that accounts
+    // created beforehand can be part of the merkle tree.
+    pub fn with_app(app: OsmosisTestApp, instantiate_msg: InstantiateMsg) -> Self {
         let wasm = Wasm::new(&app);
 
         // setup owner account
@@ -62,9 +84,7 @@ impl TestEnv {
         let contract_address = wasm
             .instantiate(
                 code_id,
-                &InstantiateMsg {
-                    merkle_root: String::from(TEST_ROOT),
-                },
+                &instantiate_msg,
                 Some(&owner.address()),
                 None,
                 &[],
@@ -83,6 +103,68 @@ impl TestEnv {
             full_denom,
         }
     }
+
+    pub fn query_balance(&self, address: &str, denom: &str) -> Uint128 {
+        let balance = self
+            .app
+            .query::<QueryBalanceRequest, QueryBalanceResponse>(
+                "/cosmos.bank.v1beta1.Query/Balance",
+                &QueryBalanceRequest {
+                    address: String::from(address),
+                    denom: String::from(denom),
+                },
+            )
+            .unwrap()
+            .balance
+            .unwrap();
+
+        Uint128::from(balance.amount.parse::<u128>().unwrap())
+    }
+}
+
+// valid_proof is VALID_PROOF_STR as sent with a claim.
+pub fn valid_proof() -> ClaimProof {
+    ClaimProof::Legacy(String::from(VALID_PROOF_STR))
+}
+
+// compact_proof encodes the proof in the compact format.
+pub fn compact_proof(proof: &Proof) -> ClaimProof {
+    let compact = proof.to_compact().unwrap();
+
+    ClaimProof::Compact {
+        hashes: compact
+            .hashes
+            .iter()
+            .map(|hash| Binary::from(hash.as_ref()))
+            .collect(),
+        path: compact.path,
+    }
+}
+
+pub fn default_instantiate_msg() -> InstantiateMsg {
+    InstantiateMsg {
+        merkle_root: String::from(TEST_ROOT),
+        hash_function: None,
+        distribution: None,
+        claim_end: None,
+        total_amount: None,
+        owner: None,
+        governance_only: None,
+        lock_options: None,
+        join_pool_id: None,
+        remote_claim_sources: None,
+        decay: None,
+        rate_limits: None,
+        claim_fee: None,
+        referral: None,
+    }
+}
+
+pub fn now_seconds() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
 }
 
 pub trait Granter {
@@ -90,6 +172,8 @@ pub trait Granter {
 
     fn execute_msg_grant_bank_send(&self);
 
+    fn execute_msg_grant_bank_send_limited(&self, spend_limit: u128);
+
     fn execute_msg_grant(&self, authorization: Any, duration_since_unix_secs: i64);
 }
 
@@ -114,10 +198,6 @@ impl Granter for TestEnv {
     }
 
     fn execute_msg_grant_bank_send(&self) {
-        // TODO: figure out serialization errors with the send authorization
-        // let spend_authorization = SendAuthorization {
-        //     spend_limit: vec![cosmos::base::v1beta1::Coin{ denom: self.full_denom.clone(), amount: String::from(MAX_SPEND_AUTHORIZATION_AMOUNT) }],
-        // };
         let generic_send_authorization = GenericAuthorization {
             msg: String::from(BANK_SEND_TYPE_URL),
         };
@@ -136,6 +216,28 @@ impl Granter for TestEnv {
         )
     }
 
+    fn execute_msg_grant_bank_send_limited(&self, spend_limit: u128) {
+        let send_authorization = SendAuthorization {
+            spend_limit: vec![v1beta1::Coin {
+                denom: self.full_denom.clone(),
+                amount: spend_limit.to_string(),
+            }],
+        };
+
+        let duration_since_unix_secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+
+        self.execute_msg_grant(
+            Any {
+                type_url: String::from(SendAuthorization::TYPE_URL),
+                value: send_authorization.to_bytes().unwrap(),
+            },
+            duration_since_unix_secs,
+        )
+    }
+
     fn execute_msg_grant(&self, authorization: Any, duration_since_unix_secs: i64) {
         // issue authz message
         let authz_grant_msg = MsgGrant {
@@ -1,48 +1,260 @@
-use merkle::hash;
-use merkle::Tree;
+use merkle::cw20_airdrop::{Cw20AirdropProof, Cw20AirdropTree};
+use merkle::hash::MerkleHasher;
+use merkle::sorted_pair::{claim_leaf, SortedPairProof, SortedPairTree};
+use merkle::{Tree, TreeError, TreeVersion};
 use std::error::Error;
+use std::marker::PhantomData;
 
-pub fn generate_root(data: &[Vec<u8>]) -> String {
-    let tree = Tree::new(data);
-    let hash = tree.get_root().unwrap();
+// TreeFormat builds roots and proofs in one of the tree formats the contract
+// can verify.
+pub trait TreeFormat {
+    // encode_entry returns the data an address and amount entry is hashed
+    // into a leaf from, address and amount concatenated by default.
+    fn encode_entry(&self, address: &str, amount: &str) -> Result<Vec<u8>, Box<dyn Error>> {
+        Ok(format!("{}{}", address, amount).into_bytes())
+    }
+
+    // encode_data returns the leaf data of an entry given on the command
+    // line, address and amount concatenated by default.
+    fn encode_data(&self, data: &str) -> Result<Vec<u8>, Box<dyn Error>> {
+        Ok(data.as_bytes().to_vec())
+    }
+
+    fn generate_root(&self, data: &[Vec<u8>]) -> Result<String, Box<dyn Error>>;
 
-    base64::encode(hash)
+    fn get_proof(
+        &self,
+        data: &[Vec<u8>],
+        proof_for: &[u8],
+        compact: bool,
+    ) -> Result<String, Box<dyn Error>>;
+
+    fn verify_proof(
+        &self,
+        root: &str,
+        proof_bytes: &str,
+        to_verify: &[u8],
+    ) -> Result<bool, Box<dyn Error>>;
+
+    fn hash(&self, data: &[u8]) -> String;
 }
 
-pub fn get_proof(data: &[Vec<u8>], proof_for: &Vec<u8>) -> Result<String, Box<dyn Error>> {
-    let tree = Tree::new(data);
+// HashedTree is the format of merkle::Tree built with hasher H.
+pub struct HashedTree<H> {
+    version: TreeVersion,
+    hasher: PhantomData<H>,
+}
 
-    let proof_opt = tree.find_proof(proof_for);
+impl<H: MerkleHasher> HashedTree<H> {
+    pub fn new(version: TreeVersion) -> Self {
+        HashedTree {
+            version,
+            hasher: PhantomData,
+        }
+    }
+}
+
+impl<H: MerkleHasher> TreeFormat for HashedTree<H> {
+    fn generate_root(&self, data: &[Vec<u8>]) -> Result<String, Box<dyn Error>> {
+        let tree = Tree::<H>::with_version(data, self.version)?;
+        let hash = tree.get_root().unwrap();
 
-    if proof_opt.is_none() {
-        return Err(format!(
-            "failed to find proof for {:?}, the data hash is {:?}",
-            proof_for,
-            hash::leaf(proof_for)
-        )
-        .into());
+        Ok(base64::encode(hash))
     }
 
-    let proof = proof_opt.unwrap();
+    fn get_proof(
+        &self,
+        data: &[Vec<u8>],
+        proof_for: &[u8],
+        compact: bool,
+    ) -> Result<String, Box<dyn Error>> {
+        let tree = Tree::<H>::with_version(data, self.version)?;
+
+        let proof_opt = tree.find_proof(&proof_for);
+
+        if proof_opt.is_none() {
+            return Err(format!(
+                "failed to find proof for {:?}, the data hash is {:?}",
+                proof_for,
+                H::leaf(proof_for)
+            )
+            .into());
+        }
 
-    let serialized = serde_json_wasm::to_string(&proof)?;
+        let proof = proof_opt.unwrap();
 
-    Ok(serialized)
+        if compact {
+            return compact_proof(&proof);
+        }
+
+        let serialized = serde_json_wasm::to_string(&proof)?;
+
+        Ok(serialized)
+    }
+
+    fn verify_proof(
+        &self,
+        root: &str,
+        proof_bytes: &str,
+        to_verify: &[u8],
+    ) -> Result<bool, Box<dyn Error>> {
+        let proof: merkle::proof::Proof = serde_json_wasm::from_str(proof_bytes)?;
+        let root_decoded = base64::decode(root)?;
+
+        Ok(proof.verify::<H, _>(&to_verify, &merkle::hash::Hash::from(root_decoded)))
+    }
+
+    fn hash(&self, data: &[u8]) -> String {
+        H::leaf(data).to_string()
+    }
+}
+
+// compact_proof serializes the proof as the compact format of the contract's
+// ClaimProof, ready to be sent with a claim.
+fn compact_proof(proof: &merkle::proof::Proof) -> Result<String, Box<dyn Error>> {
+    let compact = proof
+        .to_compact()
+        .ok_or("the proof is too deep for the compact format")?;
+
+    Ok(format!(
+        "{{\"hashes\":[{}],\"path\":{}}}",
+        base64_list(&compact.hashes),
+        compact.path
+    ))
 }
 
-pub fn verify_proof(
-    root: &String,
-    proof_bytes: &str,
-    to_verify: String,
-) -> Result<bool, Box<dyn Error>> {
-    let proof: merkle::proof::Proof = serde_json_wasm::from_str(proof_bytes)?;
-    let root_decoded = base64::decode(root)?;
+// SortedPair is the format of OpenZeppelin's StandardMerkleTree with a
+// ["string", "uint256"] leaf encoding of address and amount. Roots and proofs
+// are written 0x-prefixed hex encoded, like StandardMerkleTree's.
+pub struct SortedPair;
+
+impl TreeFormat for SortedPair {
+    fn encode_entry(&self, address: &str, amount: &str) -> Result<Vec<u8>, Box<dyn Error>> {
+        let amount: u128 = amount.parse().map_err(|_| {
+            format!(
+                "amount {} of {} must be an integer to be encoded as a uint256",
+                amount, address
+            )
+        })?;
+
+        Ok(claim_leaf(address, amount))
+    }
+
+    // the address and amount cannot be told apart once concatenated, and
+    // are given separated by a comma instead.
+    fn encode_data(&self, data: &str) -> Result<Vec<u8>, Box<dyn Error>> {
+        let (address, amount) = data
+            .split_once(',')
+            .ok_or("expected the address and amount separated by a comma")?;
+
+        self.encode_entry(address, amount)
+    }
 
-    proof.verify(&to_verify, &merkle::hash::Hash::from(root_decoded));
+    fn generate_root(&self, data: &[Vec<u8>]) -> Result<String, Box<dyn Error>> {
+        let tree = SortedPairTree::new(data)?;
 
-    Ok(true)
+        Ok(tree.get_root_hex().ok_or(TreeError::Empty)?)
+    }
+
+    fn get_proof(
+        &self,
+        data: &[Vec<u8>],
+        proof_for: &[u8],
+        compact: bool,
+    ) -> Result<String, Box<dyn Error>> {
+        let tree = SortedPairTree::new(data)?;
+
+        let proof = tree
+            .find_proof(&proof_for)
+            .ok_or_else(|| format!("failed to find proof for {:?}", proof_for))?;
+
+        if compact {
+            // the hashes variant of the contract's ClaimProof.
+            return Ok(format!("{{\"hashes\":[{}]}}", base64_list(proof.hashes())));
+        }
+
+        let serialized = serde_json_wasm::to_string(&proof)?;
+
+        Ok(serialized)
+    }
+
+    fn verify_proof(
+        &self,
+        root: &str,
+        proof_bytes: &str,
+        to_verify: &[u8],
+    ) -> Result<bool, Box<dyn Error>> {
+        let proof: SortedPairProof = serde_json_wasm::from_str(proof_bytes)?;
+        let root_decoded = hex::decode(root.trim_start_matches("0x"))?;
+
+        Ok(proof.verify(
+            &to_verify,
+            &merkle::hash::Hash::try_from(root_decoded.as_slice())?,
+        ))
+    }
+
+    fn hash(&self, data: &[u8]) -> String {
+        merkle::hash::SortedKeccak256Hasher::leaf(data).to_string()
+    }
+}
+
+// Cw20Airdrop is the cw20-merkle-airdrop compatible format. Roots and proofs
+// are hex encoded, so that existing snapshots, roots and proofs can be reused.
+pub struct Cw20Airdrop;
+
+impl TreeFormat for Cw20Airdrop {
+    fn generate_root(&self, data: &[Vec<u8>]) -> Result<String, Box<dyn Error>> {
+        let tree = Cw20AirdropTree::new(data)?;
+
+        Ok(tree.get_root_hex().ok_or(TreeError::Empty)?)
+    }
+
+    fn get_proof(
+        &self,
+        data: &[Vec<u8>],
+        proof_for: &[u8],
+        compact: bool,
+    ) -> Result<String, Box<dyn Error>> {
+        let tree = Cw20AirdropTree::new(data)?;
+
+        let proof = tree
+            .find_proof(&proof_for)
+            .ok_or_else(|| format!("failed to find proof for {:?}", proof_for))?;
+
+        if compact {
+            // the hashes variant of the contract's ClaimProof.
+            return Ok(format!("{{\"hashes\":[{}]}}", base64_list(proof.hashes())));
+        }
+
+        let serialized = serde_json_wasm::to_string(&proof)?;
+
+        Ok(serialized)
+    }
+
+    fn verify_proof(
+        &self,
+        root: &str,
+        proof_bytes: &str,
+        to_verify: &[u8],
+    ) -> Result<bool, Box<dyn Error>> {
+        let proof: Cw20AirdropProof = serde_json_wasm::from_str(proof_bytes)?;
+        let root_decoded = hex::decode(root)?;
+
+        Ok(proof.verify(
+            &to_verify,
+            &merkle::hash::Hash::try_from(root_decoded.as_slice())?,
+        ))
+    }
+
+    fn hash(&self, data: &[u8]) -> String {
+        merkle::hash::SortedSha256Hasher::leaf(data).to_string()
+    }
 }
 
-pub fn hash(data: &String) -> String {
-    return merkle::hash::leaf(data.as_bytes()).to_string();
+fn base64_list(hashes: &[merkle::hash::Hash]) -> String {
+    hashes
+        .iter()
+        .map(|hash| format!("\"{}\"", base64::encode(hash)))
+        .collect::<Vec<String>>()
+        .join(",")
 }
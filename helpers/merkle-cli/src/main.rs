@@ -1,4 +1,8 @@
-use clap::{Parser, Subcommand};
+use clap::{ArgEnum, Parser, Subcommand};
+use controller::{Cw20Airdrop, HashedTree, SortedPair, TreeFormat};
+use merkle::hash::{Keccak256Hasher, Sha256Hasher, Sha3Hasher};
+use serde::Deserialize;
+use std::collections::BTreeSet;
 use std::error::Error;
 use std::fs;
 use std::process;
@@ -9,6 +13,51 @@ mod controller;
 struct Cli {
     #[clap(subcommand)]
     command: Option<Commands>,
+
+    /// hash_function the Merkle tree is built with. Must match the
+    /// hash_function the contract is instantiated with.
+    #[clap(long, arg_enum, global = true, default_value = "sha3-256")]
+    hash_function: HashFunction,
+
+    /// tree_version how the last node of an odd level is handled: paired with
+    /// itself in v1, promoted unchanged in v2. v2 is recommended for new
+    /// trees, as distinct leaf sets cannot share a v2 root. Ignored by the
+    /// sorted-keccak256 and cw20-merkle-airdrop hash functions.
+    #[clap(long, arg_enum, global = true, default_value = "v1")]
+    tree_version: TreeVersion,
+
+    /// duplicates what to do with entries given more than once: reject them,
+    /// dedupe identical entries, or merge the entries of an address by
+    /// summing their amounts. Amounts to merge must be of the same denom.
+    #[clap(long, arg_enum, global = true, default_value = "reject")]
+    duplicates: Duplicates,
+}
+
+#[derive(ArgEnum, Clone, Copy)]
+enum Duplicates {
+    Reject,
+    Dedupe,
+    Merge,
+}
+
+#[derive(ArgEnum, Clone)]
+enum TreeVersion {
+    V1,
+    V2,
+}
+
+#[derive(ArgEnum, Clone)]
+enum HashFunction {
+    Sha3_256,
+    Sha256,
+    Keccak256,
+    /// OpenZeppelin StandardMerkleTree compatible, with a
+    /// ["string", "uint256"] leaf encoding. Roots and proofs are hex encoded,
+    /// and data given on the command line is the address and amount
+    /// separated by a comma.
+    SortedKeccak256,
+    /// cw20-merkle-airdrop compatible. Roots and proofs are hex encoded.
+    Cw20MerkleAirdrop,
 }
 
 #[derive(Subcommand)]
@@ -37,7 +86,8 @@ enum Commands {
         #[clap(parse(from_os_str))]
         data_set_path: std::path::PathBuf,
 
-        /// proof_for the data to generate proof for.
+        /// proof_for the data to generate proof for, address and amount
+        /// concatenated.
         #[clap()]
         proof_for: String,
 
@@ -49,6 +99,12 @@ enum Commands {
         /// If this flag is true
         #[clap(short, long)]
         print: bool,
+
+        /// compact flag indicating whether to output the proof in the
+        /// compact format, as raw hashes and a path bitmask. Sorted-pair
+        /// proofs have no path and are output as raw hashes only.
+        #[clap(long)]
+        compact: bool,
     },
 
     /// VerifyProof verifies the given proof against the given root.
@@ -60,7 +116,7 @@ enum Commands {
         #[clap()]
         root: String,
 
-        /// to_verify data to verify.
+        /// to_verify data to verify, address and amount concatenated.
         to_verify: String,
 
         /// proof_path is the path to the file containing proof
@@ -79,22 +135,29 @@ enum Commands {
     },
 }
 
-fn generate_root_cmd(path: std::path::PathBuf) -> Result<(), Box<dyn Error>> {
-    let entries = parse_csv(path)?;
-    let hash = controller::generate_root(&entries);
+fn generate_root_cmd<F: TreeFormat>(
+    format: &F,
+    path: std::path::PathBuf,
+    duplicates: Duplicates,
+) -> Result<(), Box<dyn Error>> {
+    let entries = load_leaves(format, path, duplicates)?;
+    let hash = format.generate_root(&entries)?;
     println!("{}", hash);
     Ok(())
 }
 
-fn generate_proof_cmd(
+fn generate_proof_cmd<F: TreeFormat>(
+    format: &F,
     path: std::path::PathBuf,
-    proof_for: &String,
+    proof_for: &str,
     proof_out_path: &Option<std::path::PathBuf>,
     print: bool,
+    compact: bool,
+    duplicates: Duplicates,
 ) -> Result<(), Box<dyn Error>> {
-    let entries = parse_csv(path)?;
+    let entries = load_leaves(format, path, duplicates)?;
 
-    let proof = controller::get_proof(&entries, &proof_for.as_bytes().to_vec())?;
+    let proof = format.get_proof(&entries, &format.encode_data(proof_for)?, compact)?;
 
     if print {
         println!("{}", proof);
@@ -107,14 +170,15 @@ fn generate_proof_cmd(
     Ok(())
 }
 
-fn verify_proof_cmd(
-    root: &String,
+fn verify_proof_cmd<F: TreeFormat>(
+    format: &F,
+    root: &str,
     proof_path: std::path::PathBuf,
-    to_verify: String,
+    to_verify: &str,
 ) -> Result<(), Box<dyn Error>> {
     let data = fs::read_to_string(&proof_path)?;
 
-    let is_valid = controller::verify_proof(root, &data, to_verify)?;
+    let is_valid = format.verify_proof(root, &data, &format.encode_data(to_verify)?)?;
 
     if !is_valid {
         return Err("The proof is invalid. End result did not match the root hash".into());
@@ -127,25 +191,117 @@ fn verify_proof_cmd(
     Ok(())
 }
 
-fn hash_cmd(data: &String) {
-    let hash = controller::hash(data);
+fn hash_cmd<F: TreeFormat>(format: &F, data: &str) -> Result<(), Box<dyn Error>> {
+    let bytes = format.encode_data(data)?;
+    let hash = format.hash(&bytes);
     println!("Data: {}", data);
-    println!("Data Bytes: {:?}", data.as_bytes());
+    println!("Data Bytes: {:?}", bytes);
     println!("Data Hash: {}", hash);
+    Ok(())
+}
+
+// load_leaves reads the address and amount entries at path and returns the
+// leaves to build a tree from, once the duplicates policy is applied and the
+// entries are encoded by the format.
+fn load_leaves<F: TreeFormat>(
+    format: &F,
+    path: std::path::PathBuf,
+    duplicates: Duplicates,
+) -> Result<Vec<Vec<u8>>, Box<dyn Error>> {
+    let entries = apply_duplicates(parse_entries(path)?, duplicates)?;
+
+    entries
+        .iter()
+        .map(|(address, amount)| format.encode_entry(address, amount))
+        .collect()
+}
+
+// apply_duplicates applies the duplicates policy to the entries. Entries that
+// collided are reported to stderr, by their index in the file.
+fn apply_duplicates(
+    entries: Vec<(String, String)>,
+    duplicates: Duplicates,
+) -> Result<Vec<(String, String)>, Box<dyn Error>> {
+    if let Duplicates::Merge = duplicates {
+        let (merged, collisions) = merkle::duplicates::merge_amounts(&entries)?;
+        for collision in &collisions {
+            eprintln!("merged the amounts of {}", collision);
+        }
+        return Ok(merged);
+    }
+
+    let collisions = merkle::duplicates::find_duplicates(&leaves(&entries));
+    if collisions.is_empty() {
+        return Ok(entries);
+    }
+
+    if let Duplicates::Reject = duplicates {
+        return Err(merkle::TreeError::DuplicateLeaf { collisions }.into());
+    }
+
+    for collision in &collisions {
+        eprintln!("dropped the duplicates of {}", collision);
+    }
+    let mut seen = BTreeSet::new();
+    Ok(entries
+        .into_iter()
+        .filter(|(address, amount)| seen.insert(format!("{}{}", address, amount)))
+        .collect())
+}
+
+// leaves are address and amount concatenated.
+fn leaves(entries: &[(String, String)]) -> Vec<Vec<u8>> {
+    entries
+        .iter()
+        .map(|(address, amount)| format!("{}{}", address, amount).into_bytes())
+        .collect()
+}
+
+// parse_entries reads the address and amount entries to build a tree from,
+// either from a csv file or from a cw20-merkle-airdrop json snapshot.
+fn parse_entries(path: std::path::PathBuf) -> Result<Vec<(String, String)>, Box<dyn Error>> {
+    if matches!(path.extension(), Some(ext) if ext == "json") {
+        return parse_cw20_airdrop_json(path);
+    }
+    parse_csv(path)
 }
 
-fn parse_csv(path: std::path::PathBuf) -> Result<Vec<Vec<u8>>, Box<dyn Error>> {
+// Cw20AirdropEntry is an entry of a cw20-merkle-airdrop snapshot.
+#[derive(Deserialize)]
+struct Cw20AirdropEntry {
+    address: String,
+    amount: String,
+}
+
+// parse_cw20_airdrop_json reads a snapshot in the json format of
+// cw20-merkle-airdrop, a list of address and amount objects.
+fn parse_cw20_airdrop_json(
+    path: std::path::PathBuf,
+) -> Result<Vec<(String, String)>, Box<dyn Error>> {
+    let data = fs::read_to_string(path)?;
+    let snapshot: Vec<Cw20AirdropEntry> = serde_json_wasm::from_str(&data)?;
+
+    Ok(snapshot
+        .into_iter()
+        .map(|entry| (entry.address, entry.amount))
+        .collect())
+}
+
+fn parse_csv(path: std::path::PathBuf) -> Result<Vec<(String, String)>, Box<dyn Error>> {
     // Build the CSV reader and iterate over each record.
     let mut csv_reader = csv::Reader::from_path(path)?;
 
-    let mut entries: Vec<Vec<u8>> = Vec::<Vec<u8>>::new();
+    let mut entries: Vec<(String, String)> = Vec::new();
 
     for str_record in csv_reader.records() {
         // The iterator yields Result<StringRecord, Error>, so we check the
         // error here.
         let entry = str_record?;
 
-        entries.push(Vec::<u8>::from(entry.as_slice()));
+        // the address is followed by the amount.
+        let address = entry.get(0).unwrap_or_default();
+        let amount: String = entry.iter().skip(1).collect();
+        entries.push((String::from(address), amount));
     }
     Ok(entries)
 }
@@ -153,9 +309,36 @@ fn parse_csv(path: std::path::PathBuf) -> Result<Vec<Vec<u8>>, Box<dyn Error>> {
 fn main() {
     let cli = Cli::parse();
 
-    match &cli.command {
+    let version = match cli.tree_version {
+        TreeVersion::V1 => merkle::TreeVersion::V1,
+        TreeVersion::V2 => merkle::TreeVersion::V2,
+    };
+
+    match cli.hash_function {
+        HashFunction::Sha3_256 => run(
+            &HashedTree::<Sha3Hasher>::new(version),
+            &cli.command,
+            cli.duplicates,
+        ),
+        HashFunction::Sha256 => run(
+            &HashedTree::<Sha256Hasher>::new(version),
+            &cli.command,
+            cli.duplicates,
+        ),
+        HashFunction::Keccak256 => run(
+            &HashedTree::<Keccak256Hasher>::new(version),
+            &cli.command,
+            cli.duplicates,
+        ),
+        HashFunction::SortedKeccak256 => run(&SortedPair, &cli.command, cli.duplicates),
+        HashFunction::Cw20MerkleAirdrop => run(&Cw20Airdrop, &cli.command, cli.duplicates),
+    }
+}
+
+fn run<F: TreeFormat>(format: &F, command: &Option<Commands>, duplicates: Duplicates) {
+    match command {
         Some(Commands::GenerateRoot { path }) => {
-            if let Err(err) = generate_root_cmd(path.to_path_buf()) {
+            if let Err(err) = generate_root_cmd(format, path.to_path_buf(), duplicates) {
                 eprintln!("error generating merkle root: {}", err);
                 process::exit(1);
             }
@@ -165,13 +348,22 @@ fn main() {
             proof_for: data,
             proof_out_path,
             print,
+            compact,
         }) => {
             if proof_out_path.is_none() && !print {
                 eprintln!("please provide a proof_out_path argument or set --print flag to true");
                 process::exit(1);
             }
 
-            if let Err(err) = generate_proof_cmd(path.to_path_buf(), data, proof_out_path, *print) {
+            if let Err(err) = generate_proof_cmd(
+                format,
+                path.to_path_buf(),
+                data,
+                proof_out_path,
+                *print,
+                *compact,
+                duplicates,
+            ) {
                 eprintln!("error generating merkle proof: {}", err);
                 process::exit(1);
             }
@@ -190,9 +382,7 @@ fn main() {
                 process::exit(1);
             }
 
-            if let Err(err) =
-                verify_proof_cmd(root, proof_path.to_path_buf(), to_verify.to_string())
-            {
+            if let Err(err) = verify_proof_cmd(format, root, proof_path.to_path_buf(), to_verify) {
                 eprintln!("error verifyin merkle proof: {}", err);
                 process::exit(1);
             }
@@ -203,8 +393,299 @@ fn main() {
                 process::exit(1);
             }
 
-            hash_cmd(data)
+            if let Err(err) = hash_cmd(format, data) {
+                eprintln!("error hashing data: {}", err);
+                process::exit(1);
+            }
         }
         None => {}
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use merkle::hash::{Hash, MerkleHasher};
+
+    const ADDRESS_AMOUNT: &str = "osmo1hqslwuc8ukaaaxfmahgnquyqx3w0tmrluwxmxj1421901";
+
+    fn testdata(name: &str) -> std::path::PathBuf {
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../../testdata")
+            .join(name)
+    }
+
+    // Compact is a proof generated with --compact. Sorted-pair proofs have no
+    // path.
+    #[derive(Deserialize)]
+    struct Compact {
+        hashes: Vec<String>,
+        path: Option<u64>,
+    }
+
+    impl Compact {
+        fn hashes(&self) -> Vec<Hash> {
+            self.hashes
+                .iter()
+                .map(|hash| Hash::from(base64::decode(hash).unwrap()))
+                .collect()
+        }
+    }
+
+    // round_trip generates the root of the entries at path and a proof of
+    // data, checks that the proof verifies data and nothing else against the
+    // root, and returns the root, the proof and the proof generated with
+    // --compact.
+    fn round_trip<F: TreeFormat>(
+        format: &F,
+        path: std::path::PathBuf,
+        data: &str,
+        other: &str,
+        duplicates: Duplicates,
+    ) -> (String, String, Compact) {
+        let leaves = load_leaves(format, path, duplicates).unwrap();
+        let root = format.generate_root(&leaves).unwrap();
+
+        let to_verify = format.encode_data(data).unwrap();
+        let proof = format.get_proof(&leaves, &to_verify, false).unwrap();
+        assert!(format.verify_proof(&root, &proof, &to_verify).unwrap());
+        assert!(!format
+            .verify_proof(&root, &proof, &format.encode_data(other).unwrap())
+            .unwrap());
+
+        let compact = format.get_proof(&leaves, &to_verify, true).unwrap();
+        (root, proof, serde_json_wasm::from_str(&compact).unwrap())
+    }
+
+    // hashed_round_trip round trips a proof of ADDRESS_AMOUNT in the
+    // address_amount.csv tree built with hasher H, and returns its root.
+    fn hashed_round_trip<H: MerkleHasher>(version: merkle::TreeVersion) -> String {
+        let (root, proof, compact) = round_trip(
+            &HashedTree::<H>::new(version),
+            testdata("address_amount.csv"),
+            ADDRESS_AMOUNT,
+            "osmo10004ufcv2aln3vl8defyk9agv5kacrzpkyw5p410",
+            Duplicates::Reject,
+        );
+
+        // the compact proof is the same proof.
+        let expanded =
+            merkle::proof::Proof::from_compact(&compact.hashes(), compact.path.unwrap()).unwrap();
+        assert_eq!(proof, serde_json_wasm::to_string(&expanded).unwrap());
+
+        root
+    }
+
+    #[test]
+    fn hash_functions_round_trip() {
+        let sha3 = hashed_round_trip::<Sha3Hasher>(merkle::TreeVersion::V1);
+        let sha256 = hashed_round_trip::<Sha256Hasher>(merkle::TreeVersion::V1);
+        let keccak256 = hashed_round_trip::<Keccak256Hasher>(merkle::TreeVersion::V1);
+
+        // the roots documented in the READMEs.
+        assert_eq!("1V0YcwzXWtB+iuOTob6juiNliUmB278xZIKMnzwjqOU=", sha3);
+        assert_eq!("2m/Qv3hxycYMzmFgrYWInV2rkcPavLdBsmoW9YsP8aI=", sha256);
+        assert_ne!(sha3, keccak256);
+        assert_ne!(sha256, keccak256);
+    }
+
+    #[test]
+    fn v2_round_trip() {
+        // address_amount.csv has odd levels, which v2 does not self-pair.
+        assert_ne!(
+            hashed_round_trip::<Sha3Hasher>(merkle::TreeVersion::V1),
+            hashed_round_trip::<Sha3Hasher>(merkle::TreeVersion::V2)
+        );
+        assert_ne!(
+            hashed_round_trip::<Sha256Hasher>(merkle::TreeVersion::V1),
+            hashed_round_trip::<Sha256Hasher>(merkle::TreeVersion::V2)
+        );
+        assert_ne!(
+            hashed_round_trip::<Keccak256Hasher>(merkle::TreeVersion::V1),
+            hashed_round_trip::<Keccak256Hasher>(merkle::TreeVersion::V2)
+        );
+    }
+
+    #[test]
+    fn sorted_pair_round_trip() {
+        let (root, proof, compact) = round_trip(
+            &SortedPair,
+            testdata("address_amount.csv"),
+            "osmo10004ufcv2aln3vl8defyk9agv5kacrzpkyw5p4,10",
+            "osmo10004ufcv2aln3vl8defyk9agv5kacrzpkyw5p4,11",
+            Duplicates::Reject,
+        );
+
+        // the root and proof documented in the README.
+        assert_eq!(
+            "0x38953a2538646c42c99c80fc14b9bd7ba7d078ce2676277b6731379d94e5fe2c",
+            root
+        );
+        assert_eq!(
+            "[\"0xbc41004c2d8b48295491e57868b1367ed72a1f4b8d62b65af55f0f59210596ff\",\"0x7f71dc1a76e131d0072b167f07ace9b9b7eeb98ac9613fd62e4eabd66f7b4abf\",\"0x3f3eb6b29be9ef01a1226a30639f978ba246efc284e345a7cd294adb43d87426\"]",
+            proof
+        );
+
+        assert_eq!(None, compact.path);
+        let expanded = merkle::sorted_pair::SortedPairProof::from(compact.hashes());
+        assert_eq!(proof, serde_json_wasm::to_string(&expanded).unwrap());
+    }
+
+    #[test]
+    fn sorted_pair_amounts_must_be_integers() {
+        let err =
+            load_leaves(&SortedPair, testdata("uosmo_only.csv"), Duplicates::Reject).unwrap_err();
+        assert!(err.to_string().contains("must be an integer"), "{}", err);
+
+        SortedPair
+            .encode_data("osmo10004ufcv2aln3vl8defyk9agv5kacrzpkyw5p410")
+            .unwrap_err();
+    }
+
+    #[test]
+    fn cw20_airdrop_round_trip() {
+        let data = "osmo10004ufcv2aln3vl8defyk9agv5kacrzpkyw5p410";
+        let (root, proof, compact) = round_trip(
+            &Cw20Airdrop,
+            testdata("address_amount.csv"),
+            data,
+            ADDRESS_AMOUNT,
+            Duplicates::Reject,
+        );
+
+        // the root documented in the README.
+        assert_eq!(
+            "d41d95e47e76885854ec57349e22ac950139b60825b0586be527b4dcb727938e",
+            root
+        );
+
+        assert_eq!(None, compact.path);
+        let expanded = merkle::cw20_airdrop::Cw20AirdropProof::from(compact.hashes());
+        assert_eq!(proof, serde_json_wasm::to_string(&expanded).unwrap());
+
+        // a json snapshot of the same entries builds the same tree.
+        let snapshot = std::env::temp_dir().join("merkle-cli-cw20-airdrop-round-trip.json");
+        let entries: Vec<String> = parse_entries(testdata("address_amount.csv"))
+            .unwrap()
+            .iter()
+            .map(|(address, amount)| {
+                format!("{{\"address\":\"{}\",\"amount\":\"{}\"}}", address, amount)
+            })
+            .collect();
+        fs::write(&snapshot, format!("[{}]", entries.join(","))).unwrap();
+
+        let (snapshot_root, snapshot_proof, _) = round_trip(
+            &Cw20Airdrop,
+            snapshot.clone(),
+            data,
+            ADDRESS_AMOUNT,
+            Duplicates::Reject,
+        );
+        assert_eq!(root, snapshot_root);
+        assert_eq!(proof, snapshot_proof);
+
+        fs::remove_file(snapshot).unwrap();
+    }
+
+    #[test]
+    fn duplicates_round_trip() {
+        let format = HashedTree::<Sha3Hasher>::new(merkle::TreeVersion::V1);
+        let path = std::env::temp_dir().join("merkle-cli-duplicates-round-trip.csv");
+        let mut csv = fs::read_to_string(testdata("address_amount.csv")).unwrap();
+        csv.push_str("osmo1hqslwuc8ukaaaxfmahgnquyqx3w0tmrluwxmxj,1421901\n");
+        fs::write(&path, &csv).unwrap();
+
+        let err = load_leaves(&format, path.clone(), Duplicates::Reject).unwrap_err();
+        assert!(err.to_string().contains("duplicate leaves"), "{}", err);
+
+        // deduplicated, the file builds the tree of address_amount.csv.
+        let (root, _, _) = round_trip(
+            &format,
+            path.clone(),
+            ADDRESS_AMOUNT,
+            "osmo10004ufcv2aln3vl8defyk9agv5kacrzpkyw5p410",
+            Duplicates::Dedupe,
+        );
+        assert_eq!("1V0YcwzXWtB+iuOTob6juiNliUmB278xZIKMnzwjqOU=", root);
+
+        // merged, the amounts of each address are summed.
+        csv.push_str("osmo10004ufcv2aln3vl8defyk9agv5kacrzpkyw5p4,5\n");
+        fs::write(&path, &csv).unwrap();
+        let (merged_root, _, _) = round_trip(
+            &format,
+            path.clone(),
+            "osmo10004ufcv2aln3vl8defyk9agv5kacrzpkyw5p415",
+            "osmo10004ufcv2aln3vl8defyk9agv5kacrzpkyw5p410",
+            Duplicates::Merge,
+        );
+        assert_ne!(root, merged_root);
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn proof_written_and_verified() {
+        let format = HashedTree::<Sha3Hasher>::new(merkle::TreeVersion::V1);
+        let proof_path = std::env::temp_dir().join("merkle-cli-proof-written-and-verified.json");
+
+        generate_proof_cmd(
+            &format,
+            testdata("address_amount.csv"),
+            ADDRESS_AMOUNT,
+            &Some(proof_path.clone()),
+            false,
+            false,
+            Duplicates::Reject,
+        )
+        .unwrap();
+
+        verify_proof_cmd(
+            &format,
+            "1V0YcwzXWtB+iuOTob6juiNliUmB278xZIKMnzwjqOU=",
+            proof_path.clone(),
+            ADDRESS_AMOUNT,
+        )
+        .unwrap();
+        verify_proof_cmd(
+            &format,
+            "2m/Qv3hxycYMzmFgrYWInV2rkcPavLdBsmoW9YsP8aI=",
+            proof_path.clone(),
+            ADDRESS_AMOUNT,
+        )
+        .unwrap_err();
+
+        fs::remove_file(proof_path).unwrap();
+    }
+
+    #[test]
+    fn repeated_addresses_kept_unless_merged() {
+        let format = HashedTree::<Sha3Hasher>::new(merkle::TreeVersion::V1);
+
+        // the addresses of uosmo_only.csv given more than once have distinct
+        // amounts, and are not rejected.
+        let leaves = load_leaves(&format, testdata("uosmo_only.csv"), Duplicates::Reject).unwrap();
+        assert_eq!(29, leaves.len());
+        assert_eq!(
+            "Nz54SQtyBVHwsmEqNI//mxFgiq8MRD7sS92IGkhgMvo=",
+            format.generate_root(&leaves).unwrap()
+        );
+
+        let merged = load_leaves(&format, testdata("uosmo_only.csv"), Duplicates::Merge).unwrap();
+        assert_eq!(10, merged.len());
+        assert!(merged.contains(&b"osmo10004ufcv2aln3vl8defyk9agv5kacrzpkyw5p439104uosmo".to_vec()));
+
+        let entries = parse_entries(testdata("uosmo_only.csv")).unwrap();
+        let tree: merkle::Tree = merkle::Tree::from_entries(
+            &entries,
+            merkle::TreeOptions {
+                duplicates: merkle::duplicates::DuplicatePolicy::Merge,
+                ..merkle::TreeOptions::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            base64::encode(tree.get_root().unwrap()),
+            format.generate_root(&merged).unwrap()
+        );
+    }
+}
@@ -1,11 +1,12 @@
-use crate::hash;
+use crate::hash::{self, MerkleHasher};
+use crate::TreeVersion;
 
 /// TODO: spec and tests
-pub fn build_leaf_level<T: AsRef<[u8]>>(items: &[T]) -> Vec<hash::Hash> {
+pub fn build_leaf_level<H: MerkleHasher, T: AsRef<[u8]>>(items: &[T]) -> Vec<hash::Hash> {
     let mut nodes: Vec<hash::Hash> = Vec::new();
     for item in items.iter() {
         let item = item.as_ref();
-        let hash = hash::leaf(item);
+        let hash = H::leaf(item);
         nodes.push(hash)
     }
 
@@ -18,8 +19,10 @@ pub fn build_leaf_level<T: AsRef<[u8]>>(items: &[T]) -> Vec<hash::Hash> {
 
 // build_branch_levels builds branch levels from the give leaf nodes.
 // mutates the parameter by pushing the new nodes onto it.
+// The last node of an odd level is paired with itself in V1 trees, and
+// promoted unchanged in V2 trees.
 // CONTRACT: nodes are sorted in incrasing order.
-pub fn build_branch_levels(nodes: &mut Vec<hash::Hash>) {
+pub fn build_branch_levels<H: MerkleHasher>(nodes: &mut Vec<hash::Hash>, version: TreeVersion) {
     let mut previous_level_length = nodes.len() as u128;
     let mut current_level_length = get_next_level_length(previous_level_length);
     let mut previous_level_start = 0;
@@ -29,13 +32,13 @@ pub fn build_branch_levels(nodes: &mut Vec<hash::Hash>) {
             let nodes_index: u128 = previous_level_start + previous_level_index;
             let left_sibling = &nodes[nodes_index as usize];
 
-            let right_sibling = if previous_level_index + 1 >= previous_level_length {
-                &nodes[nodes_index as usize] // For the case where the number of nodes at a level is odd.
+            let hash = if previous_level_index + 1 < previous_level_length {
+                H::branch(left_sibling, &nodes[(nodes_index + 1) as usize])
+            } else if version == TreeVersion::V1 {
+                H::branch(left_sibling, left_sibling) // For the case where the number of nodes at a level is odd.
             } else {
-                &nodes[(nodes_index + 1) as usize]
+                *left_sibling
             };
-
-            let hash = hash::branch(left_sibling, right_sibling);
             nodes.push(hash);
         }
         previous_level_start += previous_level_length;
@@ -77,6 +80,7 @@ fn round_up_power_of_two(n: u128) -> u128 {
 
 #[cfg(test)]
 mod tests {
+    use crate::hash::Sha3Hasher;
     use crate::test_util;
     use std::{collections::HashMap, vec};
 
@@ -89,7 +93,7 @@ mod tests {
         let mut actual_nodes: Vec<hash::Hash> = prepare_leaf_nodes(&items);
         let expected_nodes: Vec<hash::Hash> = actual_nodes.clone();
 
-        build_branch_levels(&mut actual_nodes);
+        build_branch_levels::<Sha3Hasher>(&mut actual_nodes, TreeVersion::V1);
 
         validate_nodes(&expected_nodes, &actual_nodes);
     }
@@ -136,9 +140,9 @@ mod tests {
         let mut actual_nodes: Vec<hash::Hash> = prepare_leaf_nodes(&items);
 
         let mut expected_nodes: Vec<hash::Hash> = actual_nodes.clone();
-        expected_nodes.push(hash::branch(&expected_nodes[0], &expected_nodes[1]));
+        expected_nodes.push(Sha3Hasher::branch(&expected_nodes[0], &expected_nodes[1]));
 
-        build_branch_levels(&mut actual_nodes);
+        build_branch_levels::<Sha3Hasher>(&mut actual_nodes, TreeVersion::V1);
 
         validate_nodes(&expected_nodes, &actual_nodes);
     }
@@ -150,11 +154,11 @@ mod tests {
         let mut actual_nodes: Vec<hash::Hash> = prepare_leaf_nodes(&items);
 
         let mut expected_nodes: Vec<hash::Hash> = actual_nodes.clone();
-        expected_nodes.push(hash::branch(&expected_nodes[0], &expected_nodes[1]));
-        expected_nodes.push(hash::branch(&expected_nodes[2], &expected_nodes[2]));
-        expected_nodes.push(hash::branch(&expected_nodes[3], &expected_nodes[4]));
+        expected_nodes.push(Sha3Hasher::branch(&expected_nodes[0], &expected_nodes[1]));
+        expected_nodes.push(Sha3Hasher::branch(&expected_nodes[2], &expected_nodes[2]));
+        expected_nodes.push(Sha3Hasher::branch(&expected_nodes[3], &expected_nodes[4]));
 
-        build_branch_levels(&mut actual_nodes);
+        build_branch_levels::<Sha3Hasher>(&mut actual_nodes, TreeVersion::V1);
 
         validate_nodes(&expected_nodes, &actual_nodes);
     }
@@ -173,25 +177,58 @@ mod tests {
 
         let mut expected_nodes: Vec<hash::Hash> = actual_nodes.clone();
         // level 3
-        expected_nodes.push(hash::branch(&expected_nodes[0], &expected_nodes[1]));
-        expected_nodes.push(hash::branch(&expected_nodes[2], &expected_nodes[3]));
-        expected_nodes.push(hash::branch(&expected_nodes[4], &expected_nodes[4]));
+        expected_nodes.push(Sha3Hasher::branch(&expected_nodes[0], &expected_nodes[1]));
+        expected_nodes.push(Sha3Hasher::branch(&expected_nodes[2], &expected_nodes[3]));
+        expected_nodes.push(Sha3Hasher::branch(&expected_nodes[4], &expected_nodes[4]));
+
+        // level 2
+        expected_nodes.push(Sha3Hasher::branch(&expected_nodes[5], &expected_nodes[6]));
+        expected_nodes.push(Sha3Hasher::branch(&expected_nodes[7], &expected_nodes[7]));
+
+        // level 1
+        expected_nodes.push(Sha3Hasher::branch(&expected_nodes[8], &expected_nodes[9]));
+
+        build_branch_levels::<Sha3Hasher>(&mut actual_nodes, TreeVersion::V1);
+
+        validate_nodes(&expected_nodes, &actual_nodes);
+    }
+
+    #[test]
+    fn build_branch_level_five_nodes_v2() {
+        let items: Vec<&[u8]> = vec![
+            test_util::OSMO,
+            test_util::ION,
+            test_util::WETH,
+            test_util::USDC,
+            test_util::AKT,
+        ];
+
+        let mut actual_nodes: Vec<hash::Hash> = prepare_leaf_nodes(&items);
+
+        let mut expected_nodes: Vec<hash::Hash> = actual_nodes.clone();
+        // level 3, the last leaf is promoted.
+        expected_nodes.push(Sha3Hasher::branch(&expected_nodes[0], &expected_nodes[1]));
+        expected_nodes.push(Sha3Hasher::branch(&expected_nodes[2], &expected_nodes[3]));
+        expected_nodes.push(expected_nodes[4]);
 
         // level 2
-        expected_nodes.push(hash::branch(&expected_nodes[5], &expected_nodes[6]));
-        expected_nodes.push(hash::branch(&expected_nodes[7], &expected_nodes[7]));
+        expected_nodes.push(Sha3Hasher::branch(&expected_nodes[5], &expected_nodes[6]));
+        expected_nodes.push(expected_nodes[7]);
 
         // level 1
-        expected_nodes.push(hash::branch(&expected_nodes[8], &expected_nodes[9]));
+        expected_nodes.push(Sha3Hasher::branch(&expected_nodes[8], &expected_nodes[9]));
 
-        build_branch_levels(&mut actual_nodes);
+        build_branch_levels::<Sha3Hasher>(&mut actual_nodes, TreeVersion::V2);
 
         validate_nodes(&expected_nodes, &actual_nodes);
     }
 
     fn prepare_leaf_nodes(items: &Vec<&[u8]>) -> Vec<hash::Hash> {
-        let mut actual_nodes: Vec<hash::Hash> =
-            items.into_iter().map(|i| hash::leaf(i)).rev().collect();
+        let mut actual_nodes: Vec<hash::Hash> = items
+            .into_iter()
+            .map(|i| Sha3Hasher::leaf(i))
+            .rev()
+            .collect();
 
         test_util::sort(&mut actual_nodes);
         return actual_nodes;
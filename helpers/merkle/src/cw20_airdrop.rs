@@ -0,0 +1,240 @@
+use serde::{Deserialize, Serialize};
+
+use crate::duplicates::{self, Collision, DuplicatePolicy};
+use crate::error::TreeError;
+use crate::hash::{Hash, MerkleHasher, SortedSha256Hasher};
+
+// Cw20AirdropTree is a merkle tree in the format of cw20-merkle-airdrop
+// snapshots, as built by its helper with merkletreejs. Leaves are the
+// SortedSha256Hasher hashes of address and amount, sorted. Each level pairs
+// nodes in order, and a last unpaired node is moved up unchanged instead of
+// being hashed with itself.
+#[derive(Debug)]
+pub struct Cw20AirdropTree {
+    // levels from the leaves up to the root.
+    levels: Vec<Vec<Hash>>,
+    duplicates: Vec<Collision>,
+}
+
+impl Cw20AirdropTree {
+    // new builds a tree of items, rejecting items given more than once.
+    pub fn new<T: AsRef<[u8]>>(items: &[T]) -> Result<Self, TreeError> {
+        Self::with_duplicates(items, DuplicatePolicy::default())
+    }
+
+    pub fn with_duplicates<T: AsRef<[u8]>>(
+        items: &[T],
+        policy: DuplicatePolicy,
+    ) -> Result<Self, TreeError> {
+        let duplicates = duplicates::apply_policy(items, policy)?;
+
+        let mut leaves: Vec<Hash> = items
+            .iter()
+            .map(|item| SortedSha256Hasher::leaf(item.as_ref()))
+            .collect();
+
+        if leaves.is_empty() {
+            return Ok(Cw20AirdropTree {
+                levels: vec![],
+                duplicates,
+            });
+        }
+
+        pdqsort::sort_by(&mut leaves, |a, b| a.cmp(b));
+        leaves.dedup();
+
+        let mut levels = vec![leaves];
+        while levels[levels.len() - 1].len() > 1 {
+            let level = levels[levels.len() - 1]
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => SortedSha256Hasher::branch(left, right),
+                    _ => pair[0],
+                })
+                .collect();
+            levels.push(level);
+        }
+
+        Ok(Cw20AirdropTree { levels, duplicates })
+    }
+
+    // duplicates returns the items that were given more than once and
+    // deduplicated.
+    pub fn duplicates(&self) -> &[Collision] {
+        &self.duplicates
+    }
+
+    pub fn get_root(&self) -> Option<Hash> {
+        self.levels.last().map(|level| level[0])
+    }
+
+    // get_root_hex returns the root as cw20-merkle-airdrop expects it.
+    pub fn get_root_hex(&self) -> Option<String> {
+        self.get_root().map(hex::encode)
+    }
+
+    // find_proof returns the sibling hashes from the item's leaf up to the
+    // root, skipping the levels on which the node has no sibling.
+    pub fn find_proof<T: AsRef<[u8]>>(&self, item: &T) -> Option<Cw20AirdropProof> {
+        let leaf = SortedSha256Hasher::leaf(item.as_ref());
+        let mut index = self.levels.first()?.binary_search(&leaf).ok()?;
+
+        let mut proof = Vec::new();
+        for level in &self.levels[..self.levels.len() - 1] {
+            let sibling = index ^ 1;
+            if sibling < level.len() {
+                proof.push(level[sibling]);
+            }
+            index /= 2;
+        }
+
+        Some(Cw20AirdropProof(proof))
+    }
+}
+
+// Cw20AirdropProof is a proof of a Cw20AirdropTree leaf. It serializes as a
+// list of hex encoded hashes, like the proofs cw20-merkle-airdrop is claimed
+// with.
+#[derive(Eq, PartialEq, Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(into = "Vec<String>", try_from = "Vec<String>")]
+pub struct Cw20AirdropProof(Vec<Hash>);
+
+impl Cw20AirdropProof {
+    pub fn verify<T: AsRef<[u8]>>(&self, data: &T, root: &Hash) -> bool {
+        let leaf = SortedSha256Hasher::leaf(data.as_ref());
+
+        let result = self.0.iter().fold(leaf, |cur_hash, sibling| {
+            SortedSha256Hasher::branch(&cur_hash, sibling)
+        });
+
+        result.eq(root)
+    }
+
+    pub fn hashes(&self) -> &[Hash] {
+        &self.0
+    }
+}
+
+impl From<Vec<Hash>> for Cw20AirdropProof {
+    fn from(hashes: Vec<Hash>) -> Self {
+        Cw20AirdropProof(hashes)
+    }
+}
+
+impl From<Cw20AirdropProof> for Vec<String> {
+    fn from(proof: Cw20AirdropProof) -> Self {
+        proof.0.iter().map(hex::encode).collect()
+    }
+}
+
+impl TryFrom<Vec<String>> for Cw20AirdropProof {
+    type Error = String;
+
+    fn try_from(hashes: Vec<String>) -> Result<Self, Self::Error> {
+        hashes
+            .iter()
+            .map(|hash| {
+                let bytes = hex::decode(hash).map_err(|e| e.to_string())?;
+                Hash::try_from(bytes.as_slice()).map_err(|e| e.to_string())
+            })
+            .collect::<Result<_, _>>()
+            .map(Cw20AirdropProof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ITEMS: [&str; 3] = ["osmo1claimer100", "osmo1claimer2250", "osmo1claimer3300"];
+
+    #[test]
+    fn odd_node_moved_up() {
+        let mut leaves: Vec<Hash> = ITEMS
+            .iter()
+            .map(|item| SortedSha256Hasher::leaf(item.as_bytes()))
+            .collect();
+        leaves.sort();
+
+        let mt = Cw20AirdropTree::new(&ITEMS).unwrap();
+
+        // reference root computed independently for the same leaves.
+        assert_eq!(
+            Some(String::from(
+                "0f7e8b2c22044dd661e2ec7702d641f67ee3f5af90346b086986b7776d6def64"
+            )),
+            mt.get_root_hex()
+        );
+        assert_eq!(
+            Some(SortedSha256Hasher::branch(
+                &SortedSha256Hasher::branch(&leaves[0], &leaves[1]),
+                &leaves[2]
+            )),
+            mt.get_root()
+        );
+
+        // the moved up leaf has a single sibling.
+        let last = ITEMS
+            .iter()
+            .find(|item| SortedSha256Hasher::leaf(item.as_bytes()) == leaves[2])
+            .unwrap();
+        assert_eq!(1, mt.find_proof(last).unwrap().hashes().len());
+    }
+
+    #[test]
+    fn every_leaf_verifies() {
+        let items: Vec<String> = (0..17).map(|i| format!("osmo1claimer{}100", i)).collect();
+
+        for count in 1..=items.len() {
+            let mt = Cw20AirdropTree::new(&items[..count]).unwrap();
+            let root = mt.get_root().unwrap();
+
+            for item in &items[..count] {
+                let proof = mt.find_proof(item).unwrap();
+                assert!(proof.verify(item, &root), "{} of {}", item, count);
+                assert!(!proof.verify(&"osmo1unknown100", &root));
+            }
+
+            assert!(mt.find_proof(&"osmo1unknown100").is_none());
+        }
+    }
+
+    #[test]
+    fn duplicate_items_rejected_or_deduplicated() {
+        let items = [ITEMS[0], ITEMS[1], ITEMS[2], ITEMS[1]];
+
+        let err = Cw20AirdropTree::new(&items).unwrap_err();
+        assert!(matches!(err, TreeError::DuplicateLeaf { .. }), "{:?}", err);
+
+        let mt = Cw20AirdropTree::with_duplicates(&items, DuplicatePolicy::Dedupe).unwrap();
+        assert_eq!(vec![1, 3], mt.duplicates()[0].indices);
+        assert_eq!(
+            Cw20AirdropTree::new(&ITEMS).unwrap().get_root(),
+            mt.get_root()
+        );
+    }
+
+    #[test]
+    fn empty_tree() {
+        let items: Vec<&str> = vec![];
+        let mt = Cw20AirdropTree::new(&items).unwrap();
+
+        assert!(mt.get_root().is_none());
+        assert!(mt.find_proof(&ITEMS[0]).is_none());
+    }
+
+    #[test]
+    fn hex_serialization() {
+        let mt = Cw20AirdropTree::new(&ITEMS).unwrap();
+        let proof = mt.find_proof(&ITEMS[0]).unwrap();
+
+        let serialized = serde_json_wasm::to_string(&proof).unwrap();
+        let hashes: Vec<String> = serde_json_wasm::from_str(&serialized).unwrap();
+        assert!(hashes.iter().all(|hash| hash.len() == 64), "{}", serialized);
+
+        let deserialized: Cw20AirdropProof = serde_json_wasm::from_str(&serialized).unwrap();
+        assert_eq!(proof, deserialized);
+
+        serde_json_wasm::from_str::<Cw20AirdropProof>("[\"abcd\"]").unwrap_err();
+    }
+}
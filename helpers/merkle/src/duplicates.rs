@@ -0,0 +1,211 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::error::TreeError;
+
+// DuplicatePolicy is what a tree does with items given more than once.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DuplicatePolicy {
+    // Reject fails with TreeError::DuplicateLeaf.
+    #[default]
+    Reject,
+    // Dedupe keeps a single leaf per item.
+    Dedupe,
+    // Merge merges the entries of an address into one by summing their
+    // amounts. Only trees built from address and amount entries can merge
+    // them, as an address and amount concatenated cannot be split back apart
+    // since bech32 addresses end in digits.
+    Merge,
+}
+
+// MergedEntries are the entries merged by merge_amounts, along with the
+// addresses that were merged.
+pub type MergedEntries = (Vec<(String, String)>, Vec<Collision>);
+
+// Collision is an item given more than once, with the indices it was given
+// at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Collision {
+    pub item: Vec<u8>,
+    pub indices: Vec<usize>,
+}
+
+impl fmt::Display for Collision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at {:?}",
+            String::from_utf8_lossy(&self.item),
+            self.indices
+        )
+    }
+}
+
+// find_duplicates returns the items given more than once, in the order they
+// were first given.
+pub fn find_duplicates<T: AsRef<[u8]>>(items: &[T]) -> Vec<Collision> {
+    collisions(items.iter().map(|item| item.as_ref()))
+}
+
+// apply_policy returns the items given more than once, to be deduplicated by
+// the tree, or fails if the policy rejects them. A duplicate would otherwise
+// be an arbitrary one of several equal leaves when searched for.
+pub(crate) fn apply_policy<T: AsRef<[u8]>>(
+    items: &[T],
+    policy: DuplicatePolicy,
+) -> Result<Vec<Collision>, TreeError> {
+    if policy == DuplicatePolicy::Merge {
+        return Err(TreeError::MergeRequiresEntries);
+    }
+
+    let duplicates = find_duplicates(items);
+    if !duplicates.is_empty() && policy == DuplicatePolicy::Reject {
+        return Err(TreeError::DuplicateLeaf {
+            collisions: duplicates,
+        });
+    }
+
+    Ok(duplicates)
+}
+
+// merge_amounts merges the address and amount entries of the same address by
+// summing their amounts, for trees whose leaves are address and amount
+// concatenated. Amounts are plain integers or coin strings, which must be of
+// the same denom to be merged. Returns the merged entries, in the order their
+// addresses were first given, and the addresses that were merged.
+pub fn merge_amounts(entries: &[(String, String)]) -> Result<MergedEntries, TreeError> {
+    let mut merged: Vec<(String, u128, String)> = vec![];
+    let mut positions: BTreeMap<&str, usize> = BTreeMap::new();
+
+    for (address, amount) in entries {
+        let invalid = || TreeError::InvalidAmount {
+            address: address.clone(),
+            amount: amount.clone(),
+        };
+
+        let digits = amount
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(amount.len());
+        let (value, denom) = amount.split_at(digits);
+        let value: u128 = value.parse().map_err(|_| invalid())?;
+
+        match positions.get(address.as_str()) {
+            Some(&position) => {
+                let entry = &mut merged[position];
+                if entry.2 != denom {
+                    return Err(invalid());
+                }
+                entry.1 = entry.1.checked_add(value).ok_or_else(invalid)?;
+            }
+            None => {
+                positions.insert(address, merged.len());
+                merged.push((address.clone(), value, String::from(denom)));
+            }
+        }
+    }
+
+    let merged = merged
+        .into_iter()
+        .map(|(address, value, denom)| (address, format!("{}{}", value, denom)))
+        .collect();
+
+    Ok((
+        merged,
+        collisions(entries.iter().map(|(address, _)| address.as_bytes())),
+    ))
+}
+
+fn collisions<'a>(items: impl Iterator<Item = &'a [u8]>) -> Vec<Collision> {
+    let mut indices: BTreeMap<&[u8], Vec<usize>> = BTreeMap::new();
+    for (i, item) in items.enumerate() {
+        indices.entry(item).or_default().push(i);
+    }
+
+    let mut collisions: Vec<Collision> = indices
+        .into_iter()
+        .filter(|(_, indices)| indices.len() > 1)
+        .map(|(item, indices)| Collision {
+            item: item.to_vec(),
+            indices,
+        })
+        .collect();
+    collisions.sort_by_key(|collision| collision.indices[0]);
+
+    collisions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries(entries: &[(&str, &str)]) -> Vec<(String, String)> {
+        entries
+            .iter()
+            .map(|(address, amount)| (String::from(*address), String::from(*amount)))
+            .collect()
+    }
+
+    #[test]
+    fn duplicates_reported_in_input_order() {
+        let items = ["osmo1b", "osmo1a", "osmo1c", "osmo1a", "osmo1b", "osmo1a"];
+
+        assert_eq!(
+            vec![
+                Collision {
+                    item: b"osmo1b".to_vec(),
+                    indices: vec![0, 4],
+                },
+                Collision {
+                    item: b"osmo1a".to_vec(),
+                    indices: vec![1, 3, 5],
+                },
+            ],
+            find_duplicates(&items)
+        );
+        assert!(find_duplicates(&["osmo1a", "osmo1b"]).is_empty());
+    }
+
+    #[test]
+    fn amounts_merged_per_address() {
+        let (merged, collisions) = merge_amounts(&entries(&[
+            ("osmo1b", "7uosmo"),
+            ("osmo1a", "10uosmo"),
+            ("osmo1b", "29114uosmo"),
+        ]))
+        .unwrap();
+
+        assert_eq!(
+            entries(&[("osmo1b", "29121uosmo"), ("osmo1a", "10uosmo")]),
+            merged
+        );
+        assert_eq!(
+            vec![Collision {
+                item: b"osmo1b".to_vec(),
+                indices: vec![0, 2],
+            }],
+            collisions
+        );
+
+        // plain amounts have no denom.
+        let (merged, _) = merge_amounts(&entries(&[("osmo1a", "1"), ("osmo1a", "2")])).unwrap();
+        assert_eq!(entries(&[("osmo1a", "3")]), merged);
+    }
+
+    #[test]
+    fn invalid_amounts_not_merged() {
+        let cases = [
+            entries(&[("osmo1a", "10uosmo"), ("osmo1a", "10uion")]),
+            entries(&[("osmo1a", "uosmo")]),
+            entries(&[("osmo1a", &u128::MAX.to_string()), ("osmo1a", "1")]),
+        ];
+
+        for entries in cases {
+            let err = merge_amounts(&entries).unwrap_err();
+            assert!(
+                matches!(err, TreeError::InvalidAmount { .. }),
+                "{:?}",
+                entries
+            );
+        }
+    }
+}
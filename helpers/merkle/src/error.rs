@@ -0,0 +1,42 @@
+use std::fmt;
+
+use crate::duplicates::Collision;
+
+// TreeError is why a tree could not be built from the given items.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TreeError {
+    // Empty is a tree built from no items at all.
+    Empty,
+    // DuplicateLeaf lists the items given more than once, with the indices
+    // they were given at.
+    DuplicateLeaf { collisions: Vec<Collision> },
+    // InvalidAmount is an amount that cannot be merged with the other amounts
+    // of its address.
+    InvalidAmount { address: String, amount: String },
+    // MergeRequiresEntries is DuplicatePolicy::Merge given items rather than
+    // address and amount entries.
+    MergeRequiresEntries,
+}
+
+impl fmt::Display for TreeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TreeError::Empty => write!(f, "no items to build a tree from"),
+            TreeError::DuplicateLeaf { collisions } => {
+                write!(f, "duplicate leaves:")?;
+                for collision in collisions {
+                    write!(f, " {}", collision)?;
+                }
+                Ok(())
+            }
+            TreeError::InvalidAmount { address, amount } => {
+                write!(f, "cannot merge amount {} of {}", amount, address)
+            }
+            TreeError::MergeRequiresEntries => {
+                write!(f, "only address and amount entries can be merged")
+            }
+        }
+    }
+}
+
+impl std::error::Error for TreeError {}
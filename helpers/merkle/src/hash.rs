@@ -1,7 +1,8 @@
 use std::fmt;
 
 use serde::{de, de::Visitor, Deserialize, Deserializer, Serialize, Serializer};
-use sha3::{Digest, Sha3_256};
+use sha2::Sha256;
+use sha3::{Digest, Keccak256, Sha3_256};
 
 // The distinction in prefixes is needed
 // to guard against second preimage attack
@@ -72,47 +73,123 @@ impl AsRef<[u8]> for Hash {
     }
 }
 
+impl TryFrom<&[u8]> for Hash {
+    type Error = std::array::TryFromSliceError;
+
+    fn try_from(item: &[u8]) -> Result<Self, Self::Error> {
+        Ok(Hash(<[u8; 32]>::try_from(item)?))
+    }
+}
+
 impl From<Vec<u8>> for Hash {
     fn from(item: Vec<u8>) -> Self {
         Hash(<[u8; 32]>::try_from(item.as_slice()).unwrap())
     }
 }
 
-#[derive(Clone, Default)]
-struct Hasher {
-    hasher: Sha3_256,
+// MerkleHasher is the hash function a tree is built and its proofs are
+// verified with. A root can only be verified with the hasher it was built with.
+pub trait MerkleHasher {
+    // digest hashes the concatenation of values.
+    fn digest(values: &[&[u8]]) -> Hash;
+
+    fn leaf(data: &[u8]) -> Hash {
+        Self::digest(&[LEAF_NODE_PREFIX, data])
+    }
+
+    fn branch(left_child: &Hash, right_child: &Hash) -> Hash {
+        Self::digest(&[
+            BRANCH_NODE_PREFIX,
+            left_child.as_ref(),
+            right_child.as_ref(),
+        ])
+    }
 }
 
-impl Hasher {
-    fn update(&mut self, val: &[u8]) {
-        self.hasher.update(val);
+// Sha3Hasher hashes with SHA3-256, the hash function trees were first built with.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Sha3Hasher;
+
+impl MerkleHasher for Sha3Hasher {
+    fn digest(values: &[&[u8]]) -> Hash {
+        digest::<Sha3_256>(values)
     }
+}
 
-    fn result(self) -> Hash {
-        // At the time of this writing, the sha2 library is stuck on an old version
-        // of generic_array (0.9.0). Decouple ourselves with a clone to our version.
-        Hash(<[u8; 32]>::try_from(self.hasher.finalize().as_slice()).unwrap())
+// Sha256Hasher hashes with SHA-256.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Sha256Hasher;
+
+impl MerkleHasher for Sha256Hasher {
+    fn digest(values: &[&[u8]]) -> Hash {
+        digest::<Sha256>(values)
     }
 }
 
-pub fn leaf(data: &[u8]) -> Hash {
-    hash(&[LEAF_NODE_PREFIX, data])
+// Keccak256Hasher hashes with Keccak-256, as used by EVM tooling.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Keccak256Hasher;
+
+impl MerkleHasher for Keccak256Hasher {
+    fn digest(values: &[&[u8]]) -> Hash {
+        digest::<Keccak256>(values)
+    }
 }
 
-pub fn branch(left_child: &Hash, right_child: &Hash) -> Hash {
-    hash(&[
-        BRANCH_NODE_PREFIX,
-        left_child.as_ref(),
-        right_child.as_ref(),
-    ])
+// SortedKeccak256Hasher hashes like OpenZeppelin's MerkleProof and
+// StandardMerkleTree: leaves are hashed twice with Keccak-256 instead of being
+// prefixed, and pairs are hashed in sorted order, so that proofs need no
+// sibling positions.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SortedKeccak256Hasher;
+
+impl MerkleHasher for SortedKeccak256Hasher {
+    fn digest(values: &[&[u8]]) -> Hash {
+        digest::<Keccak256>(values)
+    }
+
+    fn leaf(data: &[u8]) -> Hash {
+        Self::digest(&[Self::digest(&[data]).as_ref()])
+    }
+
+    fn branch(left_child: &Hash, right_child: &Hash) -> Hash {
+        sorted_pair(left_child, right_child, Self::digest)
+    }
 }
 
-fn hash(values: &[&[u8]]) -> Hash {
-    let mut hasher = Hasher::default();
+// SortedSha256Hasher hashes like cw20-merkle-airdrop: leaves are hashed once
+// with SHA-256 and without a prefix, and pairs are hashed in sorted order.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SortedSha256Hasher;
+
+impl MerkleHasher for SortedSha256Hasher {
+    fn digest(values: &[&[u8]]) -> Hash {
+        digest::<Sha256>(values)
+    }
+
+    fn leaf(data: &[u8]) -> Hash {
+        Self::digest(&[data])
+    }
+
+    fn branch(left_child: &Hash, right_child: &Hash) -> Hash {
+        sorted_pair(left_child, right_child, Self::digest)
+    }
+}
+
+// sorted_pair hashes the pair with the smaller hash first.
+fn sorted_pair(a: &Hash, b: &Hash, digest: fn(&[&[u8]]) -> Hash) -> Hash {
+    let (first, second) = if a <= b { (a, b) } else { (b, a) };
+    digest(&[first.as_ref(), second.as_ref()])
+}
+
+fn digest<D: Digest>(values: &[&[u8]]) -> Hash {
+    let mut hasher = D::new();
     for value in values {
         hasher.update(value);
     }
-    hasher.result()
+    // Decouple ourselves from the generic_array version of the hash
+    // libraries with a copy to our type.
+    Hash::try_from(hasher.finalize().as_slice()).unwrap()
 }
 
 #[cfg(test)]
@@ -122,11 +199,57 @@ mod tests {
 
     #[test]
     fn custom_serialization_works() {
-        let test_entry = leaf(test_util::OSMO);
+        let test_entry = Sha3Hasher::leaf(test_util::OSMO);
 
         let serialized = serde_json_wasm::to_string(&test_entry).unwrap();
         let deserialized: Hash = serde_json_wasm::from_str(&serialized).unwrap();
 
         assert_eq!(test_entry, deserialized);
     }
+
+    #[test]
+    fn hashers_differ() {
+        // reference digests of the empty string.
+        assert_eq!(
+            "a7ffc6f8bf1ed76651c14756a061d662f580ff4de43b49fa82d80a4b80f8434a",
+            hex(Sha3Hasher::digest(&[]))
+        );
+        assert_eq!(
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+            hex(Sha256Hasher::digest(&[]))
+        );
+        assert_eq!(
+            "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470",
+            hex(Keccak256Hasher::digest(&[]))
+        );
+
+        // values are concatenated.
+        assert_eq!(
+            Sha256Hasher::digest(&[b"osmoion"]),
+            Sha256Hasher::digest(&[b"osmo", b"ion"])
+        );
+    }
+
+    #[test]
+    fn sorted_keccak256_pairs_commute() {
+        let osmo = SortedKeccak256Hasher::leaf(test_util::OSMO);
+        let ion = SortedKeccak256Hasher::leaf(test_util::ION);
+
+        assert_eq!(
+            Keccak256Hasher::digest(&[Keccak256Hasher::digest(&[test_util::OSMO]).as_ref()]),
+            osmo
+        );
+        assert_eq!(
+            SortedKeccak256Hasher::branch(&osmo, &ion),
+            SortedKeccak256Hasher::branch(&ion, &osmo)
+        );
+        assert_eq!(
+            Keccak256Hasher::digest(&[osmo.min(ion).as_ref(), osmo.max(ion).as_ref()]),
+            SortedKeccak256Hasher::branch(&osmo, &ion)
+        );
+    }
+
+    fn hex(hash: Hash) -> String {
+        hash.0.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
 }
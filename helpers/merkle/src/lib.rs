@@ -1,34 +1,134 @@
+use std::marker::PhantomData;
+
+use duplicates::{Collision, DuplicatePolicy};
+use hash::MerkleHasher;
+
+pub use error::TreeError;
+
 mod binary_search;
 mod builder;
+pub mod cw20_airdrop;
+pub mod duplicates;
+mod error;
 pub mod hash;
 pub mod proof;
+pub mod sorted_pair;
+
+// TreeVersion is how a tree handles the last node of an odd level. Proofs
+// are verified the same way whatever the version, so roots of every version
+// remain verifiable.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TreeVersion {
+    // V1 pairs the last node of an odd level with itself. Different leaf
+    // lists can then share a root, e.g. [a, b, c] and [a, b, c, c], as in
+    // CVE-2012-2459.
+    #[default]
+    V1,
+    // V2 promotes the last node of an odd level to the next one unchanged.
+    V2,
+}
 
+// TreeOptions is how a tree is built from its items.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TreeOptions {
+    pub version: TreeVersion,
+    pub duplicates: DuplicatePolicy,
+}
+
+// Tree is a merkle tree built with hasher H, SHA3-256 by default.
 #[derive(Debug)]
-pub struct Tree {
+pub struct Tree<H: MerkleHasher = hash::Sha3Hasher> {
     leaf_count: u128,
     nodes: Vec<hash::Hash>,
+    version: TreeVersion,
+    duplicates: Vec<Collision>,
+    hasher: PhantomData<H>,
 }
 
-impl Tree {
-    pub fn new<T: AsRef<[u8]>>(items: &[T]) -> Self {
+impl<H: MerkleHasher> Tree<H> {
+    // new builds a V1 tree, the version existing roots were built with.
+    // Items given more than once are rejected.
+    pub fn new<T: AsRef<[u8]>>(items: &[T]) -> Result<Self, TreeError> {
+        Self::with_options(items, TreeOptions::default())
+    }
+
+    pub fn with_version<T: AsRef<[u8]>>(
+        items: &[T],
+        version: TreeVersion,
+    ) -> Result<Self, TreeError> {
+        Self::with_options(
+            items,
+            TreeOptions {
+                version,
+                ..TreeOptions::default()
+            },
+        )
+    }
+
+    pub fn with_options<T: AsRef<[u8]>>(
+        items: &[T],
+        options: TreeOptions,
+    ) -> Result<Self, TreeError> {
         if items.is_empty() {
-            return Tree {
-                leaf_count: 0,
-                nodes: Vec::<hash::Hash>::new(),
-            };
+            return Err(TreeError::Empty);
+        }
+
+        let duplicates = duplicates::apply_policy(items, options.duplicates)?;
+
+        Ok(Self::build(items, options.version, duplicates))
+    }
+
+    // from_entries builds a tree of address and amount entries, whose leaves
+    // are address and amount concatenated. Unlike items, entries can be
+    // merged with DuplicatePolicy::Merge, which reports the merged addresses
+    // as the tree's duplicates.
+    pub fn from_entries(
+        entries: &[(String, String)],
+        options: TreeOptions,
+    ) -> Result<Self, TreeError> {
+        if options.duplicates != DuplicatePolicy::Merge {
+            let items: Vec<String> = concatenate(entries);
+            return Self::with_options(&items, options);
         }
 
-        let mut nodes: Vec<hash::Hash> = builder::build_leaf_level(items);
+        if entries.is_empty() {
+            return Err(TreeError::Empty);
+        }
+
+        // merged entries are of distinct addresses, and so distinct items.
+        let (merged, duplicates) = duplicates::merge_amounts(entries)?;
+        let items: Vec<String> = concatenate(&merged);
+
+        Ok(Self::build(&items, options.version, duplicates))
+    }
+
+    fn build<T: AsRef<[u8]>>(
+        items: &[T],
+        version: TreeVersion,
+        duplicates: Vec<Collision>,
+    ) -> Self {
+        let mut nodes: Vec<hash::Hash> = builder::build_leaf_level::<H, T>(items);
+        // equal items have equal leaves, next to each other once sorted.
+        nodes.dedup();
         let leaf_count = nodes.len() as u128;
 
-        builder::build_branch_levels(&mut nodes);
+        builder::build_branch_levels::<H>(&mut nodes, version);
 
         Tree {
             leaf_count,
             nodes: nodes,
+            version,
+            duplicates,
+            hasher: PhantomData,
         }
     }
 
+    // duplicates returns the items that were given more than once and
+    // deduplicated, or the addresses whose entries were merged.
+    pub fn duplicates(&self) -> &[Collision] {
+        &self.duplicates
+    }
+
     pub fn get_root(&self) -> Option<hash::Hash> {
         let node_count = self.nodes.len();
         if self.leaf_count == 0 {
@@ -37,13 +137,15 @@ impl Tree {
         Some(self.nodes[node_count - 1])
     }
 
+    // find_proof returns the proof of item, empty if it is the only leaf and
+    // thus the root.
     pub fn find_proof<T: AsRef<[u8]>>(&self, item: &T) -> Option<proof::Proof> {
-        if self.leaf_count <= 1 {
+        if self.leaf_count == 0 {
             return None;
         }
 
         let item_ref = item.as_ref();
-        let hash_to_search_for = hash::leaf(item_ref);
+        let hash_to_search_for = H::leaf(item_ref);
 
         // binary search leaves
         let proof_index = binary_search::search(&self.nodes, self.leaf_count, &hash_to_search_for)?;
@@ -62,17 +164,21 @@ impl Tree {
             if is_left_sibling {
                 // if current_index node is on the right, we need its left sibling
                 sibling_hash = self.nodes[(level_start + current_index - 1) as usize];
+                proof.push(is_left_sibling, sibling_hash);
             } else if current_index + 1 == level_length {
                 // if current_index node is on the left but there is no right sibling
-                // grab itself for proof.
-                sibling_hash = self.nodes[(level_start + current_index) as usize];
+                // grab itself for proof in V1. In V2 it is promoted unchanged,
+                // with nothing to prove.
+                if self.version == TreeVersion::V1 {
+                    sibling_hash = self.nodes[(level_start + current_index) as usize];
+                    proof.push(is_left_sibling, sibling_hash);
+                }
             } else {
                 // current_index node is on the left, grab its right sibling
                 sibling_hash = self.nodes[(level_start + current_index + 1) as usize];
+                proof.push(is_left_sibling, sibling_hash);
             }
 
-            proof.push(is_left_sibling, sibling_hash);
-
             level_start += level_length;
             level_length = builder::get_next_level_length(level_length);
             current_index /= 2;
@@ -111,10 +217,20 @@ impl Tree {
     }
 }
 
+// concatenate returns the items of address and amount entries.
+fn concatenate(entries: &[(String, String)]) -> Vec<String> {
+    entries
+        .iter()
+        .map(|(address, amount)| format!("{}{}", address, amount))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeMap;
     use std::vec;
 
+    use crate::hash::Sha3Hasher;
     use crate::proof::Entry;
 
     use super::*;
@@ -123,29 +239,16 @@ mod tests {
     fn new_merkle_tree_empty() {
         let items: Vec<String> = vec![];
 
-        let mt = Tree::new(&items);
-
-        let root = mt.get_root();
-
-        assert_eq!(false, root.is_some());
-        assert_eq!(0, mt.get_leaf_count().unwrap());
-        assert_eq!(0, mt.get_node_count());
+        let err = Tree::<Sha3Hasher>::new(&items).unwrap_err();
 
-        match mt.get_node_at(0) {
-            Ok(result) => {
-                panic!("must have returned error but received {:?}", result)
-            }
-            Err(_error) => {
-                // expected
-            }
-        }
+        assert_eq!(TreeError::Empty, err);
     }
 
     #[test]
     fn new_merkle_tree_one_element() {
         let items: Vec<&[u8]> = vec![test_util::OSMO];
 
-        let mt = Tree::new(&items);
+        let mt: Tree = Tree::new(&items).unwrap();
 
         let root = mt.get_root();
 
@@ -156,7 +259,7 @@ mod tests {
         // TODO: extra this into helper and clean up tests
         match mt.get_node_at(0) {
             Ok(result) => {
-                assert_eq!(hash::leaf(test_util::OSMO), result);
+                assert_eq!(Sha3Hasher::leaf(test_util::OSMO), result);
                 assert_eq!(root.unwrap(), result);
             }
             Err(error) => {
@@ -169,7 +272,7 @@ mod tests {
     fn new_merkle_tree_two_elements() {
         let mut items: Vec<&[u8]> = vec![test_util::OSMO, test_util::ION];
 
-        let mt = Tree::new(&items);
+        let mt: Tree = Tree::new(&items).unwrap();
 
         test_util::hash_and_sort(&mut items);
 
@@ -181,7 +284,7 @@ mod tests {
 
         match mt.get_node_at(0) {
             Ok(result) => {
-                assert_eq!(hash::leaf(items[0]), result);
+                assert_eq!(Sha3Hasher::leaf(items[0]), result);
             }
             Err(error) => {
                 panic!("must have returned result but received error {:?}", error)
@@ -190,7 +293,7 @@ mod tests {
 
         match mt.get_node_at(1) {
             Ok(result) => {
-                assert_eq!(hash::leaf(items[1]), result);
+                assert_eq!(Sha3Hasher::leaf(items[1]), result);
             }
             Err(error) => {
                 panic!("must have returned result but received error {:?}", error)
@@ -199,9 +302,9 @@ mod tests {
 
         match mt.get_node_at(2) {
             Ok(result) => {
-                let left_hash: hash::Hash = hash::leaf(items[0]);
-                let right_hash: hash::Hash = hash::leaf(items[1]);
-                assert_eq!(hash::branch(&left_hash, &right_hash), result);
+                let left_hash: hash::Hash = Sha3Hasher::leaf(items[0]);
+                let right_hash: hash::Hash = Sha3Hasher::leaf(items[1]);
+                assert_eq!(Sha3Hasher::branch(&left_hash, &right_hash), result);
                 assert_eq!(mt.get_root().unwrap(), result);
             }
             Err(error) => {
@@ -214,7 +317,7 @@ mod tests {
     fn new_merkle_tree_three_elements() {
         let mut items: Vec<&[u8]> = vec![test_util::OSMO, test_util::WETH, test_util::ION];
 
-        let mt = Tree::new(&items);
+        let mt: Tree = Tree::new(&items).unwrap();
 
         test_util::hash_and_sort(&mut items);
 
@@ -226,7 +329,7 @@ mod tests {
 
         match mt.get_node_at(0) {
             Ok(result) => {
-                assert_eq!(hash::leaf(items[0]), result);
+                assert_eq!(Sha3Hasher::leaf(items[0]), result);
             }
             Err(error) => {
                 panic!("must have returned result but received error {:?}", error)
@@ -235,7 +338,7 @@ mod tests {
 
         match mt.get_node_at(1) {
             Ok(result) => {
-                assert_eq!(hash::leaf(items[1]), result);
+                assert_eq!(Sha3Hasher::leaf(items[1]), result);
             }
             Err(error) => {
                 panic!("must have returned result but received error {:?}", error)
@@ -244,7 +347,7 @@ mod tests {
 
         match mt.get_node_at(2) {
             Ok(result) => {
-                assert_eq!(hash::leaf(items[2]), result);
+                assert_eq!(Sha3Hasher::leaf(items[2]), result);
             }
             Err(error) => {
                 panic!("must have returned result but received error {:?}", error)
@@ -253,9 +356,9 @@ mod tests {
 
         match mt.get_node_at(3) {
             Ok(result) => {
-                let left_hash: hash::Hash = hash::leaf(items[0]);
-                let right_hash: hash::Hash = hash::leaf(items[1]);
-                assert_eq!(hash::branch(&left_hash, &right_hash), result);
+                let left_hash: hash::Hash = Sha3Hasher::leaf(items[0]);
+                let right_hash: hash::Hash = Sha3Hasher::leaf(items[1]);
+                assert_eq!(Sha3Hasher::branch(&left_hash, &right_hash), result);
             }
             Err(error) => {
                 panic!("must have returned result but received error {:?}", error)
@@ -264,9 +367,9 @@ mod tests {
 
         match mt.get_node_at(4) {
             Ok(result) => {
-                let left_hash: hash::Hash = hash::leaf(items[2]);
-                let right_hash: hash::Hash = hash::leaf(items[2]);
-                assert_eq!(hash::branch(&left_hash, &right_hash), result);
+                let left_hash: hash::Hash = Sha3Hasher::leaf(items[2]);
+                let right_hash: hash::Hash = Sha3Hasher::leaf(items[2]);
+                assert_eq!(Sha3Hasher::branch(&left_hash, &right_hash), result);
             }
             Err(error) => {
                 panic!("must have returned result but received error {:?}", error)
@@ -277,22 +380,26 @@ mod tests {
             Ok(result) => {
                 assert_eq!(mt.get_root().unwrap(), result);
 
-                let left_left_hash: hash::Hash = hash::leaf(items[0]);
-                let left_right_hash: hash::Hash = hash::leaf(items[1]);
+                let left_left_hash: hash::Hash = Sha3Hasher::leaf(items[0]);
+                let left_right_hash: hash::Hash = Sha3Hasher::leaf(items[1]);
 
-                let left_hash: hash::Hash = hash::branch(&left_left_hash, &left_right_hash);
-                assert_eq!(hash::branch(&left_left_hash, &left_right_hash), left_hash);
+                let left_hash: hash::Hash = Sha3Hasher::branch(&left_left_hash, &left_right_hash);
+                assert_eq!(
+                    Sha3Hasher::branch(&left_left_hash, &left_right_hash),
+                    left_hash
+                );
 
-                let right_left_hash: hash::Hash = hash::leaf(items[2]);
-                let right_right_hash: hash::Hash = hash::leaf(items[2]);
+                let right_left_hash: hash::Hash = Sha3Hasher::leaf(items[2]);
+                let right_right_hash: hash::Hash = Sha3Hasher::leaf(items[2]);
 
-                let right_hash: hash::Hash = hash::branch(&right_left_hash, &right_right_hash);
+                let right_hash: hash::Hash =
+                    Sha3Hasher::branch(&right_left_hash, &right_right_hash);
                 assert_eq!(
-                    hash::branch(&right_left_hash, &right_right_hash),
+                    Sha3Hasher::branch(&right_left_hash, &right_right_hash),
                     right_hash
                 );
 
-                assert_eq!(hash::branch(&left_hash, &right_hash), result);
+                assert_eq!(Sha3Hasher::branch(&left_hash, &right_hash), result);
             }
             Err(error) => {
                 panic!("must have returned result but received error {:?}", error)
@@ -304,11 +411,16 @@ mod tests {
     fn find_proof_one() {
         let items: Vec<&[u8]> = vec![test_util::OSMO];
 
-        let mt = Tree::new(&items);
+        let mt: Tree = Tree::new(&items).unwrap();
 
         let result = mt.find_proof(&test_util::OSMO);
 
-        assert_eq!(true, result.is_none());
+        // the leaf is the root.
+        let actual_proof = result.unwrap();
+        assert_eq!(0, actual_proof.get_num_entries());
+        assert!(actual_proof.verify::<Sha3Hasher, _>(&test_util::OSMO, &mt.get_root().unwrap()));
+
+        assert!(mt.find_proof(&test_util::ION).is_none());
     }
 
     #[test]
@@ -316,7 +428,7 @@ mod tests {
         // N.B.: SHA3_256 lexicographical byte order is: hash(OSMO), hash(WETH).
         let items: Vec<&[u8]> = vec![test_util::OSMO, test_util::WETH];
 
-        let mt = Tree::new(&items);
+        let mt: Tree = Tree::new(&items).unwrap();
 
         let result = mt.find_proof(&test_util::WETH);
 
@@ -327,7 +439,10 @@ mod tests {
         let actual_entry = actual_proof.get_entry_at(0);
 
         assert_eq!(1, actual_proof.get_num_entries());
-        assert_eq!(Entry::new(true, hash::leaf(test_util::OSMO)), *actual_entry);
+        assert_eq!(
+            Entry::new(true, Sha3Hasher::leaf(test_util::OSMO)),
+            *actual_entry
+        );
     }
 
     #[test]
@@ -335,7 +450,7 @@ mod tests {
         // N.B.: SHA3_256 lexicographical byte order is: hash(OSMO), hash(WETH).
         let items: Vec<&[u8]> = vec![test_util::OSMO, test_util::WETH];
 
-        let mt = Tree::new(&items);
+        let mt: Tree = Tree::new(&items).unwrap();
 
         let result = mt.find_proof(&test_util::ION);
 
@@ -367,7 +482,7 @@ mod tests {
             test_util::AKT,
         ];
 
-        let mt = Tree::new(&items);
+        let mt: Tree = Tree::new(&items).unwrap();
 
         let result = mt.find_proof(&test_util::ION);
 
@@ -378,23 +493,29 @@ mod tests {
         assert_eq!(3, actual_proof.get_num_entries());
 
         assert_eq!(
-            Entry::new(false, hash::leaf(test_util::AKT)),
+            Entry::new(false, Sha3Hasher::leaf(test_util::AKT)),
             *actual_proof.get_entry_at(0)
         );
 
         assert_eq!(
             Entry::new(
                 true,
-                hash::branch(&hash::leaf(test_util::OSMO), &hash::leaf(test_util::USDC)) // B_0
+                Sha3Hasher::branch(
+                    &Sha3Hasher::leaf(test_util::OSMO),
+                    &Sha3Hasher::leaf(test_util::USDC)
+                ) // B_0
             ),
             *actual_proof.get_entry_at(1)
         );
 
-        let b_3 = hash::branch(&hash::leaf(test_util::WETH), &hash::leaf(test_util::WETH));
+        let b_3 = Sha3Hasher::branch(
+            &Sha3Hasher::leaf(test_util::WETH),
+            &Sha3Hasher::leaf(test_util::WETH),
+        );
         assert_eq!(
             Entry::new(
                 false,
-                hash::branch(&b_3, &b_3) // B_5
+                Sha3Hasher::branch(&b_3, &b_3) // B_5
             ),
             *actual_proof.get_entry_at(2)
         );
@@ -413,7 +534,7 @@ mod tests {
             test_util::AKT,
         ];
 
-        let mt = Tree::new(&items);
+        let mt: Tree = Tree::new(&items).unwrap();
 
         let result = mt.find_proof(&test_util::WETH);
 
@@ -424,14 +545,17 @@ mod tests {
         assert_eq!(3, actual_proof.get_num_entries());
 
         assert_eq!(
-            Entry::new(false, hash::leaf(test_util::WETH)),
+            Entry::new(false, Sha3Hasher::leaf(test_util::WETH)),
             *actual_proof.get_entry_at(0)
         );
 
         assert_eq!(
             Entry::new(
                 false,
-                hash::branch(&hash::leaf(test_util::WETH), &hash::leaf(test_util::WETH)) // B_0
+                Sha3Hasher::branch(
+                    &Sha3Hasher::leaf(test_util::WETH),
+                    &Sha3Hasher::leaf(test_util::WETH)
+                ) // B_0
             ),
             *actual_proof.get_entry_at(1)
         );
@@ -439,19 +563,241 @@ mod tests {
         assert_eq!(
             Entry::new(
                 true,
-                hash::branch(
-                    &hash::branch(&hash::leaf(test_util::OSMO), &hash::leaf(test_util::USDC)),
-                    &hash::branch(&hash::leaf(test_util::ION), &hash::leaf(test_util::AKT))
+                Sha3Hasher::branch(
+                    &Sha3Hasher::branch(
+                        &Sha3Hasher::leaf(test_util::OSMO),
+                        &Sha3Hasher::leaf(test_util::USDC)
+                    ),
+                    &Sha3Hasher::branch(
+                        &Sha3Hasher::leaf(test_util::ION),
+                        &Sha3Hasher::leaf(test_util::AKT)
+                    )
                 ) // B_4
             ),
             *actual_proof.get_entry_at(2)
         );
     }
+
+    #[test]
+    fn find_proof_five_items_v2_last_node_promoted() {
+        // same tree as above, with WETH promoted up to the last level.
+        let items: Vec<&[u8]> = vec![
+            test_util::OSMO,
+            test_util::ION,
+            test_util::WETH,
+            test_util::USDC,
+            test_util::AKT,
+        ];
+
+        let mt: Tree = Tree::with_version(&items, TreeVersion::V2).unwrap();
+
+        let actual_proof = mt.find_proof(&test_util::WETH).unwrap();
+
+        assert_eq!(1, actual_proof.get_num_entries());
+        assert_eq!(
+            Entry::new(
+                true,
+                Sha3Hasher::branch(
+                    &Sha3Hasher::branch(
+                        &Sha3Hasher::leaf(test_util::OSMO),
+                        &Sha3Hasher::leaf(test_util::USDC)
+                    ),
+                    &Sha3Hasher::branch(
+                        &Sha3Hasher::leaf(test_util::ION),
+                        &Sha3Hasher::leaf(test_util::AKT)
+                    )
+                )
+            ),
+            *actual_proof.get_entry_at(0)
+        );
+        assert_eq!(
+            mt.get_root(),
+            Some(Sha3Hasher::branch(
+                &actual_proof.get_entry_at(0).hash,
+                &Sha3Hasher::leaf(test_util::WETH)
+            ))
+        );
+    }
+
+    #[test]
+    fn v1_duplicated_last_leaf_shares_root() {
+        let items: Vec<&[u8]> = vec![test_util::OSMO, test_util::ION, test_util::WETH];
+        let mut duplicated = items.clone();
+        duplicated.push(last_leaf(&items));
+
+        assert_eq!(
+            root_of(&items, TreeVersion::V1),
+            root_of(&duplicated, TreeVersion::V1)
+        );
+        assert_ne!(
+            root_of(&items, TreeVersion::V2),
+            root_of(&duplicated, TreeVersion::V2)
+        );
+
+        // trees reject the duplicated list in the first place.
+        Tree::<Sha3Hasher>::new(&duplicated).unwrap_err();
+    }
+
+    #[test]
+    fn duplicate_items_rejected_or_deduplicated() {
+        let items: Vec<&[u8]> = vec![test_util::OSMO, test_util::ION, test_util::OSMO];
+        let collisions = vec![Collision {
+            item: test_util::OSMO.to_vec(),
+            indices: vec![0, 2],
+        }];
+
+        let err = Tree::<Sha3Hasher>::new(&items).unwrap_err();
+        assert_eq!(
+            TreeError::DuplicateLeaf {
+                collisions: collisions.clone()
+            },
+            err
+        );
+
+        let mt: Tree = Tree::with_options(
+            &items,
+            TreeOptions {
+                duplicates: DuplicatePolicy::Dedupe,
+                ..TreeOptions::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(collisions, mt.duplicates());
+        assert_eq!(
+            Tree::<Sha3Hasher>::new(&items[..2]).unwrap().get_root(),
+            mt.get_root()
+        );
+
+        let err = Tree::<Sha3Hasher>::with_options(
+            &items,
+            TreeOptions {
+                duplicates: DuplicatePolicy::Merge,
+                ..TreeOptions::default()
+            },
+        )
+        .unwrap_err();
+        assert_eq!(TreeError::MergeRequiresEntries, err);
+    }
+
+    #[test]
+    fn entries_merged_by_address() {
+        let entries = |entries: &[(&str, &str)]| -> Vec<(String, String)> {
+            entries
+                .iter()
+                .map(|(address, amount)| (String::from(*address), String::from(*amount)))
+                .collect()
+        };
+        let given = entries(&[
+            ("osmo1a", "7uosmo"),
+            ("osmo1b", "10uosmo"),
+            ("osmo1a", "29114uosmo"),
+        ]);
+
+        // entries of the same address are distinct leaves unless merged.
+        let mt: Tree = Tree::from_entries(&given, TreeOptions::default()).unwrap();
+        assert!(mt.duplicates().is_empty());
+        assert!(mt.find_proof(&"osmo1a7uosmo").is_some());
+
+        let mt: Tree = Tree::from_entries(
+            &given,
+            TreeOptions {
+                duplicates: DuplicatePolicy::Merge,
+                ..TreeOptions::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            vec![Collision {
+                item: b"osmo1a".to_vec(),
+                indices: vec![0, 2],
+            }],
+            mt.duplicates()
+        );
+        assert_eq!(
+            Tree::<Sha3Hasher>::new(&["osmo1a29121uosmo", "osmo1b10uosmo"])
+                .unwrap()
+                .get_root(),
+            mt.get_root()
+        );
+        assert!(mt.find_proof(&"osmo1a7uosmo").is_none());
+    }
+
+    #[test]
+    fn v2_distinct_leaf_sets_distinct_roots() {
+        let alphabet = [
+            test_util::OSMO,
+            test_util::ION,
+            test_util::WETH,
+            test_util::USDC,
+        ];
+
+        // every list of up to 6 leaves from the alphabet, duplicates
+        // included. Lists are compared sorted, as trees sort their leaves.
+        let mut lists: Vec<Vec<&[u8]>> = vec![vec![]];
+        let mut roots: BTreeMap<hash::Hash, Vec<hash::Hash>> = BTreeMap::new();
+        for _ in 0..6 {
+            let mut next = vec![];
+            for list in &lists {
+                for item in alphabet {
+                    let mut list = list.clone();
+                    list.push(item);
+                    next.push(list);
+                }
+            }
+
+            for list in &next {
+                let mut leaves: Vec<hash::Hash> =
+                    list.iter().map(|item| Sha3Hasher::leaf(item)).collect();
+                test_util::sort(&mut leaves);
+
+                let previous = roots
+                    .entry(root_of(list, TreeVersion::V2))
+                    .or_insert(leaves.clone());
+                assert_eq!(*previous, leaves, "root shared by distinct leaf sets");
+            }
+            lists = next;
+        }
+    }
+
+    #[test]
+    fn v2_every_leaf_verifies() {
+        let items: Vec<String> = (0..17).map(|i| format!("osmo1claimer{}", i)).collect();
+
+        for count in 2..=items.len() {
+            let mt: Tree = Tree::with_version(&items[..count], TreeVersion::V2).unwrap();
+            let root = mt.get_root().unwrap();
+
+            for item in &items[..count] {
+                let proof = mt.find_proof(item).unwrap();
+                assert!(
+                    proof.verify::<Sha3Hasher, _>(item, &root),
+                    "{} of {}",
+                    item,
+                    count
+                );
+            }
+        }
+    }
+
+    // root_of builds the levels of a tree from items, duplicates included.
+    fn root_of(items: &[&[u8]], version: TreeVersion) -> hash::Hash {
+        let mut nodes = builder::build_leaf_level::<Sha3Hasher, _>(items);
+        builder::build_branch_levels::<Sha3Hasher>(&mut nodes, version);
+        nodes[nodes.len() - 1]
+    }
+
+    // last_leaf returns the item whose leaf is sorted last.
+    fn last_leaf<'a>(items: &[&'a [u8]]) -> &'a [u8] {
+        let mut items = items.to_vec();
+        test_util::hash_and_sort(&mut items);
+        items[items.len() - 1]
+    }
 }
 
 #[cfg(test)]
 pub mod test_util {
     use super::*;
+    use crate::hash::Sha3Hasher;
 
     // Hashes to: {155, 130, 51, 5, 37, 74, 205, 223, ...}
     pub const OSMO: &[u8] = b"osmo";
@@ -466,7 +812,7 @@ pub mod test_util {
 
     pub fn hash_and_sort(items: &mut Vec<&[u8]>) {
         // We expect the constructor to sort the nodes by hash.
-        pdqsort::sort_by(items, |a, b| hash::leaf(a).cmp(&hash::leaf(b)));
+        pdqsort::sort_by(items, |a, b| Sha3Hasher::leaf(a).cmp(&Sha3Hasher::leaf(b)));
     }
 
     pub fn sort(items: &mut Vec<hash::Hash>) {
@@ -1,4 +1,4 @@
-use crate::hash;
+use crate::hash::{self, MerkleHasher};
 use serde::{Deserialize, Serialize};
 
 #[derive(Eq, PartialEq, Debug, Serialize, Deserialize)]
@@ -16,9 +16,21 @@ impl Entry {
     }
 }
 
+// MAX_COMPACT_DEPTH is the number of entries a path bitmask can describe.
+const MAX_COMPACT_DEPTH: usize = u64::BITS as usize;
+
 #[derive(Default, Serialize, Deserialize)]
 pub struct Proof(Vec<Entry>);
 
+// CompactProof is the proof without its JSON overhead: the raw sibling hashes
+// from the leaf up, and a path bitmask whose i-th bit is set when the i-th
+// sibling is on the left.
+#[derive(Eq, PartialEq, Debug)]
+pub struct CompactProof {
+    pub hashes: Vec<hash::Hash>,
+    pub path: u64,
+}
+
 impl Proof {
     pub fn push(&mut self, is_left_sibling: bool, hash: hash::Hash) {
         self.0.push(Entry {
@@ -27,15 +39,17 @@ impl Proof {
         })
     }
 
-    pub fn verify<T: AsRef<[u8]>>(&self, data: &T, root: &hash::Hash) -> bool {
-        let initial_hash: hash::Hash = hash::leaf(data.as_ref());
+    // verify checks that data is a leaf of the tree with the given root, built
+    // with hasher H.
+    pub fn verify<H: MerkleHasher, T: AsRef<[u8]>>(&self, data: &T, root: &hash::Hash) -> bool {
+        let initial_hash: hash::Hash = H::leaf(data.as_ref());
 
         let result = self.0.iter().try_fold(initial_hash, |cur_hash, entry| {
             let is_entry_left: bool = entry.is_left_sibling;
             if is_entry_left {
-                Some(hash::branch(&entry.hash, &cur_hash))
+                Some(H::branch(&entry.hash, &cur_hash))
             } else {
-                Some(hash::branch(&cur_hash, &entry.hash))
+                Some(H::branch(&cur_hash, &entry.hash))
             }
         });
 
@@ -46,6 +60,44 @@ impl Proof {
         result.unwrap().eq(root)
     }
 
+    // from_compact rebuilds a proof from raw 32-byte sibling hashes and their
+    // path bitmask. Returns None if a hash is not 32 bytes long, or if the
+    // path does not fit the number of hashes.
+    pub fn from_compact<H: AsRef<[u8]>>(hashes: &[H], path: u64) -> Option<Self> {
+        if hashes.len() > MAX_COMPACT_DEPTH
+            || (hashes.len() < MAX_COMPACT_DEPTH && path >> hashes.len() != 0)
+        {
+            return None;
+        }
+
+        let mut proof = Proof::default();
+        for (i, sibling) in hashes.iter().enumerate() {
+            let sibling = hash::Hash::try_from(sibling.as_ref()).ok()?;
+            proof.push(path & (1 << i) != 0, sibling);
+        }
+        Some(proof)
+    }
+
+    // to_compact returns the proof in its compact form. None if it is deeper
+    // than a path bitmask can describe.
+    pub fn to_compact(&self) -> Option<CompactProof> {
+        if self.0.len() > MAX_COMPACT_DEPTH {
+            return None;
+        }
+
+        let path = self
+            .0
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| entry.is_left_sibling)
+            .fold(0, |path, (i, _)| path | 1 << i);
+
+        Some(CompactProof {
+            hashes: self.0.iter().map(|entry| entry.hash).collect(),
+            path,
+        })
+    }
+
     pub fn get_entry_at(&self, index: u128) -> &Entry {
         &self.0[index as usize]
     }
@@ -58,6 +110,7 @@ impl Proof {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::hash::{Keccak256Hasher, Sha256Hasher, Sha3Hasher};
     use crate::test_util;
     use crate::Tree;
 
@@ -71,22 +124,87 @@ mod tests {
             test_util::AKT,
         ];
 
-        let mt = Tree::new(&items);
+        let mt: Tree = Tree::new(&items).unwrap();
 
         let proof = mt.find_proof(&test_util::USDC).unwrap();
 
         let tree_root = &mt.get_root().unwrap();
 
         // successfuly verify node's proof.
-        assert_eq!(true, proof.verify(&test_util::USDC, tree_root));
+        assert_eq!(
+            true,
+            proof.verify::<Sha3Hasher, _>(&test_util::USDC, tree_root)
+        );
 
         // fail to verify other node in tree.
-        assert_eq!(false, proof.verify(&test_util::OSMO, tree_root));
+        assert_eq!(
+            false,
+            proof.verify::<Sha3Hasher, _>(&test_util::OSMO, tree_root)
+        );
 
         // fail to verify invalid root.
         assert_eq!(
             false,
-            proof.verify(&test_util::USDC, &hash::leaf(test_util::USDC))
+            proof.verify::<Sha3Hasher, _>(&test_util::USDC, &Sha3Hasher::leaf(test_util::USDC))
         );
     }
+
+    #[test]
+    fn verify_with_tree_hasher() {
+        let items: Vec<&[u8]> = vec![test_util::OSMO, test_util::ION, test_util::WETH];
+
+        let sha256_tree: Tree<Sha256Hasher> = Tree::new(&items).unwrap();
+        let keccak_tree: Tree<Keccak256Hasher> = Tree::new(&items).unwrap();
+
+        let sha256_root = &sha256_tree.get_root().unwrap();
+        let keccak_root = &keccak_tree.get_root().unwrap();
+        assert_ne!(sha256_root, keccak_root);
+
+        let proof = sha256_tree.find_proof(&test_util::ION).unwrap();
+        assert!(proof.verify::<Sha256Hasher, _>(&test_util::ION, sha256_root));
+        assert!(!proof.verify::<Keccak256Hasher, _>(&test_util::ION, sha256_root));
+
+        let proof = keccak_tree.find_proof(&test_util::ION).unwrap();
+        assert!(proof.verify::<Keccak256Hasher, _>(&test_util::ION, keccak_root));
+        assert!(!proof.verify::<Sha3Hasher, _>(&test_util::ION, keccak_root));
+    }
+
+    #[test]
+    fn compact_round_trip() {
+        let items: Vec<&[u8]> = vec![
+            test_util::OSMO,
+            test_util::ION,
+            test_util::WETH,
+            test_util::USDC,
+            test_util::AKT,
+        ];
+
+        let mt: Tree = Tree::new(&items).unwrap();
+        let tree_root = &mt.get_root().unwrap();
+
+        for item in items {
+            let proof = mt.find_proof(&item).unwrap();
+
+            let compact = proof.to_compact().unwrap();
+            assert_eq!(proof.get_num_entries(), compact.hashes.len() as u128);
+
+            let rebuilt = Proof::from_compact(&compact.hashes, compact.path).unwrap();
+            assert_eq!(proof.0, rebuilt.0);
+            assert!(rebuilt.verify::<Sha3Hasher, _>(&item, tree_root));
+        }
+    }
+
+    #[test]
+    fn from_compact_invalid() {
+        let sibling = Sha3Hasher::leaf(test_util::OSMO);
+
+        // hashes must be 32 bytes long.
+        assert!(Proof::from_compact(&[&sibling.as_ref()[..31]], 0).is_none());
+
+        // the path cannot point past the last hash.
+        assert!(Proof::from_compact(&[sibling], 0b10).is_none());
+
+        assert!(Proof::from_compact(&[sibling; MAX_COMPACT_DEPTH + 1], 0).is_none());
+        assert!(Proof::from_compact(&[sibling; MAX_COMPACT_DEPTH], u64::MAX).is_some());
+    }
 }
@@ -0,0 +1,383 @@
+use std::fmt;
+
+use serde::{de, de::Visitor, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::duplicates::{self, Collision, DuplicatePolicy};
+use crate::error::TreeError;
+use crate::hash::{Hash, MerkleHasher, SortedKeccak256Hasher};
+
+// LeafValue is a value of a StandardMerkleTree leaf, ABI encoded as the
+// Solidity type it is named after.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeafValue<'a> {
+    Address([u8; 20]),
+    String(&'a str),
+    Uint256(u128),
+}
+
+// encode_leaf ABI encodes values like abi.encode, into the data
+// StandardMerkleTree hashes into a leaf.
+pub fn encode_leaf(values: &[LeafValue]) -> Vec<u8> {
+    let mut head = Vec::with_capacity(32 * values.len());
+    let mut tail = Vec::new();
+
+    for value in values {
+        match value {
+            LeafValue::Address(address) => head.extend(word(address)),
+            LeafValue::Uint256(value) => head.extend(word(&value.to_be_bytes())),
+            LeafValue::String(value) => {
+                // strings are appended after the static values, and referred
+                // to by their offset from the start of the encoding.
+                let offset = 32 * values.len() + tail.len();
+                head.extend(word(&(offset as u64).to_be_bytes()));
+                tail.extend(word(&(value.len() as u64).to_be_bytes()));
+                tail.extend(value.as_bytes());
+                tail.resize(tail.len() + (32 - value.len() % 32) % 32, 0);
+            }
+        }
+    }
+
+    head.extend(tail);
+    head
+}
+
+// claim_leaf encodes the address and amount of a claim as a (string, uint256)
+// StandardMerkleTree leaf, the leaves claims are verified against.
+pub fn claim_leaf(address: &str, amount: u128) -> Vec<u8> {
+    encode_leaf(&[LeafValue::String(address), LeafValue::Uint256(amount)])
+}
+
+// word left pads a big endian value to an ABI word.
+fn word(value: &[u8]) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[32 - value.len()..].copy_from_slice(value);
+    word
+}
+
+// SortedPairTree is a merkle tree laid out like OpenZeppelin's
+// StandardMerkleTree, so that both produce the same root for the same leaf
+// values once encoded with encode_leaf. Leaves are hashed with
+// SortedKeccak256Hasher and sorted, then stored in reverse order at the end
+// of a complete binary tree whose nodes are kept in heap order, root first.
+#[derive(Debug)]
+pub struct SortedPairTree {
+    nodes: Vec<Hash>,
+    duplicates: Vec<Collision>,
+}
+
+impl SortedPairTree {
+    // new builds a tree of items, rejecting items given more than once.
+    pub fn new<T: AsRef<[u8]>>(items: &[T]) -> Result<Self, TreeError> {
+        Self::with_duplicates(items, DuplicatePolicy::default())
+    }
+
+    pub fn with_duplicates<T: AsRef<[u8]>>(
+        items: &[T],
+        policy: DuplicatePolicy,
+    ) -> Result<Self, TreeError> {
+        let duplicates = duplicates::apply_policy(items, policy)?;
+
+        let mut leaves: Vec<Hash> = items
+            .iter()
+            .map(|item| SortedKeccak256Hasher::leaf(item.as_ref()))
+            .collect();
+
+        if leaves.is_empty() {
+            return Ok(SortedPairTree {
+                nodes: leaves,
+                duplicates,
+            });
+        }
+
+        pdqsort::sort_by(&mut leaves, |a, b| a.cmp(b));
+        leaves.dedup();
+
+        let node_count = 2 * leaves.len() - 1;
+        let first_leaf = node_count - leaves.len();
+
+        let mut nodes = vec![Hash::default(); node_count];
+        for (i, leaf) in leaves.into_iter().enumerate() {
+            nodes[node_count - 1 - i] = leaf;
+        }
+        for i in (0..first_leaf).rev() {
+            nodes[i] = SortedKeccak256Hasher::branch(&nodes[2 * i + 1], &nodes[2 * i + 2]);
+        }
+
+        Ok(SortedPairTree { nodes, duplicates })
+    }
+
+    // duplicates returns the items that were given more than once and
+    // deduplicated.
+    pub fn duplicates(&self) -> &[Collision] {
+        &self.duplicates
+    }
+
+    pub fn get_root(&self) -> Option<Hash> {
+        self.nodes.first().copied()
+    }
+
+    // get_root_hex returns the root 0x-prefixed hex encoded, as
+    // StandardMerkleTree prints it.
+    pub fn get_root_hex(&self) -> Option<String> {
+        self.get_root()
+            .map(|root| format!("0x{}", hex::encode(root)))
+    }
+
+    // find_proof returns the sibling hashes from the item's leaf up to the
+    // root. The proof of a single leaf tree is empty.
+    pub fn find_proof<T: AsRef<[u8]>>(&self, item: &T) -> Option<SortedPairProof> {
+        let leaf = SortedKeccak256Hasher::leaf(item.as_ref());
+
+        // leaves are stored in descending order.
+        let first_leaf = self.nodes.len() / 2;
+        let mut index = first_leaf
+            + self.nodes[first_leaf..]
+                .binary_search_by(|probe| leaf.cmp(probe))
+                .ok()?;
+
+        let mut proof = Vec::new();
+        while index > 0 {
+            let sibling = if index % 2 == 1 { index + 1 } else { index - 1 };
+            proof.push(self.nodes[sibling]);
+            index = (index - 1) / 2;
+        }
+
+        Some(SortedPairProof(proof))
+    }
+}
+
+// SortedPairProof is a proof of a SortedPairTree leaf: the plain list of
+// sibling hashes accepted by OpenZeppelin's MerkleProof.verify. It serializes
+// as a list of 0x-prefixed hex strings, like StandardMerkleTree proofs.
+#[derive(Eq, PartialEq, Debug, Default)]
+pub struct SortedPairProof(Vec<Hash>);
+
+impl SortedPairProof {
+    pub fn verify<T: AsRef<[u8]>>(&self, data: &T, root: &Hash) -> bool {
+        let leaf = SortedKeccak256Hasher::leaf(data.as_ref());
+
+        let result = self.0.iter().fold(leaf, |cur_hash, sibling| {
+            SortedKeccak256Hasher::branch(&cur_hash, sibling)
+        });
+
+        result.eq(root)
+    }
+
+    pub fn hashes(&self) -> &[Hash] {
+        &self.0
+    }
+}
+
+impl From<Vec<Hash>> for SortedPairProof {
+    fn from(hashes: Vec<Hash>) -> Self {
+        SortedPairProof(hashes)
+    }
+}
+
+impl Serialize for SortedPairProof {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_seq(self.0.iter().map(|hash| format!("0x{}", hex::encode(hash))))
+    }
+}
+
+struct SortedPairProofVisitor;
+
+impl<'de> Visitor<'de> for SortedPairProofVisitor {
+    type Value = SortedPairProof;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a sequence of 0x-prefixed hex encoded hashes")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        let mut hashes = Vec::new();
+        while let Some(value) = seq.next_element::<String>()? {
+            let bytes = hex::decode(value.trim_start_matches("0x")).map_err(de::Error::custom)?;
+            let hash = Hash::try_from(bytes.as_slice()).map_err(de::Error::custom)?;
+            hashes.push(hash);
+        }
+        Ok(SortedPairProof(hashes))
+    }
+}
+
+impl<'de> Deserialize<'de> for SortedPairProof {
+    fn deserialize<D>(deserializer: D) -> Result<SortedPairProof, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(SortedPairProofVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util;
+
+    type H = SortedKeccak256Hasher;
+
+    fn hash(hex: &str) -> Hash {
+        Hash::try_from(hex::decode(hex.trim_start_matches("0x")).unwrap().as_slice()).unwrap()
+    }
+
+    #[test]
+    fn matches_standard_merkle_tree_vector() {
+        // the example of OpenZeppelin's merkle-tree library:
+        // StandardMerkleTree.of(values, ["address", "uint256"]).
+        let values = [
+            ("1111111111111111111111111111111111111111", 5000000000000000000),
+            ("2222222222222222222222222222222222222222", 2500000000000000000),
+        ];
+        let leaves: Vec<Vec<u8>> = values
+            .iter()
+            .map(|(address, amount)| {
+                let address = hex::decode(address).unwrap().try_into().unwrap();
+                encode_leaf(&[LeafValue::Address(address), LeafValue::Uint256(*amount)])
+            })
+            .collect();
+
+        let mt = SortedPairTree::new(&leaves).unwrap();
+        assert_eq!(
+            Some(String::from(
+                "0xd4dee0beab2d53f2cc83e567171bd2820e49898130a22622b10ead383e90bd77"
+            )),
+            mt.get_root_hex()
+        );
+
+        let proof = mt.find_proof(&leaves[0]).unwrap();
+        assert_eq!(
+            vec![hash(
+                "0xb92c48e9d7abe27fd8dfd6b5dfdbfb1c9a463f80c712b66f3a5180a090cccafc"
+            )],
+            proof.0
+        );
+    }
+
+    #[test]
+    fn claim_leaf_abi_encoding() {
+        // abi.encode("osmo1claimer", 10): the string's offset, the amount, then
+        // the string's length and its bytes padded to a word.
+        let expected = [
+            format!("{:064x}", 64),
+            format!("{:064x}", 10),
+            format!("{:064x}", 12),
+            format!("{:0<64}", hex::encode("osmo1claimer")),
+        ]
+        .concat();
+
+        assert_eq!(expected, hex::encode(claim_leaf("osmo1claimer", 10)));
+
+        // strings of a whole number of words are not padded further.
+        let address = "o".repeat(32);
+        assert_eq!(4 * 32, claim_leaf(&address, 10).len());
+    }
+
+    #[test]
+    fn matches_standard_merkle_tree_layout() {
+        let items: Vec<&[u8]> = vec![
+            test_util::OSMO,
+            test_util::ION,
+            test_util::WETH,
+            test_util::USDC,
+            test_util::AKT,
+        ];
+        let mut leaves: Vec<Hash> = items.iter().map(|item| H::leaf(item)).collect();
+        leaves.sort();
+
+        // 2n - 1 nodes: the first two leaves hang off the deepest level,
+        // pairs are formed from the last leaves backwards.
+        //
+        //                  root
+        //          n1               n2
+        //      n3      l4       l3      l2
+        //    l1  l0
+        let n3 = H::branch(&leaves[1], &leaves[0]);
+        let n1 = H::branch(&n3, &leaves[4]);
+        let n2 = H::branch(&leaves[3], &leaves[2]);
+        let root = H::branch(&n1, &n2);
+
+        let mt = SortedPairTree::new(&items).unwrap();
+        assert_eq!(Some(root), mt.get_root());
+
+        let proof = mt.find_proof(&leaf_item(&items, &leaves[0])).unwrap();
+        assert_eq!(vec![leaves[1], leaves[4], n2], proof.0);
+
+        let proof = mt.find_proof(&leaf_item(&items, &leaves[3])).unwrap();
+        assert_eq!(vec![leaves[2], n1], proof.0);
+    }
+
+    #[test]
+    fn every_leaf_verifies() {
+        let items: Vec<String> = (0..17).map(|i| format!("osmo1claimer{}", i)).collect();
+
+        for count in 1..=items.len() {
+            let mt = SortedPairTree::new(&items[..count]).unwrap();
+            let root = mt.get_root().unwrap();
+
+            for item in &items[..count] {
+                let proof = mt.find_proof(item).unwrap();
+                assert!(proof.verify(item, &root), "{} of {}", item, count);
+            }
+
+            assert!(mt.find_proof(&"osmo1unknown").is_none());
+        }
+    }
+
+    #[test]
+    fn single_leaf_tree() {
+        let mt = SortedPairTree::new(&[test_util::OSMO]).unwrap();
+
+        assert_eq!(Some(H::leaf(test_util::OSMO)), mt.get_root());
+
+        let proof = mt.find_proof(&test_util::OSMO).unwrap();
+        assert!(proof.0.is_empty());
+        assert!(proof.verify(&test_util::OSMO, &mt.get_root().unwrap()));
+    }
+
+    #[test]
+    fn duplicate_items_rejected_or_deduplicated() {
+        let items: Vec<&[u8]> = vec![test_util::OSMO, test_util::ION, test_util::OSMO];
+
+        let err = SortedPairTree::new(&items).unwrap_err();
+        assert!(matches!(err, TreeError::DuplicateLeaf { .. }), "{:?}", err);
+
+        let mt = SortedPairTree::with_duplicates(&items, DuplicatePolicy::Dedupe).unwrap();
+        assert_eq!(vec![0, 2], mt.duplicates()[0].indices);
+        assert_eq!(
+            SortedPairTree::new(&items[..2]).unwrap().get_root(),
+            mt.get_root()
+        );
+    }
+
+    #[test]
+    fn empty_tree() {
+        let items: Vec<&[u8]> = vec![];
+        let mt = SortedPairTree::new(&items).unwrap();
+
+        assert!(mt.get_root().is_none());
+        assert!(mt.find_proof(&test_util::OSMO).is_none());
+    }
+
+    #[test]
+    fn hex_serialization() {
+        let items: Vec<&[u8]> = vec![test_util::OSMO, test_util::ION, test_util::WETH];
+        let mt = SortedPairTree::new(&items).unwrap();
+        let proof = mt.find_proof(&test_util::ION).unwrap();
+
+        let serialized = serde_json_wasm::to_string(&proof).unwrap();
+        assert!(serialized.starts_with("[\"0x"), "{}", serialized);
+
+        let deserialized: SortedPairProof = serde_json_wasm::from_str(&serialized).unwrap();
+        assert_eq!(proof, deserialized);
+    }
+
+    fn leaf_item<'a>(items: &[&'a [u8]], leaf: &Hash) -> &'a [u8] {
+        items.iter().find(|item| H::leaf(item) == *leaf).unwrap()
+    }
+}